@@ -0,0 +1,626 @@
+//! `pswtf` on the command line: the same process, port and kill code paths as
+//! the desktop app, without the window.
+
+use std::path::Path;
+use std::process::ExitCode;
+use std::time::Duration;
+
+use pswtf_core::container::annotate_docker_ports;
+use pswtf_core::dns::resolve_remote_hosts;
+use pswtf_core::forward::annotate_port_forwards;
+use pswtf_core::fuser::{find_file_users, find_mount_blockers};
+use pswtf_core::kill::{
+    kill_by_tty, kill_matching, kill_multiplexer_session, kill_port, kill_process_group,
+    kill_process_tree, verify_terminated, MatchMode, MatchOptions,
+};
+use pswtf_core::ports::{collect_connections, collect_ports, collect_unix_sockets};
+use pswtf_core::priority::set_priority;
+use pswtf_core::probe::probe_port;
+use pswtf_core::process::{
+    build_snapshot, current_processes, primed_system, take_sample, ProcessFilter, SortKey,
+};
+use pswtf_core::query::parse_query;
+use pswtf_core::wsl::merge_wsl_ports;
+use pswtf_core::{
+    CpuNormalization, KillPrivilege, KillReport, KillRole, PortExposure, PortForward, PortInfo,
+    PortOrigin,
+};
+use serde::Serialize;
+
+const USAGE: &str = "\
+Usage:
+  pswtf ps [--name <filter>] [--query <expr>] [--sort cpu|memory|pid|name] [--limit <n>] [--per-core] [--outdated] [--json]
+  pswtf ports [--port <port>] [--json]
+  pswtf connections [--pid <pid>] [--resolve] [--json]
+  pswtf sockets [--pid <pid>] [--json]
+  pswtf probe <port> [--json]
+  pswtf fuser <path> [--mount] [--json]
+  pswtf renice <pid> <nice>
+  pswtf kill <pid> [--no-children] [--force] [--elevate] [--verify <ms>] [--json]
+  pswtf kill --port <port> [--no-children] [--force] [--elevate] [--verify <ms>] [--json]
+  pswtf kill --group <pgid> [--force] [--verify <ms>] [--json]
+  pswtf kill --session <tmux-or-screen-session>[:<window-index>] [--force] [--elevate] [--verify <ms>] [--json]
+  pswtf kill --tty <tty> [--force] [--elevate] [--verify <ms>] [--json]
+  pswtf kill --match <query> [--mode substring|exact|word|regex|glob] [--case-sensitive] [--exclude <pattern>]... [--all-users] [--include-self] [--force] [--elevate] [--verify <ms>] [--json]";
+
+/// Flags shared by every subcommand, plus whatever positional/valued options
+/// the subcommand itself looks up.
+struct Args {
+    positional: Vec<String>,
+    options: Vec<(String, Option<String>)>,
+}
+
+impl Args {
+    const VALUED: [&'static str; 13] = [
+        "--name",
+        "--query",
+        "--sort",
+        "--limit",
+        "--port",
+        "--pid",
+        "--group",
+        "--session",
+        "--tty",
+        "--match",
+        "--mode",
+        "--exclude",
+        "--verify",
+    ];
+
+    fn parse(raw: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut positional = Vec::new();
+        let mut options = Vec::new();
+        let mut raw = raw.peekable();
+
+        while let Some(arg) = raw.next() {
+            if !arg.starts_with("--") {
+                positional.push(arg);
+                continue;
+            }
+
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+                None => (arg, None),
+            };
+            if Self::VALUED.contains(&flag.as_str()) {
+                let value = inline
+                    .or_else(|| raw.next())
+                    .ok_or_else(|| format!("{flag} needs a value"))?;
+                options.push((flag, Some(value)));
+            } else {
+                options.push((flag, None));
+            }
+        }
+
+        Ok(Self {
+            positional,
+            options,
+        })
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.options.iter().any(|(flag, _)| flag == name)
+    }
+
+    fn value(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(flag, _)| flag == name)
+            .and_then(|(_, value)| value.as_deref())
+    }
+
+    /// Every value of a flag that can be repeated, in order.
+    fn values(&self, name: &str) -> Vec<String> {
+        self.options
+            .iter()
+            .filter(|(flag, _)| flag == name)
+            .filter_map(|(_, value)| value.clone())
+            .collect()
+    }
+
+    fn parsed<T: std::str::FromStr>(&self, name: &str) -> Result<Option<T>, String> {
+        self.value(name)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| format!("Invalid value for {name}: {value}"))
+            })
+            .transpose()
+    }
+}
+
+fn main() -> ExitCode {
+    let mut raw = std::env::args().skip(1);
+    let command = raw.next();
+
+    let result = Args::parse(raw).and_then(|args| match command.as_deref() {
+        Some("ps") => run_ps(&args),
+        Some("ports") => run_ports(&args),
+        Some("connections") => run_connections(&args),
+        Some("sockets") => run_sockets(&args),
+        Some("probe") => run_probe(&args),
+        Some("fuser") => run_fuser(&args),
+        Some("renice") => run_renice(&args),
+        Some("kill") => run_kill(&args),
+        Some("help" | "--help" | "-h") | None => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        Some(other) => Err(format!("Unknown command: {other}\n\n{USAGE}")),
+    });
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("pswtf: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn print_json(value: &impl Serialize) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|error| format!("Failed to encode output: {error}"))?;
+    println!("{json}");
+    Ok(())
+}
+
+fn parse_sort_key(value: &str) -> Result<SortKey, String> {
+    match value {
+        "cpu" => Ok(SortKey::Cpu),
+        "memory" => Ok(SortKey::Memory),
+        "pid" => Ok(SortKey::Pid),
+        "name" => Ok(SortKey::Name),
+        other => Err(format!("Unknown sort key: {other}")),
+    }
+}
+
+fn parse_match_mode(value: &str) -> Result<MatchMode, String> {
+    match value {
+        "substring" => Ok(MatchMode::Substring),
+        "exact" => Ok(MatchMode::Exact),
+        "word" => Ok(MatchMode::Word),
+        "regex" => Ok(MatchMode::Regex),
+        "glob" => Ok(MatchMode::Glob),
+        other => Err(format!("Unknown match mode: {other}")),
+    }
+}
+
+fn run_ps(args: &Args) -> Result<(), String> {
+    let mut sample = take_sample(&mut primed_system());
+    // Applied to the raw sample, so `cpu>` compares undivided percentages
+    // even with --per-core.
+    if let Some(expr) = args.value("--query") {
+        let query = parse_query(expr)?;
+        let ports = if query.uses_ports() {
+            collect_ports()?
+        } else {
+            Vec::new()
+        };
+        sample
+            .processes
+            .retain(|process| query.matches(process, &ports));
+    }
+    let filter = ProcessFilter {
+        name_filter: args.value("--name").map(str::to_string),
+        exe_outdated: args.flag("--outdated").then_some(true),
+        ..ProcessFilter::default()
+    };
+    let sort_by = args.value("--sort").map(parse_sort_key).transpose()?;
+    let snapshot = build_snapshot(
+        &sample,
+        &filter,
+        sort_by,
+        None,
+        args.parsed("--limit")?,
+        None,
+        args.flag("--per-core").then_some(CpuNormalization::PerCore),
+    );
+
+    if args.flag("--json") {
+        return print_json(&snapshot);
+    }
+
+    println!(
+        "{:>7} {:>7} {:>3} {:>6} {:>10}  NAME",
+        "PID", "PPID", "NI", "CPU%", "MEMORY"
+    );
+    for process in &snapshot.processes {
+        println!(
+            "{:>7} {:>7} {:>3} {:>6.1} {:>10}  {}",
+            process.pid,
+            process
+                .parent_pid
+                .map(|pid| pid.to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            process
+                .nice
+                .map(|nice| nice.to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            process.cpu_percent,
+            format_bytes(process.memory_bytes),
+            process.name
+        );
+    }
+    Ok(())
+}
+
+fn run_ports(args: &Args) -> Result<(), String> {
+    let mut ports = collect_ports()?;
+    annotate_docker_ports(&mut ports);
+    annotate_port_forwards(&mut ports);
+    merge_wsl_ports(&mut ports);
+    if let Some(port) = args.parsed::<u16>("--port")? {
+        ports.retain(|entry| entry.port == port);
+    }
+
+    if args.flag("--json") {
+        return print_json(&ports);
+    }
+
+    println!(
+        "{:>5}  {:<14} {:<5} {:<24} {:<8} {:<12} {:>7}  PROCESS",
+        "PORT", "SERVICE", "PROTO", "ADDRESS", "EXPOSURE", "STATE", "PID"
+    );
+    for entry in &ports {
+        print_port(entry);
+    }
+    Ok(())
+}
+
+fn print_port(entry: &PortInfo) {
+    let name = entry.process_name.as_deref().unwrap_or("-");
+    let owner = if let Some(docker) = &entry.docker {
+        format!(
+            "{name} (container {}:{})",
+            docker.container_name, docker.container_port
+        )
+    } else if let Some(forward) = &entry.forward {
+        format!("{name} (-> {})", describe_forward(forward))
+    } else {
+        match entry.origin {
+            PortOrigin::Local => name.to_string(),
+            PortOrigin::WindowsHost => format!("{name} (Windows host)"),
+            PortOrigin::Wsl => format!("{name} (WSL)"),
+        }
+    };
+    println!(
+        "{:>5}  {:<14} {:<5} {:<24} {:<8} {:<12} {:>7}  {}",
+        entry.port,
+        entry.service.as_deref().unwrap_or("-"),
+        entry.protocol,
+        entry.local_address,
+        match entry.exposure {
+            PortExposure::Loopback => "loopback",
+            PortExposure::Lan => "lan",
+            PortExposure::All => "all",
+        },
+        entry.state.as_deref().unwrap_or("-"),
+        entry
+            .pid
+            .map(|pid| pid.to_string())
+            .unwrap_or_else(|| "-".to_string()),
+        owner
+    );
+}
+
+fn run_connections(args: &Args) -> Result<(), String> {
+    let mut connections = collect_connections()?;
+    if let Some(pid) = args.parsed::<i32>("--pid")? {
+        connections.retain(|entry| entry.pid == Some(pid));
+    }
+    if args.flag("--resolve") {
+        resolve_remote_hosts(&mut connections, Duration::from_secs(2));
+    }
+
+    if args.flag("--json") {
+        return print_json(&connections);
+    }
+
+    println!(
+        "{:>7}  {:<12} {:<5} {:<28} {:<28} STATE",
+        "PID", "PROCESS", "PROTO", "LOCAL", "REMOTE"
+    );
+    for entry in &connections {
+        println!(
+            "{:>7}  {:<12} {:<5} {:<28} {:<28} {}",
+            entry
+                .pid
+                .map(|pid| pid.to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            entry.process_name.as_deref().unwrap_or("-"),
+            entry.protocol,
+            format!("{}:{}", entry.local_address, entry.local_port),
+            format!(
+                "{}:{}",
+                entry
+                    .remote_host
+                    .as_deref()
+                    .unwrap_or(&entry.remote_address),
+                entry.remote_port
+            ),
+            entry.state.as_deref().unwrap_or("-")
+        );
+    }
+    Ok(())
+}
+
+fn run_sockets(args: &Args) -> Result<(), String> {
+    let mut sockets = collect_unix_sockets()?;
+    if let Some(pid) = args.parsed::<i32>("--pid")? {
+        sockets.retain(|entry| {
+            entry.pid == Some(pid) || entry.peers.iter().any(|peer| peer.pid == pid)
+        });
+    }
+
+    if args.flag("--json") {
+        return print_json(&sockets);
+    }
+
+    println!(
+        "{:>7}  {:<12} {:<10} {:<10} {:<40} PEERS",
+        "PID", "PROCESS", "TYPE", "STATE", "PATH"
+    );
+    for entry in &sockets {
+        let peers = entry
+            .peers
+            .iter()
+            .map(|peer| format!("{} ({})", peer.process_name, peer.pid))
+            .collect::<Vec<_>>()
+            .join(", ");
+        println!(
+            "{:>7}  {:<12} {:<10} {:<10} {:<40} {}",
+            entry
+                .pid
+                .map(|pid| pid.to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            entry.process_name.as_deref().unwrap_or("-"),
+            entry.socket_type.as_deref().unwrap_or("-"),
+            entry.state.as_deref().unwrap_or("-"),
+            entry.path.as_deref().unwrap_or("-"),
+            if peers.is_empty() { "-" } else { &peers }
+        );
+    }
+    Ok(())
+}
+
+fn run_fuser(args: &Args) -> Result<(), String> {
+    let path = args
+        .positional
+        .first()
+        .ok_or_else(|| format!("fuser needs a path\n\n{USAGE}"))?;
+    let users = if args.flag("--mount") {
+        find_mount_blockers(Path::new(path))?
+    } else {
+        find_file_users(Path::new(path))?
+    };
+
+    if args.flag("--json") {
+        return print_json(&users);
+    }
+
+    println!(
+        "{:>7}  {:<16} {:<10} {:>4}  PATH",
+        "PID", "PROCESS", "USE", "FD"
+    );
+    for user in &users {
+        for file_use in &user.uses {
+            println!(
+                "{:>7}  {:<16} {:<10} {:>4}  {}",
+                user.pid,
+                user.name,
+                format!("{:?}", file_use.kind).to_lowercase(),
+                file_use
+                    .fd
+                    .map(|fd| fd.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+                file_use.path
+            );
+        }
+    }
+    Ok(())
+}
+
+fn run_probe(args: &Args) -> Result<(), String> {
+    let port = args
+        .positional
+        .first()
+        .ok_or_else(|| format!("probe needs a port\n\n{USAGE}"))?;
+    let port = port
+        .parse::<u16>()
+        .map_err(|_| format!("Invalid port: {port}"))?;
+
+    let probe = probe_port(port);
+    if args.flag("--json") {
+        return print_json(&probe);
+    }
+    if !probe.connected {
+        return Err(format!(
+            "Port {port}: {}",
+            probe.error.as_deref().unwrap_or("not reachable")
+        ));
+    }
+
+    println!(
+        "port {port} on {}: {} ({} ms){}",
+        probe.address.as_deref().unwrap_or("-"),
+        probe.protocol.unwrap_or("no response"),
+        probe.connect_ms.unwrap_or_default(),
+        probe
+            .banner
+            .as_deref()
+            .map(|banner| format!(" - {banner}"))
+            .unwrap_or_default()
+    );
+    Ok(())
+}
+
+fn run_renice(args: &Args) -> Result<(), String> {
+    let [pid, nice] = args.positional.as_slice() else {
+        return Err(format!("renice needs a PID and a nice value\n\n{USAGE}"));
+    };
+    let pid = pid
+        .parse::<i32>()
+        .map_err(|_| format!("Invalid PID: {pid}"))?;
+    let nice = nice
+        .parse::<i32>()
+        .map_err(|_| format!("Invalid nice value: {nice}"))?;
+
+    set_priority(pid, nice)?;
+    println!("process {pid} is now at nice {nice}");
+    Ok(())
+}
+
+fn describe_forward(forward: &PortForward) -> String {
+    let mut target = match &forward.namespace {
+        Some(namespace) => format!("{namespace}/{}", forward.target),
+        None => forward.target.clone(),
+    };
+    if forward.tool == "kubectl" {
+        if let Some(port) = forward.remote_port {
+            target.push_str(&format!(":{port}"));
+        }
+    }
+    if let Some(via) = &forward.via {
+        target.push_str(&format!(" via {via}"));
+    }
+    target
+}
+
+fn run_kill(args: &Args) -> Result<(), String> {
+    let include_children = !args.flag("--no-children");
+    let force = Some(args.flag("--force"));
+    let elevate = args.flag("--elevate");
+    let verify_ms = args.parsed::<u64>("--verify")?;
+    let processes = current_processes();
+
+    let mut report = if let Some(port) = args.parsed::<u16>("--port")? {
+        kill_port(
+            &processes,
+            &collect_ports()?,
+            port,
+            include_children,
+            force,
+            elevate,
+        )?
+    } else if let Some(pgid) = args.parsed::<i32>("--group")? {
+        kill_process_group(&processes, pgid, force)?
+    } else if let Some(target) = args.value("--session") {
+        // tmux targets are written `session:window`.
+        let (session, window_index) = match target.rsplit_once(':') {
+            Some((session, window)) => (
+                session,
+                Some(
+                    window
+                        .parse::<u32>()
+                        .map_err(|_| format!("Invalid window index: {window}"))?,
+                ),
+            ),
+            None => (target, None),
+        };
+        kill_multiplexer_session(&processes, session, window_index, force, elevate)?
+    } else if let Some(tty) = args.value("--tty") {
+        kill_by_tty(&processes, tty, force, elevate)?
+    } else if let Some(query) = args.value("--match") {
+        let options = MatchOptions {
+            match_mode: args
+                .value("--mode")
+                .map(parse_match_mode)
+                .transpose()?
+                .unwrap_or_default(),
+            include_children,
+            only_current_user: !args.flag("--all-users"),
+            case_sensitive: args.flag("--case-sensitive"),
+            exclude: args.values("--exclude"),
+            include_self: args.flag("--include-self"),
+        };
+        let report = kill_matching(&processes, query, &options, force, elevate)?;
+        if report.matched == 0 {
+            return Err(format!("No processes match \"{query}\""));
+        }
+        report
+    } else {
+        let pid = args.positional.first().ok_or_else(|| {
+            format!("kill needs a PID, --port, --group, --session, --tty or --match\n\n{USAGE}")
+        })?;
+        let pid = pid
+            .parse::<i32>()
+            .map_err(|_| format!("Invalid PID: {pid}"))?;
+        kill_process_tree(&processes, pid, include_children, force, elevate)?
+    };
+
+    if let Some(verify_ms) = verify_ms {
+        verify_terminated(&mut report, Duration::from_millis(verify_ms));
+    }
+
+    if args.flag("--json") {
+        return print_json(&report);
+    }
+    print_kill_report(&report);
+
+    if !report.still_running.is_empty() {
+        Err(format!(
+            "{} of {} signaled processes are still running",
+            report.still_running.len(),
+            report.killed.len()
+        ))
+    } else if report.failed.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} of {} kills failed",
+            report.failed.len(),
+            report.attempted
+        ))
+    }
+}
+
+fn print_kill_report(report: &KillReport) {
+    println!(
+        "matched={} attempted={} killed={} failed={}{}",
+        report.matched,
+        report.attempted,
+        report.killed.len(),
+        report.failed.len(),
+        if report.privilege == KillPrivilege::Elevated {
+            " (elevated)"
+        } else {
+            ""
+        }
+    );
+    for entry in &report.killed {
+        let role = match entry.role {
+            KillRole::Root => "",
+            KillRole::Descendant => ", child",
+        };
+        println!("  {}: {} ({}{role})", entry.pid, entry.name, entry.signal);
+    }
+    for failure in &report.failed {
+        println!("  {}: {}", failure.pid, failure.error);
+    }
+    for pid in &report.still_running {
+        println!("  {pid}: still running");
+    }
+    for entry in &report.supervised {
+        let hint = match &entry.stop_command {
+            Some(command) => format!("run `{}` instead", command.join(" ")),
+            None => format!("stop {} (PID {}) instead", entry.manager, entry.manager_pid),
+        };
+        println!(
+            "  {}: will be restarted by {}; {hint}",
+            entry.pid, entry.manager
+        );
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
@@ -0,0 +1,127 @@
+//! Clusters processes into the application they belong to, so Chrome's
+//! dozens of helpers read as one Chrome with their combined cost.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::Deserialize;
+
+use crate::model::{AppGroup, ProcessInfo};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GroupBy {
+    App,
+}
+
+/// Bundle IDs by bundle path. Info.plist doesn't change while an app runs,
+/// and the snapshot is regrouped on every refresh.
+static BUNDLE_IDS: Mutex<Option<HashMap<String, Option<String>>>> = Mutex::new(None);
+
+/// Groups `processes` by macOS bundle (the outermost `.app` in the exe path,
+/// which is where nested helper bundles live) or, elsewhere, by the oldest
+/// ancestor running the same executable. Most expensive group first.
+pub fn group_by_app(processes: &[ProcessInfo]) -> Vec<AppGroup> {
+    let by_pid = processes
+        .iter()
+        .map(|process| (process.pid, process))
+        .collect::<HashMap<_, _>>();
+
+    let mut groups = Vec::<AppGroup>::new();
+    let mut index_by_key = HashMap::<String, usize>::new();
+    for process in processes {
+        let bundle = process.exe.as_deref().and_then(bundle_path);
+        let (key, name) = match bundle {
+            Some(bundle) => (format!("bundle:{bundle}"), bundle_name(bundle).to_string()),
+            None => {
+                let root = same_exe_root(process, &by_pid);
+                (format!("pid:{}", root.pid), root.name.clone())
+            }
+        };
+
+        let index = *index_by_key.entry(key.clone()).or_insert_with(|| {
+            groups.push(AppGroup {
+                key,
+                name,
+                bundle_id: bundle.and_then(cached_bundle_id),
+                bundle_path: bundle.map(str::to_string),
+                pids: Vec::new(),
+                cpu_percent: 0.0,
+                memory_bytes: 0,
+            });
+            groups.len() - 1
+        });
+        let group = &mut groups[index];
+        group.pids.push(process.pid);
+        group.cpu_percent += process.cpu_percent;
+        group.memory_bytes = group.memory_bytes.saturating_add(process.memory_bytes);
+    }
+
+    groups.sort_by(|a, b| {
+        b.cpu_percent
+            .partial_cmp(&a.cpu_percent)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| b.memory_bytes.cmp(&a.memory_bytes))
+            .then_with(|| a.key.cmp(&b.key))
+    });
+    for group in &mut groups {
+        group.pids.sort_unstable();
+    }
+    groups
+}
+
+/// `/Applications/Google Chrome.app` out of
+/// `/Applications/Google Chrome.app/Contents/Frameworks/.../Google Chrome Helper.app/...`.
+pub fn bundle_path(exe: &str) -> Option<&str> {
+    let end = exe.find(".app/")? + ".app".len();
+    Some(&exe[..end])
+}
+
+fn bundle_name(bundle: &str) -> &str {
+    let file = bundle.rsplit('/').next().unwrap_or(bundle);
+    file.strip_suffix(".app").unwrap_or(file)
+}
+
+/// Walks up while the parent runs the same binary, the way Electron, Chrome
+/// and Firefox on Linux fork their renderers and zygotes.
+fn same_exe_root<'a>(
+    process: &'a ProcessInfo,
+    by_pid: &HashMap<i32, &'a ProcessInfo>,
+) -> &'a ProcessInfo {
+    let Some(exe) = process.exe.as_deref() else {
+        return process;
+    };
+
+    let mut root = process;
+    let mut depth = 0;
+    while let Some(parent) = root.parent_pid.and_then(|pid| by_pid.get(&pid)) {
+        if parent.exe.as_deref() != Some(exe) || depth > 64 {
+            break;
+        }
+        root = parent;
+        depth += 1;
+    }
+    root
+}
+
+fn cached_bundle_id(bundle: &str) -> Option<String> {
+    BUNDLE_IDS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get_or_insert_with(HashMap::new)
+        .entry(bundle.to_string())
+        .or_insert_with(|| {
+            let plist = std::fs::read(format!("{bundle}/Contents/Info.plist")).ok()?;
+            plist_bundle_id(&String::from_utf8_lossy(&plist))
+        })
+        .clone()
+}
+
+/// `CFBundleIdentifier` from an XML Info.plist. Binary plists (what Xcode
+/// writes by default for Apple's own apps) come back as `None`.
+pub fn plist_bundle_id(plist: &str) -> Option<String> {
+    let (_, rest) = plist.split_once("<key>CFBundleIdentifier</key>")?;
+    let start = rest.find("<string>")? + "<string>".len();
+    let end = rest[start..].find("</string>")? + start;
+    Some(rest[start..end].trim().to_string())
+}
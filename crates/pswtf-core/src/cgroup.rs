@@ -0,0 +1,179 @@
+//! Linux cgroup membership and the resource limits that come with it, read
+//! straight from cgroupfs. Limits are hierarchical, so a process is held to
+//! the tightest limit anywhere between its own cgroup and the root.
+
+use std::path::{Path, PathBuf};
+
+use crate::model::CgroupInfo;
+
+/// cgroup v1 writes "unlimited" as the largest page-aligned `i64`; anything
+/// this big is treated as no limit.
+const V1_UNLIMITED: u64 = 1 << 62;
+
+#[cfg(target_os = "linux")]
+pub fn collect_cgroup(pid: i32) -> Option<CgroupInfo> {
+    let membership = std::fs::read_to_string(format!("/proc/{pid}/cgroup")).ok()?;
+    cgroup_limits(&membership, Path::new("/sys/fs/cgroup"))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn collect_cgroup(_pid: i32) -> Option<CgroupInfo> {
+    None
+}
+
+/// Resolves a `/proc/<pid>/cgroup` file against a cgroupfs mounted at
+/// `root`. Uses the unified v2 hierarchy when it's mounted there, otherwise
+/// the v1 `memory`, `cpu` and `pids` controllers.
+pub fn cgroup_limits(membership: &str, root: &Path) -> Option<CgroupInfo> {
+    let entries = membership
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, ':');
+            let _hierarchy = fields.next()?;
+            Some((fields.next()?, fields.next()?))
+        })
+        .collect::<Vec<_>>();
+    let controller_path = |controller: &str| {
+        entries
+            .iter()
+            .find(|(controllers, _)| controllers.split(',').any(|name| name == controller))
+            .map(|(_, path)| *path)
+    };
+
+    let unified = entries
+        .iter()
+        .find(|(controllers, _)| controllers.is_empty())
+        .map(|(_, path)| *path);
+    if let Some(path) = unified.filter(|_| root.join("cgroup.controllers").exists()) {
+        return Some(v2_limits(root, path));
+    }
+
+    let path = controller_path("memory")
+        .or_else(|| controller_path("cpu"))
+        .or(unified)?;
+    Some(v1_limits(root, path, controller_path))
+}
+
+fn v2_limits(root: &Path, path: &str) -> CgroupInfo {
+    let own = cgroup_dir(root, path);
+    let read = |file: &str| read_trimmed(&own.join(file));
+
+    CgroupInfo {
+        path: path.to_string(),
+        version: 2,
+        memory_limit_bytes: tightest(root, path, |dir| {
+            read_trimmed(&dir.join("memory.max")).and_then(|text| text.parse().ok())
+        }),
+        memory_usage_bytes: read("memory.current").and_then(|text| text.parse().ok()),
+        cpu_limit_cores: tightest(root, path, |dir| {
+            read_trimmed(&dir.join("cpu.max")).and_then(|text| parse_cpu_max(&text))
+        }),
+        pids_limit: tightest(root, path, |dir| {
+            read_trimmed(&dir.join("pids.max")).and_then(|text| text.parse().ok())
+        }),
+        oom_kills: read("memory.events").and_then(|text| keyed_value(&text, "oom_kill")),
+        throttled_periods: read("cpu.stat").and_then(|text| keyed_value(&text, "nr_throttled")),
+    }
+}
+
+fn v1_limits<'a>(
+    root: &Path,
+    path: &str,
+    controller_path: impl Fn(&str) -> Option<&'a str>,
+) -> CgroupInfo {
+    // Each v1 controller is its own hierarchy, mounted by name (`cpu` is
+    // usually a symlink to the combined `cpu,cpuacct`).
+    let hierarchy = |controller: &str| {
+        controller_path(controller).map(|path| (root.join(controller), path.to_string()))
+    };
+    let memory = hierarchy("memory");
+    let cpu = hierarchy("cpu");
+    let pids = hierarchy("pids");
+    let read = |hierarchy: &Option<(PathBuf, String)>, file: &str| {
+        let (mount, path) = hierarchy.as_ref()?;
+        read_trimmed(&cgroup_dir(mount, path).join(file))
+    };
+
+    CgroupInfo {
+        path: path.to_string(),
+        version: 1,
+        memory_limit_bytes: memory.as_ref().and_then(|(mount, path)| {
+            tightest(mount, path, |dir| {
+                read_trimmed(&dir.join("memory.limit_in_bytes"))
+                    .and_then(|text| text.parse::<u64>().ok())
+                    .filter(|limit| *limit < V1_UNLIMITED)
+            })
+        }),
+        memory_usage_bytes: read(&memory, "memory.usage_in_bytes")
+            .and_then(|text| text.parse().ok()),
+        cpu_limit_cores: cpu.as_ref().and_then(|(mount, path)| {
+            tightest(mount, path, |dir| {
+                let quota = read_trimmed(&dir.join("cpu.cfs_quota_us"))?;
+                let period = read_trimmed(&dir.join("cpu.cfs_period_us"))?;
+                parse_cpu_max(&format!("{quota} {period}"))
+            })
+        }),
+        pids_limit: pids.as_ref().and_then(|(mount, path)| {
+            tightest(mount, path, |dir| {
+                read_trimmed(&dir.join("pids.max")).and_then(|text| text.parse().ok())
+            })
+        }),
+        oom_kills: read(&memory, "memory.oom_control")
+            .and_then(|text| keyed_value(&text, "oom_kill")),
+        throttled_periods: read(&cpu, "cpu.stat")
+            .and_then(|text| keyed_value(&text, "nr_throttled")),
+    }
+}
+
+/// `cpu.max` is `<quota> <period>` in microseconds, or `max <period>`.
+/// v1's `-1` quota means the same as `max`.
+pub fn parse_cpu_max(text: &str) -> Option<f64> {
+    let mut fields = text.split_whitespace();
+    let quota = fields
+        .next()?
+        .parse::<f64>()
+        .ok()
+        .filter(|quota| *quota > 0.0)?;
+    let period = fields
+        .next()
+        .map_or(Some(100_000.0), |period| period.parse::<f64>().ok())
+        .filter(|period| *period > 0.0)?;
+    Some(quota / period)
+}
+
+/// The smallest limit set on `path` or any of its ancestors.
+fn tightest<T: PartialOrd>(
+    root: &Path,
+    path: &str,
+    limit: impl Fn(&Path) -> Option<T>,
+) -> Option<T> {
+    let mut best = None::<T>;
+    let mut current = Some(path.trim_end_matches('/'));
+    while let Some(path) = current {
+        if let Some(value) = limit(&cgroup_dir(root, path)) {
+            if best.as_ref().map_or(true, |best| value < *best) {
+                best = Some(value);
+            }
+        }
+        current = path.rsplit_once('/').map(|(parent, _)| parent);
+    }
+    best
+}
+
+fn cgroup_dir(root: &Path, path: &str) -> PathBuf {
+    root.join(path.trim_start_matches('/'))
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .map(|text| text.trim().to_string())
+}
+
+/// A `<key> <value>` line out of `memory.events`, `cpu.stat` and friends.
+fn keyed_value(text: &str, key: &str) -> Option<u64> {
+    text.lines().find_map(|line| {
+        let (name, value) = line.split_once(' ')?;
+        (name == key).then(|| value.trim().parse().ok()).flatten()
+    })
+}
@@ -0,0 +1,412 @@
+use std::collections::HashMap;
+#[cfg(target_os = "linux")]
+use std::collections::HashSet;
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+use crate::model::ContainerInfo;
+use crate::model::{DockerPortMapping, PortInfo, ProcessInfo};
+#[cfg(target_os = "linux")]
+use crate::process::{epoch_millis, process_started_at, START_TIME_TOLERANCE_MS};
+
+/// `docker ps` is far too slow to run every sample, so names are cached and
+/// only re-fetched when an unknown ID shows up, at most this often.
+const NAME_REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// What a process's `/proc/<pid>/cgroup` said. A cgroup is set when the
+/// process starts and almost never moves, so this is reused until it is
+/// `NAME_REFRESH_INTERVAL` old, as long as the PID still belongs to the
+/// process that started at `started_at_epoch_ms`.
+#[cfg(target_os = "linux")]
+struct CgroupEntry {
+    started_at_epoch_ms: u128,
+    container: Option<(&'static str, String)>,
+    read: Instant,
+}
+
+#[derive(Default)]
+struct ContainerCache {
+    names: HashMap<String, String>,
+    refreshed: Option<Instant>,
+    #[cfg(target_os = "linux")]
+    cgroups: HashMap<i32, CgroupEntry>,
+}
+
+static CACHE: Mutex<Option<ContainerCache>> = Mutex::new(None);
+
+/// Fills in `ProcessInfo.container` for every process in a container.
+pub fn annotate_containers(processes: &mut [ProcessInfo]) {
+    let mut guard = CACHE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let cache = guard.get_or_insert_with(ContainerCache::default);
+    detect_containers(processes, cache);
+
+    let mut runtimes = processes
+        .iter()
+        .filter_map(|process| process.container.as_ref())
+        .filter(|container| container.id.is_some())
+        .map(|container| container.runtime)
+        .collect::<Vec<_>>();
+    if runtimes.is_empty() {
+        return;
+    }
+    runtimes.sort_unstable();
+    runtimes.dedup();
+
+    let unknown = processes
+        .iter()
+        .filter_map(|process| process.container.as_ref()?.id.as_ref())
+        .any(|id| !cache.names.contains_key(id));
+    let stale = cache
+        .refreshed
+        .map_or(true, |at| at.elapsed() >= NAME_REFRESH_INTERVAL);
+    if unknown && stale {
+        cache.names = runtimes
+            .iter()
+            .flat_map(|runtime| list_container_names(runtime))
+            .collect();
+        cache.refreshed = Some(Instant::now());
+    }
+
+    for container in processes
+        .iter_mut()
+        .filter_map(|process| process.container.as_mut())
+    {
+        if let Some(id) = &container.id {
+            container.name = cache.names.get(id).cloned();
+        }
+    }
+}
+
+/// Stops a container through its runtime's CLI, which gives the workload
+/// its normal shutdown grace period instead of signaling shim children.
+pub fn stop_container(runtime: &str, id: &str) -> Result<(), String> {
+    let valid = !id.is_empty()
+        && !id.starts_with('-')
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'));
+    if !valid {
+        return Err(format!("Invalid container ID: {id}"));
+    }
+
+    let program = cli_for_runtime(runtime)
+        .ok_or_else(|| format!("Containers managed by {runtime} can't be stopped from pswtf"))?;
+    let output = Command::new(program)
+        .args(["stop", id])
+        .output()
+        .map_err(|error| format!("Failed to run {program}: {error}"))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{program} stop failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+fn cli_for_runtime(runtime: &str) -> Option<&'static str> {
+    match runtime {
+        "docker" | "docker-desktop" => Some("docker"),
+        "podman" => Some("podman"),
+        _ => None,
+    }
+}
+
+fn list_container_names(runtime: &str) -> Vec<(String, String)> {
+    let Some(program) = cli_for_runtime(runtime) else {
+        return Vec::new();
+    };
+    let Ok(output) = Command::new(program)
+        .args(["ps", "--no-trunc", "--format", "{{.ID}}\t{{.Names}}"])
+        .output()
+    else {
+        return Vec::new();
+    };
+    if !output.status.success() {
+        return Vec::new();
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (id, name) = line.split_once('\t')?;
+            Some((id.trim().to_string(), name.trim().to_string()))
+        })
+        .collect()
+}
+
+/// Pulls the runtime and 64-hex container ID out of a `/proc/<pid>/cgroup`
+/// file: cgroup v1 (`/docker/<id>`), systemd scopes (`docker-<id>.scope`,
+/// `libpod-<id>.scope`) and Kubernetes (`cri-containerd-<id>.scope`, `crio-<id>`).
+pub fn parse_cgroup_container(contents: &str) -> Option<(&'static str, String)> {
+    for line in contents.lines() {
+        let path = line.splitn(3, ':').nth(2).unwrap_or(line);
+        let segments = path.split('/').collect::<Vec<_>>();
+
+        for (index, segment) in segments.iter().enumerate() {
+            let segment = segment.trim_end_matches(".scope");
+            let (runtime, id) = if let Some(id) = segment.strip_prefix("docker-") {
+                ("docker", id)
+            } else if let Some(id) = segment.strip_prefix("libpod-") {
+                ("podman", id)
+            } else if let Some(id) = segment
+                .strip_prefix("cri-containerd-")
+                .or_else(|| segment.strip_prefix("crio-"))
+            {
+                ("kubernetes", id)
+            } else if index > 0 && segments[index - 1] == "docker" {
+                ("docker", segment)
+            } else {
+                continue;
+            };
+
+            if id.len() == 64 && id.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Some((runtime, id.to_string()));
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn detect_containers(processes: &mut [ProcessInfo], cache: &mut ContainerCache) {
+    let live = processes
+        .iter()
+        .map(|process| process.pid)
+        .collect::<HashSet<_>>();
+    cache.cgroups.retain(|pid, _| live.contains(pid));
+
+    let now = epoch_millis();
+    for process in processes.iter_mut() {
+        let started_at_epoch_ms = process_started_at(process, now);
+        let reusable = cache.cgroups.get(&process.pid).is_some_and(|entry| {
+            entry.started_at_epoch_ms.abs_diff(started_at_epoch_ms) <= START_TIME_TOLERANCE_MS
+                && entry.read.elapsed() < NAME_REFRESH_INTERVAL
+        });
+        if !reusable {
+            let container = std::fs::read_to_string(format!("/proc/{}/cgroup", process.pid))
+                .ok()
+                .and_then(|contents| parse_cgroup_container(&contents));
+            cache.cgroups.insert(
+                process.pid,
+                CgroupEntry {
+                    started_at_epoch_ms,
+                    container,
+                    read: Instant::now(),
+                },
+            );
+        }
+
+        if let Some((runtime, id)) = &cache.cgroups[&process.pid].container {
+            process.container = Some(ContainerInfo {
+                runtime,
+                id: Some(id.clone()),
+                name: None,
+            });
+        }
+    }
+}
+
+/// Where the Docker Engine API listens: the standard path on Linux (and the
+/// symlink Docker Desktop installs), then Docker Desktop's per-user socket.
+#[cfg(unix)]
+const DOCKER_SOCKETS: &[&str] = &["/var/run/docker.sock", "~/.docker/run/docker.sock"];
+#[cfg(unix)]
+const DOCKER_API_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ApiContainer {
+    id: String,
+    names: Vec<String>,
+    #[serde(default)]
+    ports: Vec<ApiPort>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ApiPort {
+    private_port: u16,
+    public_port: Option<u16>,
+    #[serde(rename = "Type")]
+    protocol: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ApiTop {
+    titles: Vec<String>,
+    processes: Vec<Vec<String>>,
+}
+
+/// Listener names that mean "Docker is forwarding this port". lsof cuts
+/// command names to 9 characters, hence the prefixes.
+fn is_docker_forwarder(process_name: &str) -> bool {
+    ["docker-pr", "com.dock", "vpnkit", "rootlessk"]
+        .iter()
+        .any(|prefix| process_name.starts_with(prefix))
+}
+
+/// Replaces the unhelpful `docker-proxy` / `com.docker.backend` owner of
+/// published ports with the container behind them, via the Docker socket.
+/// Does nothing when no port is a Docker forward or Docker isn't reachable.
+pub fn annotate_docker_ports(ports: &mut [PortInfo]) {
+    let forwarded = ports.iter().any(|entry| {
+        entry
+            .process_name
+            .as_deref()
+            .is_some_and(is_docker_forwarder)
+    });
+    if !forwarded {
+        return;
+    }
+
+    let Some(containers) = docker_get("/containers/json")
+        .ok()
+        .and_then(|body| serde_json::from_slice::<Vec<ApiContainer>>(&body).ok())
+    else {
+        return;
+    };
+
+    let mut main_processes = HashMap::<String, Option<(i32, String)>>::new();
+    for entry in ports.iter_mut() {
+        if !entry
+            .process_name
+            .as_deref()
+            .is_some_and(is_docker_forwarder)
+        {
+            continue;
+        }
+
+        let protocol = entry.protocol.to_ascii_lowercase();
+        let Some((container, mapping)) = containers.iter().find_map(|container| {
+            container
+                .ports
+                .iter()
+                .find(|port| port.public_port == Some(entry.port) && port.protocol == protocol)
+                .map(|port| (container, port))
+        }) else {
+            continue;
+        };
+
+        let main = main_processes
+            .entry(container.id.clone())
+            .or_insert_with(|| container_main_process(&container.id))
+            .clone();
+        entry.docker = Some(DockerPortMapping {
+            container_id: container.id.clone(),
+            container_name: container
+                .names
+                .first()
+                .map(|name| name.trim_start_matches('/').to_string())
+                .unwrap_or_default(),
+            container_port: mapping.private_port,
+            process_pid: main.as_ref().map(|(pid, _)| *pid),
+            process_command: main.map(|(_, command)| command),
+        });
+    }
+}
+
+fn container_main_process(id: &str) -> Option<(i32, String)> {
+    let body = docker_get(&format!("/containers/{id}/top")).ok()?;
+    let top = serde_json::from_slice::<ApiTop>(&body).ok()?;
+
+    let column = |title: &str| top.titles.iter().position(|candidate| candidate == title);
+    let pid_column = column("PID")?;
+    let command_column = column("CMD").or_else(|| column("COMMAND"))?;
+    let first = top.processes.first()?;
+
+    Some((
+        first.get(pid_column)?.parse().ok()?,
+        first.get(command_column)?.clone(),
+    ))
+}
+
+/// A bare-bones HTTP/1.0 GET against the Docker socket. 1.0 keeps the
+/// daemon from chunking the response, so the body is everything after the
+/// headers.
+#[cfg(unix)]
+fn docker_get(path: &str) -> Result<Vec<u8>, String> {
+    use std::io::{Read, Write};
+    use std::os::unix::net::UnixStream;
+
+    let home = std::env::var("HOME").unwrap_or_default();
+    let mut stream = DOCKER_SOCKETS
+        .iter()
+        .map(|socket| socket.replacen('~', &home, 1))
+        .find_map(|socket| UnixStream::connect(socket).ok())
+        .ok_or_else(|| "Docker socket not found".to_string())?;
+    stream
+        .set_read_timeout(Some(DOCKER_API_TIMEOUT))
+        .and_then(|_| stream.set_write_timeout(Some(DOCKER_API_TIMEOUT)))
+        .map_err(|error| format!("Failed to configure Docker socket: {error}"))?;
+
+    write!(stream, "GET {path} HTTP/1.0\r\nHost: docker\r\n\r\n")
+        .map_err(|error| format!("Failed to query Docker: {error}"))?;
+    let mut response = Vec::new();
+    stream
+        .read_to_end(&mut response)
+        .map_err(|error| format!("Failed to read Docker response: {error}"))?;
+
+    let split = response
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .ok_or_else(|| "Malformed Docker response".to_string())?;
+    let status_line = String::from_utf8_lossy(&response[..split]);
+    if status_line.split_whitespace().nth(1) != Some("200") {
+        return Err(format!(
+            "Docker API returned {}",
+            status_line.lines().next().unwrap_or_default()
+        ));
+    }
+
+    Ok(response[split + 4..].to_vec())
+}
+
+#[cfg(not(unix))]
+fn docker_get(_path: &str) -> Result<Vec<u8>, String> {
+    Err("Docker socket access is only supported on Unix".to_string())
+}
+
+/// Containers on macOS run inside Docker Desktop's VM, invisible to the host
+/// process table. What the host does see is Docker Desktop's own
+/// `com.docker.*` tree, which owns every published port, so flag that.
+#[cfg(target_os = "macos")]
+fn detect_containers(processes: &mut [ProcessInfo], _cache: &mut ContainerCache) {
+    let parents = processes
+        .iter()
+        .map(|process| (process.pid, (process.parent_pid, process.name.clone())))
+        .collect::<HashMap<_, _>>();
+
+    for process in processes.iter_mut() {
+        let mut current = Some(process.pid);
+        let mut depth = 0;
+        while let Some((parent_pid, name)) = current.and_then(|pid| parents.get(&pid)) {
+            if name.starts_with("com.docker.") {
+                process.container = Some(ContainerInfo {
+                    runtime: "docker-desktop",
+                    id: None,
+                    name: None,
+                });
+                break;
+            }
+            depth += 1;
+            if depth > 64 {
+                break;
+            }
+            current = *parent_pid;
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn detect_containers(_processes: &mut [ProcessInfo], _cache: &mut ContainerCache) {}
@@ -0,0 +1,178 @@
+//! Hands paths over to the desktop: a terminal opened in a process's
+//! working directory, or its executable shown in the file manager.
+
+use std::path::Path;
+use std::process::Command;
+
+/// Opens a new terminal window in `dir`: iTerm if it's installed and
+/// Terminal.app otherwise on macOS, `$TERMINAL` or the first common
+/// emulator found on Linux, Windows Terminal or `cmd` on Windows.
+pub fn open_terminal(dir: &Path) -> Result<(), String> {
+    if !dir.is_dir() {
+        return Err(format!("{} is not a directory", dir.display()));
+    }
+    launch_terminal(dir)
+}
+
+#[cfg(target_os = "macos")]
+fn launch_terminal(dir: &Path) -> Result<(), String> {
+    let app = if Path::new("/Applications/iTerm.app").exists() {
+        "iTerm"
+    } else {
+        "Terminal"
+    };
+    let status = Command::new("open")
+        .args(["-a", app])
+        .arg(dir)
+        .status()
+        .map_err(|error| format!("Failed to open {app}: {error}"))?;
+    if !status.success() {
+        return Err(format!("Failed to open {app}"));
+    }
+    Ok(())
+}
+
+/// Terminals start in their own working directory when none is passed, so
+/// every candidate is launched from `dir` rather than given a flag.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn launch_terminal(dir: &Path) -> Result<(), String> {
+    const FALLBACKS: [&str; 8] = [
+        "xdg-terminal-exec",
+        "x-terminal-emulator",
+        "gnome-terminal",
+        "konsole",
+        "xfce4-terminal",
+        "alacritty",
+        "kitty",
+        "xterm",
+    ];
+
+    let preferred = std::env::var("TERMINAL").ok();
+    let preferred = preferred
+        .as_deref()
+        .and_then(|terminal| terminal.split_whitespace().next());
+    for terminal in preferred.into_iter().chain(FALLBACKS) {
+        match spawn_detached(Command::new(terminal).current_dir(dir)) {
+            Ok(()) => return Ok(()),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(error) => return Err(format!("Failed to start {terminal}: {error}")),
+        }
+    }
+    Err("No terminal emulator found; set $TERMINAL".to_string())
+}
+
+#[cfg(windows)]
+fn launch_terminal(dir: &Path) -> Result<(), String> {
+    match spawn_detached(Command::new("wt").arg("-d").arg(dir)) {
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => spawn_detached(
+            Command::new("cmd")
+                .args(["/C", "start", "", "cmd.exe"])
+                .current_dir(dir),
+        )
+        .map_err(|error| format!("Failed to start cmd: {error}")),
+        result => result.map_err(|error| format!("Failed to start Windows Terminal: {error}")),
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn launch_terminal(_dir: &Path) -> Result<(), String> {
+    Err("Opening a terminal is not supported on this platform".to_string())
+}
+
+/// Opens the file manager with `path` selected: Finder, Explorer, or on
+/// Linux whatever implements the freedesktop `FileManager1` interface
+/// (Nautilus, Dolphin, Nemo), falling back to opening the parent folder.
+pub fn reveal_in_file_manager(path: &Path) -> Result<(), String> {
+    if !path.exists() {
+        return Err(format!("{} no longer exists", path.display()));
+    }
+    reveal(path)
+}
+
+#[cfg(target_os = "macos")]
+fn reveal(path: &Path) -> Result<(), String> {
+    let status = Command::new("open")
+        .arg("-R")
+        .arg(path)
+        .status()
+        .map_err(|error| format!("Failed to open Finder: {error}"))?;
+    if !status.success() {
+        return Err("Failed to open Finder".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn reveal(path: &Path) -> Result<(), String> {
+    let uri = format!(
+        "file://{}",
+        percent_encode_path(&path.display().to_string())
+    );
+    let shown = Command::new("dbus-send")
+        .args([
+            "--session",
+            "--dest=org.freedesktop.FileManager1",
+            "--type=method_call",
+            "/org/freedesktop/FileManager1",
+            "org.freedesktop.FileManager1.ShowItems",
+        ])
+        .arg(format!("array:string:{uri}"))
+        .arg("string:")
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success());
+    if shown {
+        return Ok(());
+    }
+
+    let parent = path.parent().unwrap_or(path);
+    spawn_detached(Command::new("xdg-open").arg(parent))
+        .map_err(|error| format!("Failed to open the file manager: {error}"))
+}
+
+/// Explorer reports failure through its exit code even when the window
+/// opened, so only a failure to start it counts.
+#[cfg(windows)]
+fn reveal(path: &Path) -> Result<(), String> {
+    let mut select = std::ffi::OsString::from("/select,");
+    select.push(path);
+    spawn_detached(Command::new("explorer").arg(select))
+        .map_err(|error| format!("Failed to open Explorer: {error}"))
+}
+
+#[cfg(not(any(unix, windows)))]
+fn reveal(_path: &Path) -> Result<(), String> {
+    Err("Revealing files is not supported on this platform".to_string())
+}
+
+/// Escapes everything a `file://` URI can't carry as-is; `/` is kept.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn percent_encode_path(path: &str) -> String {
+    path.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+/// Starts `command` without tying it to pswtf's stdio, and reaps it on a
+/// background thread so launchers that exit straight away don't linger as
+/// zombies.
+#[cfg(any(windows, all(unix, not(target_os = "macos"))))]
+fn spawn_detached(command: &mut Command) -> std::io::Result<()> {
+    use std::process::Stdio;
+
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    std::thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
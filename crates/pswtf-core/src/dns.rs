@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::model::ConnectionInfo;
+
+/// How long a lookup result, including "no PTR record", is reused.
+const CACHE_TTL: Duration = Duration::from_secs(600);
+/// Caps the number of lookup threads one call can start.
+const MAX_LOOKUPS_PER_CALL: usize = 64;
+
+enum CachedName {
+    /// A lookup thread is still running; its result lands in the cache
+    /// even if the caller that started it has stopped waiting.
+    Pending,
+    Resolved(Option<String>, Instant),
+}
+
+static CACHE: Mutex<Option<HashMap<IpAddr, CachedName>>> = Mutex::new(None);
+
+fn with_cache<T>(f: impl FnOnce(&mut HashMap<IpAddr, CachedName>) -> T) -> T {
+    let mut guard = CACHE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    f(guard.get_or_insert_with(HashMap::new))
+}
+
+/// Fills in `remote_host` from reverse DNS, waiting at most `timeout` for
+/// lookups that aren't cached yet. Slow lookups keep running and show up
+/// on a later call.
+pub fn resolve_remote_hosts(connections: &mut [ConnectionInfo], timeout: Duration) {
+    let addresses = connections
+        .iter()
+        .filter_map(|entry| parse_ip(&entry.remote_address))
+        .collect::<Vec<_>>();
+
+    let (sender, receiver) = mpsc::channel::<(IpAddr, Option<String>)>();
+    let mut names = HashMap::<IpAddr, Option<String>>::new();
+    let mut waiting = 0usize;
+    for address in addresses {
+        if names.contains_key(&address) {
+            continue;
+        }
+        let start = with_cache(|cache| match cache.get(&address) {
+            Some(CachedName::Resolved(name, at)) if at.elapsed() < CACHE_TTL => {
+                names.insert(address, name.clone());
+                false
+            }
+            Some(CachedName::Pending) => false,
+            _ if waiting >= MAX_LOOKUPS_PER_CALL => false,
+            _ => {
+                cache.insert(address, CachedName::Pending);
+                true
+            }
+        });
+        if !start {
+            continue;
+        }
+
+        let sender = sender.clone();
+        let spawned = std::thread::Builder::new()
+            .name("pswtf-dns".to_string())
+            .spawn(move || {
+                let name = reverse_lookup(address);
+                with_cache(|cache| {
+                    cache.insert(address, CachedName::Resolved(name.clone(), Instant::now()))
+                });
+                let _ = sender.send((address, name));
+            });
+        match spawned {
+            Ok(_) => waiting += 1,
+            Err(_) => with_cache(|cache| {
+                cache.remove(&address);
+            }),
+        }
+    }
+    drop(sender);
+
+    let deadline = Instant::now() + timeout;
+    while waiting > 0 {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match receiver.recv_timeout(remaining) {
+            Ok((address, name)) => {
+                names.insert(address, name);
+                waiting -= 1;
+            }
+            Err(_) => break,
+        }
+    }
+
+    for entry in connections.iter_mut() {
+        if let Some(address) = parse_ip(&entry.remote_address) {
+            entry.remote_host = names.get(&address).cloned().flatten();
+        }
+    }
+}
+
+/// lsof prints link-local IPv6 addresses with a `%interface` suffix.
+fn parse_ip(address: &str) -> Option<IpAddr> {
+    address.split('%').next()?.parse().ok()
+}
+
+/// Blocking PTR lookup through the system resolver (so /etc/hosts and
+/// mDNS apply). `None` when the address has no name.
+#[cfg(unix)]
+pub fn reverse_lookup(address: IpAddr) -> Option<String> {
+    let mut storage = unsafe { std::mem::zeroed::<libc::sockaddr_storage>() };
+    let length = match address {
+        IpAddr::V4(v4) => {
+            let addr = unsafe { &mut *(&mut storage as *mut _ as *mut libc::sockaddr_in) };
+            addr.sin_family = libc::AF_INET as libc::sa_family_t;
+            addr.sin_addr.s_addr = u32::from_ne_bytes(v4.octets());
+            std::mem::size_of::<libc::sockaddr_in>()
+        }
+        IpAddr::V6(v6) => {
+            let addr = unsafe { &mut *(&mut storage as *mut _ as *mut libc::sockaddr_in6) };
+            addr.sin6_family = libc::AF_INET6 as libc::sa_family_t;
+            addr.sin6_addr.s6_addr = v6.octets();
+            std::mem::size_of::<libc::sockaddr_in6>()
+        }
+    };
+    #[cfg(any(
+        target_os = "macos",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd"
+    ))]
+    {
+        storage.ss_len = length as u8;
+    }
+
+    let mut host = [0 as libc::c_char; 1025];
+    let result = unsafe {
+        libc::getnameinfo(
+            &storage as *const _ as *const libc::sockaddr,
+            length as libc::socklen_t,
+            host.as_mut_ptr(),
+            host.len() as libc::socklen_t,
+            std::ptr::null_mut(),
+            0,
+            libc::NI_NAMEREQD,
+        )
+    };
+    if result != 0 {
+        return None;
+    }
+
+    let name = unsafe { std::ffi::CStr::from_ptr(host.as_ptr()) };
+    Some(name.to_string_lossy().into_owned())
+}
+
+#[cfg(not(unix))]
+pub fn reverse_lookup(_address: IpAddr) -> Option<String> {
+    None
+}
@@ -0,0 +1,216 @@
+use sysinfo::{Pid, PidExt, ProcessExt, System, SystemExt};
+
+use crate::model::{PortForward, PortInfo};
+
+/// Listener names worth reading the command line of. lsof cuts command
+/// names to 9 characters.
+const FORWARDER_NAMES: &[&str] = &["kubectl", "ssh", "cloud-sql", "cloud_sql"];
+
+/// Options of `ssh` that take a value, so the value isn't mistaken for the
+/// destination.
+const SSH_VALUED_OPTIONS: &str = "BbcDEeFIiJLlmOopQRSWw";
+
+/// Annotates listeners owned by `kubectl port-forward`, `ssh -L` and
+/// `cloud-sql-proxy` with where the traffic actually goes, so it's clear
+/// what killing the tunnel would cut off.
+pub fn annotate_port_forwards(ports: &mut [PortInfo]) {
+    let mut system = System::new();
+    for entry in ports.iter_mut() {
+        let (Some(pid), Some(name)) = (entry.pid, entry.process_name.as_deref()) else {
+            continue;
+        };
+        if !FORWARDER_NAMES
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+        {
+            continue;
+        }
+        let Ok(raw_pid) = u32::try_from(pid) else {
+            continue;
+        };
+
+        let target = Pid::from_u32(raw_pid);
+        system.refresh_process(target);
+        if let Some(process) = system.process(target) {
+            entry.forward = parse_forward(process.cmd(), entry.port);
+        }
+    }
+}
+
+/// Works out what a tunnel's command line forwards `local_port` to.
+pub fn parse_forward(args: &[String], local_port: u16) -> Option<PortForward> {
+    let program = args.first()?.rsplit('/').next()?;
+    match program {
+        "kubectl" | "oc" => parse_kubectl(&args[1..], local_port),
+        "ssh" => parse_ssh(&args[1..], local_port),
+        "cloud-sql-proxy" | "cloud_sql_proxy" => parse_cloud_sql_proxy(&args[1..], local_port),
+        _ => None,
+    }
+}
+
+/// `kubectl [-n ns] port-forward [-n ns] TYPE/NAME [LOCAL:]REMOTE ...`.
+fn parse_kubectl(args: &[String], local_port: u16) -> Option<PortForward> {
+    let mut namespace = None;
+    let mut positional = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "-n" || arg == "--namespace" {
+            namespace = iter.next().cloned();
+        } else if let Some(value) = arg.strip_prefix("--namespace=") {
+            namespace = Some(value.to_string());
+        } else if arg == "--address" || arg == "--context" || arg == "--kubeconfig" {
+            iter.next();
+        } else if !arg.starts_with('-') {
+            positional.push(arg.as_str());
+        }
+    }
+
+    let (command, rest) = positional.split_first()?;
+    if *command != "port-forward" {
+        return None;
+    }
+    let (resource, specs) = rest.split_first()?;
+    let remote_port = specs.iter().find_map(|spec| {
+        let (local, remote) = spec.split_once(':').unwrap_or((spec, spec));
+        // An empty local side means kubectl picked a random port.
+        (local.is_empty() || local.parse::<u16>().ok() == Some(local_port))
+            .then(|| remote.parse().ok())
+            .flatten()
+    });
+
+    Some(PortForward {
+        tool: "kubectl",
+        target: resource.to_string(),
+        namespace,
+        remote_port,
+        via: None,
+    })
+}
+
+/// `ssh -L [bind:]port:host:hostport [-L ...] destination`.
+fn parse_ssh(args: &[String], local_port: u16) -> Option<PortForward> {
+    let mut forwards = Vec::new();
+    let mut destination = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let Some(cluster) = arg.strip_prefix('-') else {
+            destination = destination.or(Some(arg.clone()));
+            continue;
+        };
+        // Flags can be clustered, as in `-fNL 8080:db:5432`. The first one
+        // that takes a value gets the rest of the cluster, or the next
+        // argument when the cluster ends there.
+        let Some((index, flag)) = cluster
+            .char_indices()
+            .find(|(_, flag)| SSH_VALUED_OPTIONS.contains(*flag))
+        else {
+            continue;
+        };
+        let rest = &cluster[index + flag.len_utf8()..];
+        let value = if rest.is_empty() {
+            iter.next().cloned()
+        } else {
+            Some(rest.to_string())
+        };
+        if flag == 'L' {
+            forwards.extend(value);
+        }
+    }
+
+    forwards.iter().find_map(|spec| {
+        let fields = split_outside_brackets(spec);
+        // [bind:]port:host:hostport, read from the end.
+        let [.., port, host, host_port] = fields.as_slice() else {
+            return None;
+        };
+        if port.parse::<u16>().ok()? != local_port {
+            return None;
+        }
+        Some(PortForward {
+            tool: "ssh",
+            target: format!("{host}:{host_port}"),
+            namespace: None,
+            remote_port: host_port.parse().ok(),
+            via: destination.clone(),
+        })
+    })
+}
+
+/// Splits an ssh forward spec on `:`, keeping bracketed IPv6 hosts whole.
+fn split_outside_brackets(spec: &str) -> Vec<&str> {
+    let mut fields = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (index, c) in spec.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            ':' if depth == 0 => {
+                fields.push(&spec[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    fields.push(&spec[start..]);
+    fields
+}
+
+/// v2: `cloud-sql-proxy [--port N] project:region:instance[?port=N] ...`,
+/// where instances without their own port count up from `--port` (5432).
+/// v1: `cloud_sql_proxy -instances=project:region:instance=tcp:N,...`.
+fn parse_cloud_sql_proxy(args: &[String], local_port: u16) -> Option<PortForward> {
+    let mut base_port = 5432u16;
+    let mut instances = Vec::<(String, Option<u16>)>::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with('-') => (flag, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        match flag.trim_start_matches('-') {
+            "port" | "p" => {
+                if let Some(port) = inline.or_else(|| iter.next().cloned()) {
+                    base_port = port.parse().unwrap_or(base_port);
+                }
+            }
+            "instances" => {
+                let list = inline.or_else(|| iter.next().cloned()).unwrap_or_default();
+                instances.extend(list.split(',').map(
+                    |instance| match instance.split_once("=tcp:") {
+                        Some((name, port)) => (
+                            name.to_string(),
+                            port.rsplit(':').next().and_then(|port| port.parse().ok()),
+                        ),
+                        None => (instance.to_string(), None),
+                    },
+                ));
+            }
+            _ if !arg.starts_with('-') && arg.matches(':').count() == 2 => {
+                let (name, query) = arg.split_once('?').unwrap_or((arg, ""));
+                let port = query
+                    .split('&')
+                    .find_map(|pair| pair.strip_prefix("port="))
+                    .and_then(|port| port.parse().ok());
+                instances.push((name.to_string(), port));
+            }
+            _ => {}
+        }
+    }
+
+    let mut next_port = base_port;
+    instances.into_iter().find_map(|(name, port)| {
+        let port = port.unwrap_or_else(|| {
+            let port = next_port;
+            next_port = next_port.saturating_add(1);
+            port
+        });
+        (port == local_port).then_some(PortForward {
+            tool: "cloud-sql-proxy",
+            target: name,
+            namespace: None,
+            remote_port: None,
+            via: None,
+        })
+    })
+}
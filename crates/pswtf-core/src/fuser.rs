@@ -0,0 +1,250 @@
+//! Which processes have a file, or anything under a directory, open: the
+//! other half of "can't delete this because something is using it", and of
+//! "the volume can't be ejected".
+
+use std::path::Path;
+
+use crate::lsof::parse_fields;
+use crate::model::{FileUse, FileUseKind, FileUser};
+
+/// Every process holding `path` or, for a directory, anything beneath it,
+/// whether as an open file, working directory, root, executable or mapping.
+/// Only processes pswtf is allowed to inspect show up.
+pub fn find_file_users(path: &Path) -> Result<Vec<FileUser>, String> {
+    let target = std::fs::canonicalize(path)
+        .map_err(|error| format!("Can't resolve {}: {error}", path.display()))?;
+    let mut users = collect_file_users(&target, false)?;
+    users.sort_by_key(|user| user.pid);
+    Ok(users)
+}
+
+/// Everything keeping the filesystem mounted at `mount_point` busy: open
+/// files, working directories and mappings anywhere on it.
+pub fn find_mount_blockers(mount_point: &Path) -> Result<Vec<FileUser>, String> {
+    let target = std::fs::canonicalize(mount_point)
+        .map_err(|error| format!("Can't resolve {}: {error}", mount_point.display()))?;
+    if !is_mount_point(&target) {
+        return Err(format!("{} is not a mount point", target.display()));
+    }
+
+    let mut users = collect_file_users(&target, true)?;
+    users.sort_by_key(|user| user.pid);
+    Ok(users)
+}
+
+/// Linux lists every mount, bind mounts included, in mountinfo; the fifth
+/// field is the mount point with spaces and the like octal-escaped.
+#[cfg(target_os = "linux")]
+fn is_mount_point(path: &Path) -> bool {
+    let Ok(mountinfo) = std::fs::read_to_string("/proc/self/mountinfo") else {
+        return false;
+    };
+    let wanted = path.display().to_string();
+    mountinfo
+        .lines()
+        .filter_map(|line| line.split(' ').nth(4))
+        .any(|mount| unescape_mount_path(mount) == wanted)
+}
+
+#[cfg(target_os = "linux")]
+fn unescape_mount_path(path: &str) -> String {
+    let mut unescaped = Vec::with_capacity(path.len());
+    let bytes = path.as_bytes();
+    let mut index = 0;
+    while index < bytes.len() {
+        let octal = bytes
+            .get(index + 1..index + 4)
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .and_then(|digits| u8::from_str_radix(digits, 8).ok());
+        match (bytes[index], octal) {
+            (b'\\', Some(byte)) => {
+                unescaped.push(byte);
+                index += 4;
+            }
+            (byte, _) => {
+                unescaped.push(byte);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&unescaped).into_owned()
+}
+
+/// A directory on a different device than its parent, or `/` itself.
+#[cfg(all(unix, not(target_os = "linux")))]
+fn is_mount_point(path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    let Some(parent) = path.parent() else {
+        return true;
+    };
+    match (std::fs::metadata(path), std::fs::metadata(parent)) {
+        (Ok(own), Ok(parent)) => own.is_dir() && own.dev() != parent.dev(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn is_mount_point(_path: &Path) -> bool {
+    false
+}
+
+/// Walks `/proc` directly; lsof would also stat every file it finds,
+/// which can hang on a dead network mount.
+///
+/// For a whole mount, a path under the mount point can still be on another
+/// filesystem mounted inside it (`/proc` under `/`, say), and holding that
+/// doesn't keep this one busy, so files are matched by device instead.
+#[cfg(target_os = "linux")]
+fn collect_file_users(target: &Path, whole_mount: bool) -> Result<Vec<FileUser>, String> {
+    use std::os::unix::fs::MetadataExt;
+
+    let mount_device = if whole_mount {
+        Some(
+            std::fs::metadata(target)
+                .map_err(|error| format!("Can't stat {}: {error}", target.display()))?
+                .dev(),
+        )
+    } else {
+        None
+    };
+    let entries =
+        std::fs::read_dir("/proc").map_err(|error| format!("Failed to read /proc: {error}"))?;
+
+    let mut users = Vec::new();
+    for entry in entries.flatten() {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse::<i32>().ok())
+        else {
+            continue;
+        };
+        let dir = entry.path();
+        let mut uses = Vec::new();
+        // The device comes from stat on the /proc link, which follows it
+        // to the open file even when that has been deleted or moved.
+        let mut push = |kind, fd, path: &Path, device: Option<u64>| {
+            let on_target = match (mount_device, device) {
+                (Some(mount_device), Some(device)) => device == mount_device,
+                _ => path.starts_with(target),
+            };
+            if on_target {
+                let path = path.display().to_string();
+                let file_use = FileUse { kind, fd, path };
+                if !uses.contains(&file_use) {
+                    uses.push(file_use);
+                }
+            }
+        };
+
+        for (link, kind) in [
+            ("cwd", FileUseKind::Cwd),
+            ("root", FileUseKind::Root),
+            ("exe", FileUseKind::Executable),
+        ] {
+            if let Ok(path) = std::fs::read_link(dir.join(link)) {
+                let device = std::fs::metadata(dir.join(link)).map(|meta| meta.dev());
+                push(kind, None, &path, device.ok());
+            }
+        }
+        if let Ok(fds) = std::fs::read_dir(dir.join("fd")) {
+            for fd in fds.flatten() {
+                let number = fd.file_name().to_str().and_then(|name| name.parse().ok());
+                if let (Some(number), Ok(path)) = (number, std::fs::read_link(fd.path())) {
+                    let device = std::fs::metadata(fd.path()).map(|meta| meta.dev());
+                    push(FileUseKind::Open, Some(number), &path, device.ok());
+                }
+            }
+        }
+        let exe = std::fs::read_link(dir.join("exe")).ok();
+        if let Ok(maps) = std::fs::read_to_string(dir.join("maps")) {
+            for (device, path) in maps.lines().filter_map(parse_maps_file) {
+                if exe.as_deref() != Some(Path::new(path)) {
+                    push(FileUseKind::Mapped, None, Path::new(path), Some(device));
+                }
+            }
+        }
+
+        if !uses.is_empty() {
+            let name = std::fs::read_to_string(dir.join("comm"))
+                .map(|comm| comm.trim_end().to_string())
+                .unwrap_or_default();
+            users.push(FileUser { pid, name, uses });
+        }
+    }
+    Ok(users)
+}
+
+/// The device and path of a file-backed `/proc/<pid>/maps` line:
+///
+/// `7f1d0a000000-7f1d0a021000 r--p 00000000 fe:01 280542  /usr/lib/libc.so.6`
+///
+/// The device is `major:minor` in hex, turned into the `st_dev` stat gives.
+/// Addresses, permissions, offset, device and inode come before the path
+/// and none of them contain a `/`.
+#[cfg(target_os = "linux")]
+pub fn parse_maps_file(line: &str) -> Option<(u64, &str)> {
+    let path = &line[line.find('/')?..];
+    let (major, minor) = line.split_whitespace().nth(3)?.split_once(':')?;
+    let device = libc::makedev(
+        u32::from_str_radix(major, 16).ok()?,
+        u32::from_str_radix(minor, 16).ok()?,
+    );
+    Some((device, path))
+}
+
+/// lsof given a mount point lists everything open on that filesystem, which
+/// is far quicker than `+D` walking the whole tree.
+#[cfg(all(unix, not(target_os = "linux")))]
+fn collect_file_users(target: &Path, whole_mount: bool) -> Result<Vec<FileUser>, String> {
+    let selector = if whole_mount || !target.is_dir() {
+        "--"
+    } else {
+        "+D"
+    };
+    let output = crate::lsof::run_lsof([std::ffi::OsStr::new(selector), target.as_os_str()])?;
+    Ok(file_users_from_lsof(&output, target))
+}
+
+#[cfg(not(unix))]
+fn collect_file_users(_target: &Path, _whole_mount: bool) -> Result<Vec<FileUser>, String> {
+    Err("Finding which processes use a file is not supported on this platform".to_string())
+}
+
+/// Turns `lsof -F` output into file users, keeping only files at or under
+/// `target`.
+pub fn file_users_from_lsof(output: &str, target: &Path) -> Vec<FileUser> {
+    parse_fields(output)
+        .into_iter()
+        .filter_map(|process| {
+            let mut uses = Vec::<FileUse>::new();
+            for file in process.files {
+                let Some(path) = file.name.filter(|name| Path::new(name).starts_with(target))
+                else {
+                    continue;
+                };
+                let (kind, fd) = match file.fd.as_str() {
+                    "cwd" => (FileUseKind::Cwd, None),
+                    "rtd" => (FileUseKind::Root, None),
+                    "txt" => (FileUseKind::Executable, None),
+                    fd => match fd.parse() {
+                        Ok(fd) => (FileUseKind::Open, Some(fd)),
+                        // `mem`, `mmap`, `DEL` and friends.
+                        Err(_) => (FileUseKind::Mapped, None),
+                    },
+                };
+                let file_use = FileUse { kind, fd, path };
+                if !uses.contains(&file_use) {
+                    uses.push(file_use);
+                }
+            }
+
+            (!uses.is_empty()).then_some(FileUser {
+                pid: process.pid,
+                name: process.command,
+                uses,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,967 @@
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+use std::collections::HashMap;
+#[cfg(not(target_os = "linux"))]
+use std::collections::HashSet;
+
+use crate::model::{
+    FileDescriptor, LoadedModule, MemoryBreakdown, OpenFile, ProcessCounters, ResourceLimit,
+    ThreadInfo, ThreadWait,
+};
+
+#[cfg(target_os = "linux")]
+pub fn collect_memory_breakdown(pid: i32) -> Option<MemoryBreakdown> {
+    let rollup = std::fs::read_to_string(format!("/proc/{pid}/smaps_rollup")).ok()?;
+
+    let mut fields = HashMap::<&str, u64>::new();
+    for line in rollup.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let Some(kb) = value
+            .split_whitespace()
+            .next()
+            .and_then(|text| text.parse::<u64>().ok())
+        else {
+            continue;
+        };
+        fields.insert(key.trim(), kb.saturating_mul(1024));
+    }
+
+    let field = |key: &str| fields.get(key).copied().unwrap_or_default();
+
+    Some(MemoryBreakdown {
+        resident_bytes: *fields.get("Rss")?,
+        shared_bytes: Some(field("Shared_Clean") + field("Shared_Dirty")),
+        swapped_bytes: Some(field("Swap")),
+        unique_bytes: Some(field("Private_Clean") + field("Private_Dirty")),
+    })
+}
+
+#[cfg(target_os = "macos")]
+pub fn collect_memory_breakdown(pid: i32) -> Option<MemoryBreakdown> {
+    // task_info needs a task port, which other processes won't hand out
+    // without entitlements; proc_pid_rusage works for anything we can see.
+    let mut info = std::mem::MaybeUninit::<libc::rusage_info_v2>::zeroed();
+    let result = unsafe {
+        libc::proc_pid_rusage(
+            pid,
+            libc::RUSAGE_INFO_V2,
+            info.as_mut_ptr() as *mut libc::rusage_info_t,
+        )
+    };
+    if result != 0 {
+        return None;
+    }
+    let info = unsafe { info.assume_init() };
+
+    // phys_footprint is the kernel's own "memory attributable to this
+    // process" figure and is the closest macOS analogue to USS.
+    Some(MemoryBreakdown {
+        resident_bytes: info.ri_resident_size,
+        shared_bytes: None,
+        swapped_bytes: None,
+        unique_bytes: Some(info.ri_phys_footprint),
+    })
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn collect_memory_breakdown(_pid: i32) -> Option<MemoryBreakdown> {
+    None
+}
+
+/// Context switches are per thread in `/proc`, so they're summed over
+/// `task/*/status`; the fault counts in `stat` already cover every thread.
+#[cfg(target_os = "linux")]
+pub fn collect_process_counters(pid: i32) -> Option<ProcessCounters> {
+    let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    // Fields after the parenthesised name, which may itself hold spaces:
+    // state ppid pgrp session tty_nr tpgid flags minflt cminflt majflt.
+    let fields = stat
+        .rsplit_once(')')?
+        .1
+        .split_whitespace()
+        .collect::<Vec<_>>();
+    let fault = |index: usize| fields.get(index).and_then(|value| value.parse().ok());
+
+    let mut counters = ProcessCounters {
+        minor_page_faults: fault(7),
+        major_page_faults: fault(9),
+        ..ProcessCounters::default()
+    };
+
+    let statuses = std::fs::read_dir(format!("/proc/{pid}/task"))
+        .map(|tasks| {
+            tasks
+                .flatten()
+                .filter_map(|task| std::fs::read_to_string(task.path().join("status")).ok())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    for status in statuses {
+        for line in status.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let slot = match key {
+                "voluntary_ctxt_switches" => &mut counters.voluntary_context_switches,
+                "nonvoluntary_ctxt_switches" => &mut counters.involuntary_context_switches,
+                _ => continue,
+            };
+            if let Ok(count) = value.trim().parse::<u64>() {
+                *slot = Some(slot.unwrap_or_default() + count);
+            }
+        }
+    }
+    counters.context_switches = counters
+        .voluntary_context_switches
+        .zip(counters.involuntary_context_switches)
+        .map(|(voluntary, involuntary)| voluntary + involuntary);
+
+    Some(counters)
+}
+
+/// macOS only has a combined context switch count, and counts page-ins
+/// (faults served from disk) rather than major faults as such.
+#[cfg(target_os = "macos")]
+pub fn collect_process_counters(pid: i32) -> Option<ProcessCounters> {
+    let mut info = std::mem::MaybeUninit::<libc::proc_taskinfo>::zeroed();
+    let size = std::mem::size_of::<libc::proc_taskinfo>() as libc::c_int;
+    let result = unsafe {
+        libc::proc_pidinfo(
+            pid,
+            libc::PROC_PIDTASKINFO,
+            0,
+            info.as_mut_ptr() as *mut libc::c_void,
+            size,
+        )
+    };
+    if result != size {
+        return None;
+    }
+    let info = unsafe { info.assume_init() };
+
+    let faults = info.pti_faults.max(0) as u64;
+    let pageins = info.pti_pageins.max(0) as u64;
+    Some(ProcessCounters {
+        voluntary_context_switches: None,
+        involuntary_context_switches: None,
+        context_switches: Some(info.pti_csw.max(0) as u64),
+        minor_page_faults: Some(faults.saturating_sub(pageins)),
+        major_page_faults: Some(pageins),
+    })
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn collect_process_counters(_pid: i32) -> Option<ProcessCounters> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+pub fn collect_resource_limits(pid: i32) -> Option<Vec<ResourceLimit>> {
+    let limits = std::fs::read_to_string(format!("/proc/{pid}/limits")).ok()?;
+    Some(parse_proc_limits(&limits))
+}
+
+/// macOS has no API for reading another process's limits.
+#[cfg(not(target_os = "linux"))]
+pub fn collect_resource_limits(_pid: i32) -> Option<Vec<ResourceLimit>> {
+    None
+}
+
+/// Parses `/proc/<pid>/limits`. Limit names contain spaces, so the columns
+/// are cut where the header's `Soft Limit`, `Hard Limit` and `Units` start.
+pub fn parse_proc_limits(text: &str) -> Vec<ResourceLimit> {
+    let mut lines = text.lines();
+    let Some(header) = lines.next() else {
+        return Vec::new();
+    };
+    let (Some(soft_at), Some(hard_at), Some(unit_at)) = (
+        header.find("Soft Limit"),
+        header.find("Hard Limit"),
+        header.find("Units"),
+    ) else {
+        return Vec::new();
+    };
+
+    lines
+        .filter_map(|line| {
+            let cell = |start: usize, end: usize| {
+                line.get(start.min(line.len())..end.min(line.len()))
+                    .unwrap_or_default()
+                    .trim()
+            };
+            let name = cell(0, soft_at);
+            if name.is_empty() {
+                return None;
+            }
+            let bound = |value: &str| match value {
+                "unlimited" => Some(None),
+                value => value.parse::<u64>().ok().map(Some),
+            };
+            let unit = cell(unit_at, line.len());
+            Some(ResourceLimit {
+                name: name.to_string(),
+                soft: bound(cell(soft_at, hard_at))?,
+                hard: bound(cell(hard_at, unit_at))?,
+                unit: (!unit.is_empty()).then(|| unit.to_string()),
+            })
+        })
+        .collect()
+}
+
+fn module_kind(path: &str) -> &'static str {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    let is_library = file_name.ends_with(".so")
+        || file_name.contains(".so.")
+        || file_name.ends_with(".dylib")
+        || file_name.ends_with(".node")
+        || path.contains(".framework/");
+
+    if is_library {
+        "library"
+    } else {
+        "file"
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn collect_loaded_modules(pid: i32) -> Result<Vec<LoadedModule>, String> {
+    let maps = std::fs::read_to_string(format!("/proc/{pid}/maps"))
+        .map_err(|error| format!("Failed to read memory maps for {pid}: {error}"))?;
+
+    let mut order = Vec::<String>::new();
+    let mut sizes = HashMap::<String, u64>::new();
+
+    for line in maps.lines() {
+        // address perms offset dev inode pathname
+        let mut columns = line.splitn(6, char::is_whitespace);
+        let Some(range) = columns.next() else {
+            continue;
+        };
+        let Some(path) = columns.nth(4).map(str::trim) else {
+            continue;
+        };
+        if !path.starts_with('/') {
+            continue;
+        }
+
+        let size = range
+            .split_once('-')
+            .and_then(|(start, end)| {
+                let start = u64::from_str_radix(start, 16).ok()?;
+                let end = u64::from_str_radix(end, 16).ok()?;
+                Some(end.saturating_sub(start))
+            })
+            .unwrap_or_default();
+
+        if !sizes.contains_key(path) {
+            order.push(path.to_string());
+        }
+        *sizes.entry(path.to_string()).or_default() += size;
+    }
+
+    Ok(order
+        .into_iter()
+        .map(|path| LoadedModule {
+            kind: module_kind(&path).to_string(),
+            mapped_bytes: sizes.get(&path).copied(),
+            path,
+        })
+        .collect())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn collect_loaded_modules(pid: i32) -> Result<Vec<LoadedModule>, String> {
+    // lsof reports mapped program text (the executable and dylibs) as "txt".
+    let output = crate::lsof::run_lsof(["-p", &pid.to_string()])?;
+    let processes = crate::lsof::parse_fields(&output);
+    let process = processes
+        .into_iter()
+        .find(|process| process.pid == pid)
+        .ok_or_else(|| format!("Process {pid} was not found"))?;
+
+    let mut seen = HashSet::new();
+    let modules = process
+        .files
+        .into_iter()
+        .filter_map(|file| {
+            let path = file.name.filter(|_| file.fd == "txt")?;
+            if !path.starts_with('/') || !seen.insert(path.clone()) {
+                return None;
+            }
+            Some(LoadedModule {
+                kind: module_kind(&path).to_string(),
+                mapped_bytes: None,
+                path,
+            })
+        })
+        .collect();
+
+    Ok(modules)
+}
+
+#[cfg(target_os = "linux")]
+const THREAD_SAMPLE_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+
+#[cfg(target_os = "linux")]
+fn linux_state_label(state: char) -> String {
+    match state {
+        'R' => "Run",
+        'S' => "Sleep",
+        'D' => "UninterruptibleDiskSleep",
+        'Z' => "Zombie",
+        'T' => "Stop",
+        't' => "Tracing",
+        'X' | 'x' => "Dead",
+        'I' => "Idle",
+        'P' => "Parked",
+        other => return other.to_string(),
+    }
+    .to_string()
+}
+
+/// Returns `(name, state, utime + stime ticks)` from a `/proc/.../stat` file.
+#[cfg(target_os = "linux")]
+fn read_stat_cpu_ticks(path: &str) -> Option<(String, char, u64)> {
+    let stat = std::fs::read_to_string(path).ok()?;
+
+    // The comm field is parenthesised and may itself contain spaces or ')'.
+    let open = stat.find('(')?;
+    let close = stat.rfind(')')?;
+    let name = stat.get(open + 1..close)?.to_string();
+
+    let fields = stat
+        .get(close + 1..)?
+        .split_whitespace()
+        .collect::<Vec<_>>();
+    let state = fields.first()?.chars().next()?;
+    let utime = fields.get(11)?.parse::<u64>().ok()?;
+    let stime = fields.get(12)?.parse::<u64>().ok()?;
+
+    Some((name, state, utime + stime))
+}
+
+#[cfg(target_os = "linux")]
+pub fn collect_threads(pid: i32) -> Result<Vec<ThreadInfo>, String> {
+    let task_dir = format!("/proc/{pid}/task");
+
+    let sample = || -> Result<HashMap<i32, (String, char, u64)>, String> {
+        let entries = std::fs::read_dir(&task_dir)
+            .map_err(|error| format!("Failed to read threads for {pid}: {error}"))?;
+
+        Ok(entries
+            .filter_map(|entry| {
+                let tid = entry.ok()?.file_name().to_str()?.parse::<i32>().ok()?;
+                let stat = read_stat_cpu_ticks(&format!("{task_dir}/{tid}/stat"))?;
+                Some((tid, stat))
+            })
+            .collect())
+    };
+
+    let started = std::time::Instant::now();
+    let first = sample()?;
+    std::thread::sleep(THREAD_SAMPLE_INTERVAL);
+    let second = sample()?;
+    let elapsed = started.elapsed().as_secs_f32();
+
+    let ticks_per_second = unsafe { libc::sysconf(libc::_SC_CLK_TCK) }.max(1) as f32;
+
+    let mut threads = second
+        .into_iter()
+        .map(|(tid, (name, state, ticks))| {
+            let previous = first.get(&tid).map(|(_, _, ticks)| *ticks).unwrap_or(ticks);
+            let busy_seconds = ticks.saturating_sub(previous) as f32 / ticks_per_second;
+
+            ThreadInfo {
+                tid,
+                name,
+                state: linux_state_label(state),
+                cpu_percent: busy_seconds / elapsed * 100.0,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    threads.sort_by(|a, b| {
+        b.cpu_percent
+            .partial_cmp(&a.cpu_percent)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.tid.cmp(&b.tid))
+    });
+
+    Ok(threads)
+}
+
+#[cfg(target_os = "macos")]
+pub fn collect_threads(pid: i32) -> Result<Vec<ThreadInfo>, String> {
+    // Not exported by the libc crate; see <sys/proc_info.h>.
+    const PROC_PIDLISTTHREADS: libc::c_int = 6;
+    const TH_USAGE_SCALE: f32 = 1000.0;
+
+    let mut handles = vec![0u64; 4096];
+    let written = unsafe {
+        libc::proc_pidinfo(
+            pid,
+            PROC_PIDLISTTHREADS,
+            0,
+            handles.as_mut_ptr() as *mut libc::c_void,
+            (handles.len() * std::mem::size_of::<u64>()) as libc::c_int,
+        )
+    };
+    if written <= 0 {
+        return Err(format!("Failed to list threads for {pid}"));
+    }
+    handles.truncate(written as usize / std::mem::size_of::<u64>());
+
+    let mut threads = handles
+        .into_iter()
+        .filter_map(|handle| {
+            let mut info = std::mem::MaybeUninit::<libc::proc_threadinfo>::zeroed();
+            let size = std::mem::size_of::<libc::proc_threadinfo>() as libc::c_int;
+            let result = unsafe {
+                libc::proc_pidinfo(
+                    pid,
+                    libc::PROC_PIDTHREADINFO,
+                    handle,
+                    info.as_mut_ptr() as *mut libc::c_void,
+                    size,
+                )
+            };
+            if result != size {
+                return None;
+            }
+            let info = unsafe { info.assume_init() };
+
+            let name = unsafe { std::ffi::CStr::from_ptr(info.pth_name.as_ptr()) }
+                .to_string_lossy()
+                .into_owned();
+            let state = match info.pth_run_state {
+                libc::TH_STATE_RUNNING => "Run",
+                libc::TH_STATE_STOPPED => "Stop",
+                libc::TH_STATE_WAITING => "Sleep",
+                libc::TH_STATE_UNINTERRUPTIBLE => "UninterruptibleDiskSleep",
+                libc::TH_STATE_HALTED => "Dead",
+                _ => "Unknown",
+            };
+
+            Some(ThreadInfo {
+                // Thread handles are 64-bit kernel ids; the low bits are
+                // only used to tell threads apart in the UI.
+                tid: handle as i32,
+                name,
+                state: state.to_string(),
+                cpu_percent: info.pth_cpu_usage as f32 / TH_USAGE_SCALE * 100.0,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    threads.sort_by(|a, b| {
+        b.cpu_percent
+            .partial_cmp(&a.cpu_percent)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.tid.cmp(&b.tid))
+    });
+
+    Ok(threads)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn collect_threads(_pid: i32) -> Result<Vec<ThreadInfo>, String> {
+    Err("Thread listing is not supported on this platform".to_string())
+}
+
+/// Each thread's state and, for sleeping ones, its `wchan`, grouped.
+/// Unlike `collect_threads` this takes a single look, so it's cheap enough
+/// for the details view.
+#[cfg(target_os = "linux")]
+pub fn collect_thread_waits(pid: i32) -> Option<Vec<ThreadWait>> {
+    let task_dir = format!("/proc/{pid}/task");
+    let mut groups = HashMap::<(String, Option<String>), u32>::new();
+    for entry in std::fs::read_dir(&task_dir).ok()? {
+        let Some(tid) = entry
+            .ok()
+            .and_then(|entry| entry.file_name().into_string().ok())
+        else {
+            continue;
+        };
+        let Some((_, state, _)) = read_stat_cpu_ticks(&format!("{task_dir}/{tid}/stat")) else {
+            continue;
+        };
+        // `0` for a running thread, or for every thread when kallsyms is
+        // restricted.
+        let wait_channel = std::fs::read_to_string(format!("{task_dir}/{tid}/wchan"))
+            .ok()
+            .map(|wchan| wchan.trim().to_string())
+            .filter(|wchan| !wchan.is_empty() && wchan != "0");
+        *groups
+            .entry((linux_state_label(state), wait_channel))
+            .or_default() += 1;
+    }
+    Some(sorted_waits(groups))
+}
+
+#[cfg(target_os = "macos")]
+pub fn collect_thread_waits(pid: i32) -> Option<Vec<ThreadWait>> {
+    let threads = collect_threads(pid).ok()?;
+    let mut groups = HashMap::<(String, Option<String>), u32>::new();
+    for thread in threads {
+        *groups.entry((thread.state, None)).or_default() += 1;
+    }
+    Some(sorted_waits(groups))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn collect_thread_waits(_pid: i32) -> Option<Vec<ThreadWait>> {
+    None
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn sorted_waits(groups: HashMap<(String, Option<String>), u32>) -> Vec<ThreadWait> {
+    let mut waits = groups
+        .into_iter()
+        .map(|((state, wait_channel), threads)| ThreadWait {
+            reason: wait_channel.as_deref().and_then(wait_reason),
+            state,
+            wait_channel,
+            threads,
+        })
+        .collect::<Vec<_>>();
+    waits.sort_by(|a, b| {
+        b.threads
+            .cmp(&a.threads)
+            .then_with(|| a.state.cmp(&b.state))
+            .then_with(|| a.wait_channel.cmp(&b.wait_channel))
+    });
+    waits
+}
+
+/// Names the common Linux wait channels. Symbol names shift between kernel
+/// versions (`futex_wait_queue_me` became `futex_wait_queue`), so these
+/// match on part of the name.
+pub fn wait_reason(wait_channel: &str) -> Option<&'static str> {
+    const REASONS: [(&str, &str); 22] = [
+        ("futex", "lock or condition variable (futex)"),
+        ("do_epoll_wait", "waiting for I/O on fds (epoll)"),
+        ("ep_poll", "waiting for I/O on fds (epoll)"),
+        ("do_select", "waiting for I/O on fds (select)"),
+        ("core_sys_select", "waiting for I/O on fds (select)"),
+        ("do_sys_poll", "waiting for I/O on fds (poll)"),
+        ("do_poll", "waiting for I/O on fds (poll)"),
+        ("inet_csk_accept", "waiting for a connection (accept)"),
+        ("sk_wait_data", "waiting for network data"),
+        ("tcp_", "waiting on a TCP socket"),
+        ("unix_stream", "waiting on a Unix socket"),
+        ("sock_", "waiting on a socket"),
+        ("pipe_", "waiting on a pipe"),
+        ("wait_woken", "waiting on a terminal or socket"),
+        ("n_tty_read", "waiting for terminal input"),
+        ("do_wait", "waiting for a child process to exit"),
+        ("hrtimer_nanosleep", "sleeping on a timer"),
+        ("do_nanosleep", "sleeping on a timer"),
+        ("io_schedule", "waiting for disk I/O"),
+        ("folio_wait", "waiting for disk I/O"),
+        ("jbd2", "waiting for the filesystem journal"),
+        ("do_signal_stop", "stopped by a signal"),
+    ];
+
+    REASONS
+        .iter()
+        .find(|(pattern, _)| wait_channel.contains(pattern))
+        .map(|(_, reason)| *reason)
+}
+
+/// Classifies an open file by its path the way `ls -l` would.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn path_kind(path: &str) -> &'static str {
+    use std::os::unix::fs::FileTypeExt;
+
+    match std::fs::metadata(path) {
+        Ok(metadata) if metadata.is_dir() => "directory",
+        Ok(metadata) if metadata.file_type().is_char_device() => "device",
+        Ok(metadata) if metadata.file_type().is_block_device() => "device",
+        Ok(metadata) if metadata.file_type().is_fifo() => "pipe",
+        Ok(metadata) if metadata.file_type().is_socket() => "socket",
+        _ => "file",
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn linux_fd_kind(target: &str) -> &'static str {
+    if target.starts_with("socket:") {
+        "socket"
+    } else if target.starts_with("pipe:") {
+        "pipe"
+    } else if target.starts_with("anon_inode:") {
+        "anon-inode"
+    } else if target.starts_with('/') {
+        path_kind(target)
+    } else {
+        "other"
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn count_open_file_handles(pid: i32) -> Option<u32> {
+    let entries = std::fs::read_dir(format!("/proc/{pid}/fd")).ok()?;
+    Some(entries.count() as u32)
+}
+
+#[cfg(target_os = "linux")]
+pub fn collect_open_files(pid: i32) -> Result<Vec<OpenFile>, String> {
+    let entries = std::fs::read_dir(format!("/proc/{pid}/fd"))
+        .map_err(|error| format!("Failed to read open files for {pid}: {error}"))?;
+
+    let mut files = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let fd = entry.file_name().to_str()?.parse::<i32>().ok()?;
+            // The fd can be closed between listing and reading the link.
+            let target = std::fs::read_link(entry.path()).ok()?;
+            let target = target.to_string_lossy().into_owned();
+            Some(OpenFile {
+                fd,
+                kind: linux_fd_kind(&target).to_string(),
+                path: Some(target),
+            })
+        })
+        .collect::<Vec<_>>();
+    files.sort_by_key(|file| file.fd);
+
+    Ok(files)
+}
+
+/// `collect_open_files` plus each fd's flags, offset and watch count, which
+/// covers the eventfds, inotify and epoll handles lsof mostly glosses over
+/// but that count toward the open files limit all the same.
+#[cfg(target_os = "linux")]
+pub fn collect_fd_table(pid: i32) -> Result<Vec<FileDescriptor>, String> {
+    Ok(collect_open_files(pid)?
+        .into_iter()
+        .map(|file| {
+            // Empty if the fd was closed since the listing.
+            let fdinfo = std::fs::read_to_string(format!("/proc/{pid}/fdinfo/{}", file.fd))
+                .unwrap_or_default();
+            let field = |name: &str| {
+                fdinfo.lines().find_map(|line| {
+                    let (key, value) = line.split_once(':')?;
+                    (key == name).then(|| value.trim())
+                })
+            };
+            let flags = field("flags").and_then(|flags| i32::from_str_radix(flags, 8).ok());
+            let watches = fdinfo
+                .lines()
+                .filter(|line| line.starts_with("inotify wd:") || line.starts_with("tfd:"))
+                .count();
+            let watched = file.path.as_deref().is_some_and(|path| {
+                path == "anon_inode:inotify" || path == "anon_inode:[eventpoll]"
+            });
+
+            FileDescriptor {
+                flags: flags.map(open_flag_names).unwrap_or_default(),
+                offset: field("pos").and_then(|pos| pos.parse().ok()),
+                watches: watched.then_some(watches),
+                file,
+            }
+        })
+        .collect())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn collect_fd_table(_pid: i32) -> Result<Vec<FileDescriptor>, String> {
+    Err("The file descriptor table is only available on Linux".to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn open_flag_names(flags: i32) -> Vec<&'static str> {
+    const FLAGS: [(i32, &str); 9] = [
+        (libc::O_APPEND, "O_APPEND"),
+        (libc::O_NONBLOCK, "O_NONBLOCK"),
+        (libc::O_DSYNC, "O_DSYNC"),
+        (libc::O_SYNC, "O_SYNC"),
+        (libc::O_DIRECT, "O_DIRECT"),
+        (libc::O_LARGEFILE, "O_LARGEFILE"),
+        (libc::O_NOATIME, "O_NOATIME"),
+        (libc::O_PATH, "O_PATH"),
+        (libc::O_CLOEXEC, "O_CLOEXEC"),
+    ];
+
+    let access = match flags & libc::O_ACCMODE {
+        libc::O_WRONLY => "O_WRONLY",
+        libc::O_RDWR => "O_RDWR",
+        _ => "O_RDONLY",
+    };
+    // O_SYNC includes the O_DSYNC bit, so only list O_DSYNC on its own.
+    let sync = flags & libc::O_SYNC == libc::O_SYNC;
+    std::iter::once(access)
+        .chain(FLAGS.iter().filter_map(|(bit, name)| {
+            let set = flags & bit == *bit && !(sync && *bit == libc::O_DSYNC);
+            set.then_some(*name)
+        }))
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn list_fds(pid: i32) -> Option<Vec<libc::proc_fdinfo>> {
+    // Room for fds opened between asking for the size and listing them,
+    // which the second call would otherwise cut off.
+    const HEADROOM: usize = 32;
+
+    let entry_size = std::mem::size_of::<libc::proc_fdinfo>();
+    // A null buffer asks for the size needed.
+    let needed =
+        unsafe { libc::proc_pidinfo(pid, libc::PROC_PIDLISTFDS, 0, std::ptr::null_mut(), 0) };
+    if needed <= 0 {
+        return None;
+    }
+
+    let mut fds = Vec::<libc::proc_fdinfo>::with_capacity(needed as usize / entry_size + HEADROOM);
+    let written = unsafe {
+        libc::proc_pidinfo(
+            pid,
+            libc::PROC_PIDLISTFDS,
+            0,
+            fds.as_mut_ptr() as *mut libc::c_void,
+            (fds.capacity() * entry_size) as libc::c_int,
+        )
+    };
+    if written <= 0 {
+        return None;
+    }
+    unsafe { fds.set_len(written as usize / entry_size) };
+    Some(fds)
+}
+
+#[cfg(target_os = "macos")]
+fn vnode_path(pid: i32, fd: i32) -> Option<String> {
+    // Not exported by the libc crate; see <sys/proc_info.h>. Only the path
+    // is read, so the leading proc_fileinfo and vnode_info are left opaque.
+    const PROC_PIDFDVNODEPATHINFO: libc::c_int = 2;
+    #[repr(C)]
+    struct VnodeFdInfoWithPath {
+        info: [u8; 176],
+        path: [libc::c_char; libc::PATH_MAX as usize],
+    }
+
+    let mut info = std::mem::MaybeUninit::<VnodeFdInfoWithPath>::zeroed();
+    let size = std::mem::size_of::<VnodeFdInfoWithPath>() as libc::c_int;
+    let written = unsafe {
+        libc::proc_pidfdinfo(
+            pid,
+            fd,
+            PROC_PIDFDVNODEPATHINFO,
+            info.as_mut_ptr() as *mut libc::c_void,
+            size,
+        )
+    };
+    if written != size {
+        return None;
+    }
+    let info = unsafe { info.assume_init() };
+    let path = unsafe { std::ffi::CStr::from_ptr(info.path.as_ptr()) };
+    Some(path.to_string_lossy().into_owned()).filter(|path| !path.is_empty())
+}
+
+#[cfg(target_os = "macos")]
+pub fn count_open_file_handles(pid: i32) -> Option<u32> {
+    list_fds(pid).map(|fds| fds.len() as u32)
+}
+
+#[cfg(target_os = "macos")]
+pub fn collect_open_files(pid: i32) -> Result<Vec<OpenFile>, String> {
+    let fds = list_fds(pid).ok_or_else(|| format!("Failed to list open files for {pid}"))?;
+
+    Ok(fds
+        .into_iter()
+        .map(|fd| {
+            let path = match fd.proc_fdtype as libc::c_int {
+                libc::PROX_FDTYPE_VNODE => vnode_path(pid, fd.proc_fd),
+                _ => None,
+            };
+            let kind = match fd.proc_fdtype as libc::c_int {
+                libc::PROX_FDTYPE_VNODE => path.as_deref().map_or("file", path_kind),
+                libc::PROX_FDTYPE_SOCKET => "socket",
+                libc::PROX_FDTYPE_PIPE => "pipe",
+                libc::PROX_FDTYPE_KQUEUE => "kqueue",
+                libc::PROX_FDTYPE_PSHM => "shared-memory",
+                libc::PROX_FDTYPE_PSEM => "semaphore",
+                libc::PROX_FDTYPE_FSEVENTS => "fsevents",
+                _ => "other",
+            };
+            OpenFile {
+                fd: fd.proc_fd,
+                kind: kind.to_string(),
+                path,
+            }
+        })
+        .collect())
+}
+
+/// Handles of every kind (files, registry keys, events, ...), which is what
+/// Task Manager's "Handles" column shows.
+#[cfg(windows)]
+pub fn count_open_file_handles(pid: i32) -> Option<u32> {
+    use windows_sys::Win32::System::Threading::{
+        GetProcessHandleCount, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    let handle = WindowsProcess::open(pid, PROCESS_QUERY_LIMITED_INFORMATION)?;
+    let mut count = 0u32;
+    (unsafe { GetProcessHandleCount(handle.0, &mut count) } != 0).then_some(count)
+}
+
+/// Elsewhere lsof is the only source; it also lists cwd and mapped files,
+/// which have no fd number and are skipped.
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+pub fn count_open_file_handles(pid: i32) -> Option<u32> {
+    collect_open_files(pid).ok().map(|files| files.len() as u32)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn collect_open_files(pid: i32) -> Result<Vec<OpenFile>, String> {
+    let output = crate::lsof::run_lsof(["-p", &pid.to_string()])?;
+    let processes = crate::lsof::parse_fields(&output);
+    let process = processes
+        .into_iter()
+        .find(|process| process.pid == pid)
+        .ok_or_else(|| format!("Process {pid} was not found"))?;
+
+    Ok(process
+        .files
+        .into_iter()
+        .filter_map(|file| {
+            Some(OpenFile {
+                fd: file.fd.parse().ok()?,
+                kind: file.file_type.unwrap_or_default().to_ascii_lowercase(),
+                path: file.name,
+            })
+        })
+        .collect())
+}
+
+/// An `OpenProcess` handle, closed on drop.
+#[cfg(windows)]
+struct WindowsProcess(windows_sys::Win32::Foundation::HANDLE);
+
+#[cfg(windows)]
+impl WindowsProcess {
+    fn open(
+        pid: i32,
+        access: windows_sys::Win32::System::Threading::PROCESS_ACCESS_RIGHTS,
+    ) -> Option<Self> {
+        let pid = u32::try_from(pid).ok()?;
+        let handle = unsafe { windows_sys::Win32::System::Threading::OpenProcess(access, 0, pid) };
+        (handle != 0).then_some(Self(handle))
+    }
+
+    fn read<T: Copy>(&self, address: usize) -> Option<T> {
+        let mut value = std::mem::MaybeUninit::<T>::uninit();
+        let mut read = 0usize;
+        let ok = unsafe {
+            windows_sys::Win32::System::Diagnostics::Debug::ReadProcessMemory(
+                self.0,
+                address as *const std::ffi::c_void,
+                value.as_mut_ptr() as *mut std::ffi::c_void,
+                std::mem::size_of::<T>(),
+                &mut read,
+            )
+        };
+        (ok != 0 && read == std::mem::size_of::<T>()).then(|| unsafe { value.assume_init() })
+    }
+}
+
+#[cfg(windows)]
+impl Drop for WindowsProcess {
+    fn drop(&mut self) {
+        unsafe { windows_sys::Win32::Foundation::CloseHandle(self.0) };
+    }
+}
+
+/// Working directory of `pid` where sysinfo can't report one. On Windows it
+/// is read out of the process's PEB (`ProcessParameters->CurrentDirectory`);
+/// elsewhere this is `None`.
+#[cfg(windows)]
+pub fn process_cwd(pid: i32) -> Option<String> {
+    use windows_sys::Win32::System::Threading::{
+        NtQueryInformationProcess, ProcessBasicInformation, PROCESS_BASIC_INFORMATION,
+        PROCESS_QUERY_INFORMATION, PROCESS_VM_READ,
+    };
+
+    // Offsets into the undocumented-but-stable PEB and
+    // RTL_USER_PROCESS_PARAMETERS layouts for this build's pointer width.
+    // A 32-bit process under WOW64 is read through its native PEB, which
+    // may lag behind a later chdir.
+    #[cfg(target_pointer_width = "64")]
+    const OFFSETS: (usize, usize) = (0x20, 0x38);
+    #[cfg(target_pointer_width = "32")]
+    const OFFSETS: (usize, usize) = (0x10, 0x24);
+    let (parameters_offset, current_directory_offset) = OFFSETS;
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct UnicodeString {
+        length: u16,
+        maximum_length: u16,
+        buffer: usize,
+    }
+
+    let process = WindowsProcess::open(pid, PROCESS_QUERY_INFORMATION | PROCESS_VM_READ)?;
+    let mut basic = std::mem::MaybeUninit::<PROCESS_BASIC_INFORMATION>::zeroed();
+    let status = unsafe {
+        NtQueryInformationProcess(
+            process.0,
+            ProcessBasicInformation,
+            basic.as_mut_ptr() as *mut std::ffi::c_void,
+            std::mem::size_of::<PROCESS_BASIC_INFORMATION>() as u32,
+            std::ptr::null_mut(),
+        )
+    };
+    if status < 0 {
+        return None;
+    }
+    let peb = unsafe { basic.assume_init() }.PebBaseAddress as usize;
+
+    let parameters = process.read::<usize>(peb + parameters_offset)?;
+    let directory = process.read::<UnicodeString>(parameters + current_directory_offset)?;
+    let mut units = vec![0u16; directory.length as usize / 2];
+    let mut read = 0usize;
+    let ok = unsafe {
+        windows_sys::Win32::System::Diagnostics::Debug::ReadProcessMemory(
+            process.0,
+            directory.buffer as *const std::ffi::c_void,
+            units.as_mut_ptr() as *mut std::ffi::c_void,
+            directory.length as usize,
+            &mut read,
+        )
+    };
+    units.truncate(read / 2);
+    if ok == 0 || units.is_empty() {
+        return None;
+    }
+
+    let path = String::from_utf16_lossy(&units);
+    // Windows keeps the trailing separator on the current directory.
+    Some(match path.trim_end_matches('\\') {
+        "" => path,
+        trimmed if trimmed.ends_with(':') => format!("{trimmed}\\"),
+        trimmed => trimmed.to_string(),
+    })
+}
+
+#[cfg(not(windows))]
+pub fn process_cwd(_pid: i32) -> Option<String> {
+    None
+}
+
+/// Windows has no chroot, so the closest analogue to a root directory is
+/// the volume the process is working on (`C:\`, `\\server\share\`).
+#[cfg(windows)]
+pub fn process_root(pid: i32) -> Option<String> {
+    let cwd = std::path::PathBuf::from(process_cwd(pid)?);
+    cwd.ancestors()
+        .last()
+        .map(|root| root.display().to_string())
+}
+
+#[cfg(not(windows))]
+pub fn process_root(_pid: i32) -> Option<String> {
+    None
+}
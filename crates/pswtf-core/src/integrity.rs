@@ -0,0 +1,123 @@
+//! Fingerprints for a process's executable: its SHA-256 and, on macOS, who
+//! signed it.
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+use sha2::{Digest, Sha256};
+
+use crate::model::{CodeSignature, ExecutableIntegrity};
+
+/// Hashing a browser-sized binary takes a noticeable fraction of a second,
+/// so results are kept per path until the file's size or mtime changes.
+type HashKey = (String, u64, Option<SystemTime>);
+
+/// Hashes nobody has asked for in this long are dropped, so executables
+/// that have come and gone don't pile up in a long-running app.
+const HASH_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+struct CachedHash {
+    sha256: String,
+    used: Instant,
+}
+
+static HASHES: Mutex<Option<HashMap<HashKey, CachedHash>>> = Mutex::new(None);
+
+pub fn executable_integrity(exe: &str) -> ExecutableIntegrity {
+    ExecutableIntegrity {
+        path: exe.to_string(),
+        sha256: sha256_file(exe),
+        signature: code_signature(exe),
+    }
+}
+
+pub fn sha256_file(path: &str) -> Option<String> {
+    let metadata = std::fs::metadata(path).ok()?;
+    let key = (path.to_string(), metadata.len(), metadata.modified().ok());
+    {
+        let mut guard = HASHES
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let hashes = guard.get_or_insert_with(HashMap::new);
+        // Older versions of this file can't come back, so they go too.
+        hashes.retain(|cached_key, cached| {
+            cached.used.elapsed() < HASH_CACHE_TTL && (cached_key.0 != key.0 || *cached_key == key)
+        });
+        if let Some(cached) = hashes.get_mut(&key) {
+            cached.used = Instant::now();
+            return Some(cached.sha256.clone());
+        }
+    }
+
+    let mut file = std::fs::File::open(path).ok()?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer).ok()?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    let hash = hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+
+    HASHES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get_or_insert_with(HashMap::new)
+        .insert(
+            key,
+            CachedHash {
+                sha256: hash.clone(),
+                used: Instant::now(),
+            },
+        );
+    Some(hash)
+}
+
+/// `codesign -dv` prints to stderr, and exits non-zero for unsigned code.
+#[cfg(target_os = "macos")]
+fn code_signature(exe: &str) -> Option<CodeSignature> {
+    let output = std::process::Command::new("codesign")
+        .args(["-dv", "--verbose=2", exe])
+        .output()
+        .ok()?;
+    Some(parse_codesign(&String::from_utf8_lossy(&output.stderr)))
+}
+
+#[cfg(not(target_os = "macos"))]
+fn code_signature(_exe: &str) -> Option<CodeSignature> {
+    None
+}
+
+/// Parses the `key=value` lines of `codesign -dv --verbose=2`. Unsigned
+/// binaries produce only "code object is not signed at all".
+pub fn parse_codesign(output: &str) -> CodeSignature {
+    let mut signature = CodeSignature::default();
+
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        match key {
+            "Identifier" => signature.identifier = Some(value.to_string()),
+            "TeamIdentifier" if value != "not set" => {
+                signature.team_id = Some(value.to_string());
+            }
+            "Authority" => signature.authorities.push(value.to_string()),
+            "Signature" if value == "adhoc" => signature.adhoc = true,
+            _ => {}
+        }
+    }
+
+    // Every signature, ad-hoc ones included, carries an identifier.
+    signature.signed = signature.identifier.is_some();
+    signature
+}
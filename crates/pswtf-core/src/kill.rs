@@ -0,0 +1,1135 @@
+use std::collections::{HashMap, HashSet};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+#[cfg(unix)]
+use nix::errno::Errno;
+#[cfg(unix)]
+use nix::sys::signal::{kill, killpg};
+#[cfg(unix)]
+use nix::unistd::Pid as UnixPid;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, PidExt, ProcessExt, ProcessStatus, System, SystemExt};
+
+#[cfg(unix)]
+pub use nix::sys::signal::Signal;
+
+/// Windows has no signals, so these name what pswtf does instead: `SIGTERM`
+/// asks the process's windows to close and `SIGKILL` terminates it outright.
+#[cfg(windows)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Signal {
+    SIGHUP,
+    SIGTERM,
+    SIGKILL,
+}
+
+#[cfg(windows)]
+impl Signal {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Signal::SIGHUP => "SIGHUP",
+            Signal::SIGTERM => "SIGTERM",
+            Signal::SIGKILL => "SIGKILL",
+        }
+    }
+}
+
+use crate::model::{
+    KillError, KillPrivilege, KillReport, KillRole, KilledProcess, KnownDaemon, PortInfo,
+    ProcessInfo, ReloadReport, SupervisedProcess, ZombieGroup,
+};
+use crate::process::current_user_id;
+use crate::protect::{ProtectionCheck, PROTECTED_REASON};
+use crate::supervisor::find_supervised;
+use crate::terminal::multiplexer_members;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MatchMode {
+    #[default]
+    Substring,
+    Exact,
+    Word,
+    Regex,
+    /// Shell-style `*`, `?` and `[...]`, matched against the whole name or
+    /// command line: `python3.*`, `*webpack*dev*`.
+    Glob,
+}
+
+/// Every mode compiles to a regex, so case-insensitive matching goes through
+/// the regex crate's Unicode simple case folding whichever mode is used:
+/// `Ärger` finds `ärger` and `Σ` finds both `σ` and `ς`, while `ß` stays
+/// distinct from `ss` everywhere.
+pub enum ProcessMatcher {
+    Substring(Regex),
+    Exact(Regex),
+    Regex(Regex),
+    Glob(Regex),
+}
+
+impl ProcessMatcher {
+    pub fn new(query: &str, mode: MatchMode) -> Result<Self, String> {
+        Self::with_case(query, mode, false)
+    }
+
+    pub fn with_case(query: &str, mode: MatchMode, case_sensitive: bool) -> Result<Self, String> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Err("Query cannot be empty".to_string());
+        }
+        let build = |pattern: &str, kind: &str| {
+            RegexBuilder::new(pattern)
+                .case_insensitive(!case_sensitive)
+                .build()
+                .map_err(|error| format!("Invalid {kind}: {error}"))
+        };
+
+        match mode {
+            MatchMode::Substring => build(&regex::escape(query), "query").map(Self::Substring),
+            MatchMode::Exact => {
+                build(&format!("^{}$", regex::escape(query)), "query").map(Self::Exact)
+            }
+            MatchMode::Word => {
+                build(&format!(r"\b{}\b", regex::escape(query)), "query").map(Self::Regex)
+            }
+            MatchMode::Regex => build(query, "regex").map(Self::Regex),
+            MatchMode::Glob => build(&glob_to_regex(query)?, "glob").map(Self::Glob),
+        }
+    }
+
+    pub fn matches_text(&self, text: &str) -> bool {
+        match self {
+            Self::Substring(regex)
+            | Self::Exact(regex)
+            | Self::Regex(regex)
+            | Self::Glob(regex) => regex.is_match(text),
+        }
+    }
+
+    pub fn matches(&self, process: &ProcessInfo) -> bool {
+        let whole_name = || {
+            // Compare whole names only: the process name or argv[0]'s basename.
+            let program = process
+                .cmd
+                .split_whitespace()
+                .next()
+                .map(|arg0| arg0.rsplit('/').next().unwrap_or(arg0));
+            self.matches_text(&process.name) || program.is_some_and(|p| self.matches_text(p))
+        };
+        match self {
+            Self::Exact(_) => whole_name(),
+            // Globs are anchored, so `python3.*` has to be tried against the
+            // program name and `*webpack*dev*` against the command line.
+            Self::Glob(_) => whole_name() || self.matches_text(&process.cmd),
+            _ => self.matches_text(&process.name) || self.matches_text(&process.cmd),
+        }
+    }
+}
+
+/// An anchored regex for a shell glob. `[!...]` negates a class like in
+/// `sh`; there is no `**` or brace expansion.
+fn glob_to_regex(glob: &str) -> Result<String, String> {
+    let mut regex = String::from("^");
+    let mut chars = glob.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            '[' => {
+                regex.push('[');
+                if chars.next_if(|next| matches!(next, '!' | '^')).is_some() {
+                    regex.push('^');
+                }
+                // A `]` right after the opening bracket is a literal.
+                if chars.next_if_eq(&']').is_some() {
+                    regex.push_str(r"\]");
+                }
+                loop {
+                    match chars.next() {
+                        Some(']') => break,
+                        Some(ch @ ('\\' | '[' | '&' | '~')) => {
+                            regex.push('\\');
+                            regex.push(ch);
+                        }
+                        Some(ch) => regex.push(ch),
+                        None => return Err(format!("Unclosed [ in glob: {glob}")),
+                    }
+                }
+                regex.push(']');
+            }
+            ch => regex.push_str(&regex::escape(&ch.to_string())),
+        }
+    }
+    regex.push('$');
+    Ok(regex)
+}
+
+#[derive(Debug, Clone)]
+pub struct MatchOptions {
+    pub match_mode: MatchMode,
+    pub include_children: bool,
+    pub only_current_user: bool,
+    /// Off by default: `node` also matches `Node`.
+    pub case_sensitive: bool,
+    /// Patterns, in `match_mode` like the query, for processes to spare
+    /// even when they match. Everything below a spared process is spared
+    /// too, so excluding `nvim` keeps its language servers alive.
+    pub exclude: Vec<String>,
+    /// Let matches include pswtf's own ancestors and descendants (its
+    /// WebView and other helpers). Ancestors stay protected regardless.
+    pub include_self: bool,
+}
+
+/// The processes a matching kill would target. Preview and kill both go
+/// through `find_matching_processes` so the two can never disagree.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchSet {
+    pub roots: Vec<ProcessInfo>,
+    pub descendants: Vec<ProcessInfo>,
+    /// Targets a process manager would restart after the kill.
+    pub supervised: Vec<SupervisedProcess>,
+    #[serde(skip)]
+    pub kill_order: Vec<i32>,
+}
+
+pub fn build_child_map(processes: &[ProcessInfo]) -> HashMap<i32, Vec<i32>> {
+    let mut child_map = HashMap::<i32, Vec<i32>>::new();
+
+    for process in processes {
+        if let Some(parent_pid) = process.parent_pid {
+            child_map.entry(parent_pid).or_default().push(process.pid);
+        }
+    }
+
+    child_map
+}
+
+pub fn collect_descendants(root_pid: i32, child_map: &HashMap<i32, Vec<i32>>, out: &mut Vec<i32>) {
+    if let Some(children) = child_map.get(&root_pid) {
+        for child_pid in children {
+            collect_descendants(*child_pid, child_map, out);
+            out.push(*child_pid);
+        }
+    }
+}
+
+/// pswtf's own PID plus every ancestor and descendant of it in `processes`.
+pub fn own_process_tree(processes: &[ProcessInfo]) -> HashSet<i32> {
+    let self_pid = std::process::id() as i32;
+    let parents = processes
+        .iter()
+        .map(|process| (process.pid, process.parent_pid))
+        .collect::<HashMap<_, _>>();
+
+    let mut tree = HashSet::from([self_pid]);
+    let mut current = parents.get(&self_pid).copied().flatten();
+    while let Some(pid) = current.filter(|pid| tree.insert(*pid)) {
+        current = parents.get(&pid).copied().flatten();
+    }
+
+    let mut descendants = Vec::new();
+    collect_descendants(self_pid, &build_child_map(processes), &mut descendants);
+    tree.extend(descendants);
+    tree
+}
+
+pub fn dedupe_pids(pids: Vec<i32>) -> Vec<i32> {
+    let mut seen = HashSet::new();
+    let mut deduped = Vec::new();
+
+    for pid in pids {
+        if seen.insert(pid) {
+            deduped.push(pid);
+        }
+    }
+
+    deduped
+}
+
+pub fn find_matching_processes(
+    processes: &[ProcessInfo],
+    query: &str,
+    options: &MatchOptions,
+) -> Result<MatchSet, String> {
+    // Build the matcher first so a malformed pattern never reaches the kill path.
+    let matcher = ProcessMatcher::with_case(query, options.match_mode, options.case_sensitive)?;
+    let exclusions = options
+        .exclude
+        .iter()
+        .filter(|pattern| !pattern.trim().is_empty())
+        .map(|pattern| {
+            ProcessMatcher::with_case(pattern, options.match_mode, options.case_sensitive)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut processes = processes.to_vec();
+    if !options.include_self {
+        // A broad query like "tauri" or "webkit" would otherwise reach the
+        // app's own helpers and take it down halfway through the kill.
+        let own_tree = own_process_tree(&processes);
+        processes.retain(|process| !own_tree.contains(&process.pid));
+    }
+    if options.only_current_user {
+        // Other users' processes would only come back as EPERM noise, so
+        // leave them out of both the matched roots and their descendants.
+        let current_uid = current_user_id();
+        processes.retain(|process| current_uid.is_none() || process.user_id == current_uid);
+    }
+    if !exclusions.is_empty() {
+        let child_map = build_child_map(&processes);
+        let mut spared = Vec::new();
+        for process in &processes {
+            if exclusions
+                .iter()
+                .any(|exclusion| exclusion.matches(process))
+            {
+                spared.push(process.pid);
+                collect_descendants(process.pid, &child_map, &mut spared);
+            }
+        }
+        let spared = spared.into_iter().collect::<HashSet<_>>();
+        processes.retain(|process| !spared.contains(&process.pid));
+    }
+    let child_map = build_child_map(&processes);
+
+    let root_pids = processes
+        .iter()
+        .filter(|process| matcher.matches(process))
+        .map(|process| process.pid)
+        .collect::<Vec<_>>();
+
+    let mut targets = Vec::<i32>::new();
+    for root_pid in &root_pids {
+        if options.include_children {
+            collect_descendants(*root_pid, &child_map, &mut targets);
+        }
+        targets.push(*root_pid);
+    }
+    let kill_order = dedupe_pids(targets);
+
+    let root_set = root_pids.iter().copied().collect::<HashSet<_>>();
+    let target_set = kill_order.iter().copied().collect::<HashSet<_>>();
+
+    let supervised = find_supervised(&processes, &kill_order);
+    let mut roots = Vec::new();
+    let mut descendants = Vec::new();
+    for process in processes {
+        if root_set.contains(&process.pid) {
+            roots.push(process);
+        } else if target_set.contains(&process.pid) {
+            descendants.push(process);
+        }
+    }
+
+    Ok(MatchSet {
+        roots,
+        descendants,
+        supervised,
+        kill_order,
+    })
+}
+
+pub fn resolve_signal(force: Option<bool>) -> Signal {
+    if force.unwrap_or(false) {
+        Signal::SIGKILL
+    } else {
+        Signal::SIGTERM
+    }
+}
+
+/// Signals every target except pswtf itself. Protected processes (see
+/// `protect::ProtectedList`) are never signaled and come back in `failed`.
+///
+/// `roots` are the targets that matched directly; every other target is
+/// reported as a descendant.
+///
+/// With `allow_elevation`, targets that fail with EPERM are retried in one
+/// batch behind the OS's admin prompt (see `elevated_kill`).
+pub fn perform_kill(
+    targets: Vec<i32>,
+    roots: &[i32],
+    signal: Signal,
+    allow_elevation: bool,
+) -> KillReport {
+    let self_pid = std::process::id() as i32;
+    let mut protection = ProtectionCheck::new();
+    let mut system = System::new();
+
+    let mut attempted = 0usize;
+    let mut killed = Vec::<KilledProcess>::new();
+    let mut failed = Vec::<KillError>::new();
+    let mut denied = Vec::<KilledProcess>::new();
+
+    for pid in targets {
+        if pid <= 0 || pid == self_pid {
+            continue;
+        }
+
+        attempted += 1;
+        if protection.is_protected(pid) {
+            failed.push(KillError {
+                pid,
+                error: PROTECTED_REASON.to_string(),
+            });
+            continue;
+        }
+
+        // Read before signaling: afterwards the process may already be gone.
+        let entry = describe_target(&mut system, pid, roots, signal);
+        match deliver(pid, signal) {
+            Ok(()) => killed.push(entry),
+            Err(DeliveryError::PermissionDenied) if allow_elevation => denied.push(entry),
+            Err(error) => failed.push(KillError {
+                pid,
+                error: error.to_string(),
+            }),
+        }
+    }
+
+    let mut privilege = KillPrivilege::Unprivileged;
+    if !denied.is_empty() {
+        let pids = denied.iter().map(|entry| entry.pid).collect::<Vec<_>>();
+        match elevated_kill(&pids, signal) {
+            Ok(()) => {
+                privilege = KillPrivilege::Elevated;
+                killed.extend(denied);
+            }
+            Err(error) => {
+                let outcomes = elevated_kill_failures(&pids, &error, |pid| {
+                    let pid = Pid::from_u32(pid as u32);
+                    system.refresh_process(pid)
+                        && system
+                            .process(pid)
+                            .is_some_and(|process| process.status() != ProcessStatus::Zombie)
+                });
+                for (entry, failure) in denied.into_iter().zip(outcomes) {
+                    match failure {
+                        None => {
+                            privilege = KillPrivilege::Elevated;
+                            killed.push(entry);
+                        }
+                        Some(reason) => failed.push(KillError {
+                            pid: entry.pid,
+                            error: format!(
+                                "{}; elevated retry failed: {reason}",
+                                DeliveryError::PermissionDenied
+                            ),
+                        }),
+                    }
+                }
+            }
+        }
+    }
+
+    KillReport {
+        matched: roots.len(),
+        attempted,
+        killed,
+        failed,
+        privilege,
+        still_running: Vec::new(),
+        supervised: Vec::new(),
+    }
+}
+
+/// Why `deliver` failed. Only permission errors are worth an elevated retry.
+enum DeliveryError {
+    PermissionDenied,
+    Failed(String),
+}
+
+impl std::fmt::Display for DeliveryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            #[cfg(unix)]
+            DeliveryError::PermissionDenied => write!(f, "{}", Errno::EPERM),
+            #[cfg(not(unix))]
+            DeliveryError::PermissionDenied => f.write_str("Access is denied"),
+            DeliveryError::Failed(error) => f.write_str(error),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn deliver(pid: i32, signal: Signal) -> Result<(), DeliveryError> {
+    kill(UnixPid::from_raw(pid), signal).map_err(|error| match error {
+        Errno::EPERM => DeliveryError::PermissionDenied,
+        error => DeliveryError::Failed(error.to_string()),
+    })
+}
+
+#[cfg(windows)]
+fn deliver(pid: i32, signal: Signal) -> Result<(), DeliveryError> {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{OpenProcess, TerminateProcess, PROCESS_TERMINATE};
+
+    match signal {
+        Signal::SIGKILL => {
+            let handle = unsafe { OpenProcess(PROCESS_TERMINATE, 0, pid as u32) };
+            if handle == 0 {
+                return Err(last_delivery_error());
+            }
+            let result = if unsafe { TerminateProcess(handle, 1) } == 0 {
+                Err(last_delivery_error())
+            } else {
+                Ok(())
+            };
+            unsafe { CloseHandle(handle) };
+            result
+        }
+        Signal::SIGTERM => close_windows(pid),
+        Signal::SIGHUP => Err(DeliveryError::Failed(
+            "SIGHUP has no Windows equivalent".to_string(),
+        )),
+    }
+}
+
+#[cfg(windows)]
+fn last_delivery_error() -> DeliveryError {
+    use windows_sys::Win32::Foundation::ERROR_ACCESS_DENIED;
+
+    let error = std::io::Error::last_os_error();
+    if error.raw_os_error() == Some(ERROR_ACCESS_DENIED as i32) {
+        DeliveryError::PermissionDenied
+    } else {
+        DeliveryError::Failed(error.to_string())
+    }
+}
+
+/// The graceful path, like `taskkill` without `/F`: posts WM_CLOSE to every
+/// top-level window the process owns, as if the user clicked close. Console
+/// and background processes have no windows and can only be force-killed.
+#[cfg(windows)]
+fn close_windows(pid: i32) -> Result<(), DeliveryError> {
+    use windows_sys::Win32::Foundation::{BOOL, HWND, LPARAM};
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        EnumWindows, GetWindowThreadProcessId, PostMessageW, WM_CLOSE,
+    };
+
+    struct Search {
+        pid: u32,
+        windows: Vec<HWND>,
+    }
+
+    unsafe extern "system" fn collect(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let search = &mut *(lparam as *mut Search);
+        let mut owner = 0u32;
+        GetWindowThreadProcessId(hwnd, &mut owner);
+        if owner == search.pid {
+            search.windows.push(hwnd);
+        }
+        1
+    }
+
+    let mut search = Search {
+        pid: pid as u32,
+        windows: Vec::new(),
+    };
+    unsafe { EnumWindows(Some(collect), &mut search as *mut Search as LPARAM) };
+    if search.windows.is_empty() {
+        return Err(DeliveryError::Failed(
+            "Process has no windows to close; force kill it instead".to_string(),
+        ));
+    }
+
+    for hwnd in search.windows {
+        unsafe { PostMessageW(hwnd, WM_CLOSE, 0, 0) };
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn deliver_to_group(pgid: i32, signal: Signal) -> Result<(), String> {
+    killpg(UnixPid::from_raw(pgid), signal).map_err(|error| error.to_string())
+}
+
+#[cfg(not(unix))]
+fn deliver_to_group(_pgid: i32, _signal: Signal) -> Result<(), String> {
+    Err("Process groups are not supported on this platform".to_string())
+}
+
+/// Records which of the killed processes will be respawned. `supervised`
+/// has to be worked out before the kill, while the targets still exist.
+fn with_supervised(mut report: KillReport, mut supervised: Vec<SupervisedProcess>) -> KillReport {
+    supervised.retain(|entry| report.killed.iter().any(|killed| killed.pid == entry.pid));
+    report.supervised = supervised;
+    report
+}
+
+fn describe_target(system: &mut System, pid: i32, roots: &[i32], signal: Signal) -> KilledProcess {
+    let sys_pid = Pid::from_u32(pid as u32);
+    system.refresh_process(sys_pid);
+    let (name, cmd) = system
+        .process(sys_pid)
+        .map(|process| (process.name().to_string(), process.cmd().join(" ")))
+        .unwrap_or_default();
+
+    KilledProcess {
+        pid,
+        name,
+        cmd,
+        signal: signal.as_str().to_string(),
+        role: if roots.contains(&pid) {
+            KillRole::Root
+        } else {
+            KillRole::Descendant
+        },
+    }
+}
+
+/// How often `verify_terminated` re-checks the targets while waiting.
+const VERIFY_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// `killed` only means the signal was delivered. Waits up to `timeout` for
+/// those PIDs to actually exit and moves the stragglers (say, a daemon that
+/// ignores SIGTERM) into `still_running`. Zombies count as gone: they've
+/// exited and are only waiting on their parent.
+pub fn verify_terminated(report: &mut KillReport, timeout: Duration) {
+    let deadline = Instant::now() + timeout;
+    let mut system = System::new();
+    let mut pending = report
+        .killed
+        .iter()
+        .map(|entry| entry.pid)
+        .collect::<Vec<_>>();
+
+    loop {
+        pending.retain(|pid| {
+            let pid = Pid::from_u32(*pid as u32);
+            system.refresh_process(pid)
+                && system
+                    .process(pid)
+                    .is_some_and(|process| process.status() != ProcessStatus::Zombie)
+        });
+        if pending.is_empty() || Instant::now() >= deadline {
+            break;
+        }
+        std::thread::sleep(VERIFY_POLL_INTERVAL.min(deadline - Instant::now()));
+    }
+
+    report.still_running = pending;
+}
+
+/// Signals `pids` as root, asking the user to authenticate first: through
+/// the standard admin dialog via `osascript` on macOS, and polkit's
+/// `pkexec` on Linux. All PIDs go in one `kill` call so there's one prompt.
+fn elevated_kill(pids: &[i32], signal: Signal) -> Result<(), String> {
+    let signal_name = signal.as_str().trim_start_matches("SIG");
+    let pid_list = pids
+        .iter()
+        .map(|pid| pid.to_string())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut command = if cfg!(target_os = "macos") {
+        let mut command = Command::new("osascript");
+        command.arg("-e").arg(format!(
+            "do shell script \"kill -{signal_name} {pid_list}\" with administrator privileges"
+        ));
+        command
+    } else if cfg!(target_os = "linux") {
+        let mut command = Command::new("pkexec");
+        command
+            .arg("/bin/kill")
+            .arg(format!("-{signal_name}"))
+            .args(pids.iter().map(|pid| pid.to_string()));
+        command
+    } else {
+        return Err("Elevation is not supported on this platform".to_string());
+    };
+
+    let output = command
+        .output()
+        .map_err(|error| format!("Failed to run {:?}: {error}", command.get_program()))?;
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    Err(if stderr.is_empty() {
+        format!("exited with {}", output.status)
+    } else {
+        stderr
+    })
+}
+
+/// Which of `pids` an elevated `kill` that exited with `error` didn't
+/// signal, one entry per PID with the reason. `kill` carries on past PIDs
+/// it can't signal and names each of them in its errors, so when any PID is
+/// named the rest were signaled. When none is, the prompt was dismissed or
+/// the helper didn't get as far as `kill`, and whatever `is_running` still
+/// finds alive failed.
+pub fn elevated_kill_failures(
+    pids: &[i32],
+    error: &str,
+    mut is_running: impl FnMut(i32) -> bool,
+) -> Vec<Option<String>> {
+    let names_pid = |line: &str, pid: i32| {
+        line.split(|c: char| !c.is_ascii_digit())
+            .any(|number| number == pid.to_string())
+    };
+    let line_naming = |pid: i32| error.lines().find(|line| names_pid(line, pid));
+    let kill_ran = pids.iter().any(|pid| line_naming(*pid).is_some());
+
+    pids.iter()
+        .map(|&pid| match line_naming(pid) {
+            Some(line) => Some(line.trim().to_string()),
+            None if kill_ran || !is_running(pid) => None,
+            None => Some(error.to_string()),
+        })
+        .collect()
+}
+
+pub fn kill_process_tree(
+    processes: &[ProcessInfo],
+    pid: i32,
+    include_children: bool,
+    force: Option<bool>,
+    allow_elevation: bool,
+) -> Result<KillReport, String> {
+    if pid <= 0 {
+        return Err("PID must be a positive integer".to_string());
+    }
+
+    if !processes.iter().any(|process| process.pid == pid) {
+        return Err(format!("Process {pid} was not found"));
+    }
+
+    let child_map = build_child_map(processes);
+
+    let mut targets = Vec::<i32>::new();
+    if include_children {
+        collect_descendants(pid, &child_map, &mut targets);
+    }
+    targets.push(pid);
+
+    let deduped = dedupe_pids(targets);
+    let supervised = find_supervised(processes, &deduped);
+
+    Ok(with_supervised(
+        perform_kill(deduped, &[pid], resolve_signal(force), allow_elevation),
+        supervised,
+    ))
+}
+
+/// Signals a whole process group in one `killpg` call, so pipeline stages
+/// and job-control children die together even after being reparented. The
+/// group is refused outright if it holds pswtf itself or anything protected,
+/// since `killpg` can't skip individual members.
+pub fn kill_process_group(
+    processes: &[ProcessInfo],
+    pgid: i32,
+    force: Option<bool>,
+) -> Result<KillReport, String> {
+    if pgid <= 0 {
+        return Err("Process group ID must be a positive integer".to_string());
+    }
+
+    let members = processes
+        .iter()
+        .filter(|process| process.pgid == Some(pgid))
+        .collect::<Vec<_>>();
+    if members.is_empty() {
+        return Err(format!("Process group {pgid} was not found"));
+    }
+
+    let self_pid = std::process::id() as i32;
+    let mut protection = ProtectionCheck::new();
+    if let Some(pid) = members
+        .iter()
+        .map(|process| process.pid)
+        .find(|pid| *pid == self_pid || protection.is_protected(*pid))
+    {
+        return Err(format!(
+            "Process group {pgid} contains protected process {pid}"
+        ));
+    }
+
+    let member_pids = members
+        .iter()
+        .map(|process| process.pid)
+        .collect::<Vec<_>>();
+    let supervised = find_supervised(processes, &member_pids);
+
+    // Every member is a direct target of the group signal.
+    let signal = resolve_signal(force);
+    let (killed, failed) = match deliver_to_group(pgid, signal) {
+        Ok(()) => (
+            members
+                .iter()
+                .map(|process| KilledProcess {
+                    pid: process.pid,
+                    name: process.name.clone(),
+                    cmd: process.cmd.clone(),
+                    signal: signal.as_str().to_string(),
+                    role: KillRole::Root,
+                })
+                .collect(),
+            Vec::new(),
+        ),
+        Err(error) => (
+            Vec::new(),
+            members
+                .iter()
+                .map(|process| KillError {
+                    pid: process.pid,
+                    error: error.to_string(),
+                })
+                .collect(),
+        ),
+    };
+
+    let report = KillReport {
+        matched: members.len(),
+        attempted: members.len(),
+        killed,
+        failed,
+        privilege: KillPrivilege::Unprivileged,
+        still_running: Vec::new(),
+        supervised: Vec::new(),
+    };
+    Ok(with_supervised(report, supervised))
+}
+
+/// Daemons whose SIGHUP handling is well known, keyed by process name.
+const KNOWN_DAEMONS: &[(&[&str], KnownDaemon)] = &[
+    (
+        &["nginx"],
+        KnownDaemon {
+            daemon: "nginx",
+            hot_reload: true,
+            note:
+                "Re-reads its config and gracefully replaces workers; send it to the master process",
+        },
+    ),
+    (
+        &["postgres", "postmaster"],
+        KnownDaemon {
+            daemon: "postgres",
+            hot_reload: true,
+            note: "Reloads postgresql.conf and pg_hba.conf; some settings still need a restart",
+        },
+    ),
+    (
+        &["sshd"],
+        KnownDaemon {
+            daemon: "sshd",
+            hot_reload: true,
+            note: "Re-executes itself with the new config; open sessions are unaffected",
+        },
+    ),
+    (
+        &["httpd", "apache2"],
+        KnownDaemon {
+            daemon: "apache",
+            hot_reload: false,
+            note:
+                "SIGHUP is a hard restart that drops in-flight requests; prefer a graceful restart",
+        },
+    ),
+];
+
+pub fn known_daemon(process_name: &str) -> Option<KnownDaemon> {
+    KNOWN_DAEMONS
+        .iter()
+        .find(|(names, _)| names.contains(&process_name))
+        .map(|(_, daemon)| daemon.clone())
+}
+
+/// Sends SIGHUP to `pid` alone (never its children) and says how the
+/// daemon is expected to react, if it's one pswtf recognizes.
+pub fn reload_process(processes: &[ProcessInfo], pid: i32) -> Result<ReloadReport, String> {
+    if pid <= 0 {
+        return Err("PID must be a positive integer".to_string());
+    }
+
+    let process = processes
+        .iter()
+        .find(|process| process.pid == pid)
+        .ok_or_else(|| format!("Process {pid} was not found"))?;
+
+    Ok(ReloadReport {
+        report: perform_kill(vec![pid], &[pid], Signal::SIGHUP, false),
+        daemon: known_daemon(&process.name),
+    })
+}
+
+/// Freezes `pid` with SIGSTOP, e.g. to let a volume eject without losing
+/// the process's state. `resume_process` undoes it.
+#[cfg(unix)]
+pub fn suspend_process(processes: &[ProcessInfo], pid: i32) -> Result<KillReport, String> {
+    signal_one(processes, pid, Signal::SIGSTOP)
+}
+
+#[cfg(unix)]
+pub fn resume_process(processes: &[ProcessInfo], pid: i32) -> Result<KillReport, String> {
+    signal_one(processes, pid, Signal::SIGCONT)
+}
+
+#[cfg(unix)]
+fn signal_one(processes: &[ProcessInfo], pid: i32, signal: Signal) -> Result<KillReport, String> {
+    if pid <= 0 {
+        return Err("PID must be a positive integer".to_string());
+    }
+    if !processes.iter().any(|process| process.pid == pid) {
+        return Err(format!("Process {pid} was not found"));
+    }
+
+    Ok(perform_kill(vec![pid], &[pid], signal, false))
+}
+
+#[cfg(not(unix))]
+pub fn suspend_process(_processes: &[ProcessInfo], _pid: i32) -> Result<KillReport, String> {
+    Err("Suspending processes is only supported on Unix".to_string())
+}
+
+#[cfg(not(unix))]
+pub fn resume_process(_processes: &[ProcessInfo], _pid: i32) -> Result<KillReport, String> {
+    Err("Suspending processes is only supported on Unix".to_string())
+}
+
+/// The zombies in `processes` grouped by the parent that isn't reaping
+/// them, worst offender first.
+pub fn list_zombies(processes: &[ProcessInfo]) -> Vec<ZombieGroup> {
+    let mut groups = HashMap::<Option<i32>, Vec<ProcessInfo>>::new();
+    for process in processes.iter().filter(|process| is_zombie(process)) {
+        groups
+            .entry(process.parent_pid)
+            .or_default()
+            .push(process.clone());
+    }
+
+    let mut groups = groups
+        .into_iter()
+        .map(|(parent_pid, mut zombies)| {
+            zombies.sort_by_key(|zombie| zombie.pid);
+            let parent = parent_pid
+                .and_then(|pid| processes.iter().find(|process| process.pid == pid))
+                .cloned();
+            ZombieGroup {
+                orphaned: parent.is_none() || parent_pid == Some(1),
+                parent_pid,
+                parent,
+                zombies,
+            }
+        })
+        .collect::<Vec<_>>();
+    groups.sort_by(|a, b| {
+        b.zombies
+            .len()
+            .cmp(&a.zombies.len())
+            .then_with(|| a.parent_pid.cmp(&b.parent_pid))
+    });
+    groups
+}
+
+fn is_zombie(process: &ProcessInfo) -> bool {
+    process.status == format!("{:?}", ProcessStatus::Zombie)
+}
+
+/// Sends SIGCHLD to a parent with unreaped zombies. A parent whose handler
+/// missed a wakeup, or that only reaps from its event loop, often collects
+/// them on the next one; one that never calls wait() has to be killed.
+#[cfg(unix)]
+pub fn signal_zombie_parent(processes: &[ProcessInfo], pid: i32) -> Result<KillReport, String> {
+    if pid <= 0 {
+        return Err("PID must be a positive integer".to_string());
+    }
+    if !processes.iter().any(|process| process.pid == pid) {
+        return Err(format!("Process {pid} was not found"));
+    }
+    if !processes
+        .iter()
+        .any(|process| process.parent_pid == Some(pid) && is_zombie(process))
+    {
+        return Err(format!("Process {pid} has no zombie children"));
+    }
+
+    Ok(perform_kill(vec![pid], &[pid], Signal::SIGCHLD, false))
+}
+
+#[cfg(not(unix))]
+pub fn signal_zombie_parent(_processes: &[ProcessInfo], _pid: i32) -> Result<KillReport, String> {
+    Err("Zombie processes only exist on Unix".to_string())
+}
+
+pub fn kill_matching(
+    processes: &[ProcessInfo],
+    query: &str,
+    options: &MatchOptions,
+    force: Option<bool>,
+    allow_elevation: bool,
+) -> Result<KillReport, String> {
+    let match_set = find_matching_processes(processes, query, options)?;
+    Ok(kill_match_set(match_set, force, allow_elevation))
+}
+
+/// Kills an already resolved `match_set`, e.g. one checked against an
+/// earlier preview first.
+pub fn kill_match_set(
+    match_set: MatchSet,
+    force: Option<bool>,
+    allow_elevation: bool,
+) -> KillReport {
+    if match_set.roots.is_empty() {
+        return KillReport {
+            matched: 0,
+            attempted: 0,
+            killed: Vec::new(),
+            failed: Vec::new(),
+            privilege: KillPrivilege::Unprivileged,
+            still_running: Vec::new(),
+            supervised: Vec::new(),
+        };
+    }
+
+    let roots = match_set
+        .roots
+        .iter()
+        .map(|process| process.pid)
+        .collect::<Vec<_>>();
+    with_supervised(
+        perform_kill(
+            match_set.kill_order,
+            &roots,
+            resolve_signal(force),
+            allow_elevation,
+        ),
+        match_set.supervised,
+    )
+}
+
+/// Kills every process holding `port` (any protocol), plus their children
+/// when `include_children` is set, as a single combined report.
+pub fn kill_port(
+    processes: &[ProcessInfo],
+    ports: &[PortInfo],
+    port: u16,
+    include_children: bool,
+    force: Option<bool>,
+    allow_elevation: bool,
+) -> Result<KillReport, String> {
+    let owners = dedupe_pids(
+        ports
+            .iter()
+            .filter(|entry| entry.port == port)
+            .filter_map(|entry| entry.pid)
+            .collect(),
+    );
+    if owners.is_empty() {
+        return Err(format!("No process is listening on port {port}"));
+    }
+
+    let child_map = build_child_map(processes);
+    let mut targets = Vec::<i32>::new();
+    for owner in &owners {
+        if include_children {
+            collect_descendants(*owner, &child_map, &mut targets);
+        }
+        targets.push(*owner);
+    }
+
+    let targets = dedupe_pids(targets);
+    let supervised = find_supervised(processes, &targets);
+
+    Ok(with_supervised(
+        perform_kill(targets, &owners, resolve_signal(force), allow_elevation),
+        supervised,
+    ))
+}
+
+/// Kills everything running in a tmux/screen session, or in one tmux
+/// window when `window_index` is set, leaving the pane shells alive.
+pub fn kill_multiplexer_session(
+    processes: &[ProcessInfo],
+    session: &str,
+    window_index: Option<u32>,
+    force: Option<bool>,
+    allow_elevation: bool,
+) -> Result<KillReport, String> {
+    let members = multiplexer_members(processes, session, window_index);
+    if members.is_empty() {
+        return Err(match window_index {
+            Some(index) => format!("Nothing is running in window {index} of session {session}"),
+            None => format!("Nothing is running in session {session}"),
+        });
+    }
+
+    // Whatever each pane shell started directly is a root; the rest are
+    // its descendants, which go first.
+    let roots = members
+        .iter()
+        .filter(|process| {
+            process.parent_pid == process.multiplexer.as_ref().map(|pane| pane.pane_pid)
+        })
+        .map(|process| process.pid)
+        .collect::<Vec<_>>();
+    let child_map = build_child_map(processes);
+    let mut targets = Vec::<i32>::new();
+    for root in &roots {
+        collect_descendants(*root, &child_map, &mut targets);
+        targets.push(*root);
+    }
+    let targets = dedupe_pids(targets);
+    let supervised = find_supervised(processes, &targets);
+
+    Ok(with_supervised(
+        perform_kill(targets, &roots, resolve_signal(force), allow_elevation),
+        supervised,
+    ))
+}
+
+/// Kills everything attached to the controlling terminal `tty` (`pts/3`,
+/// or `/dev/ttys003` with the prefix), shell included, along with anything
+/// those processes started since, so a terminal window's jobs go as a unit.
+/// pswtf's own process tree is left alone, which makes running it from the
+/// terminal being cleaned up safe.
+pub fn kill_by_tty(
+    processes: &[ProcessInfo],
+    tty: &str,
+    force: Option<bool>,
+    allow_elevation: bool,
+) -> Result<KillReport, String> {
+    let tty = tty.trim();
+    let tty = tty.strip_prefix("/dev/").unwrap_or(tty);
+    if tty.is_empty() {
+        return Err("TTY must not be empty".to_string());
+    }
+
+    let own_tree = own_process_tree(processes);
+    let members = processes
+        .iter()
+        .filter(|process| process.tty.as_deref() == Some(tty))
+        .filter(|process| !own_tree.contains(&process.pid))
+        .map(|process| (process.pid, process.parent_pid))
+        .collect::<HashMap<_, _>>();
+    if members.is_empty() {
+        return Err(format!("Nothing is running on {tty}"));
+    }
+
+    // Members whose parent isn't on the terminal (the login shell, usually)
+    // are the roots; children that detached from it still go with them.
+    let mut roots = members
+        .iter()
+        .filter(|(_, parent)| parent.map_or(true, |parent| !members.contains_key(&parent)))
+        .map(|(pid, _)| *pid)
+        .collect::<Vec<_>>();
+    roots.sort_unstable();
+    let child_map = build_child_map(processes);
+    let mut targets = Vec::<i32>::new();
+    for root in &roots {
+        collect_descendants(*root, &child_map, &mut targets);
+        targets.push(*root);
+    }
+    targets.retain(|pid| !own_tree.contains(pid));
+    let targets = dedupe_pids(targets);
+    let supervised = find_supervised(processes, &targets);
+
+    Ok(with_supervised(
+        perform_kill(targets, &roots, resolve_signal(force), allow_elevation),
+        supervised,
+    ))
+}
@@ -0,0 +1,30 @@
+//! Process, port and kill logic shared by the pswtf desktop app and CLI.
+
+pub mod apps;
+pub mod cgroup;
+pub mod container;
+pub mod desktop;
+pub mod dns;
+pub mod forward;
+pub mod fuser;
+pub mod inspect;
+pub mod integrity;
+pub mod kill;
+pub mod lsof;
+pub mod memmap;
+pub mod model;
+pub mod ports;
+pub mod priority;
+pub mod probe;
+pub mod process;
+pub mod profile;
+pub mod protect;
+pub mod query;
+pub mod service;
+pub mod shell;
+pub mod supervisor;
+pub mod system;
+pub mod terminal;
+pub mod wsl;
+
+pub use model::*;
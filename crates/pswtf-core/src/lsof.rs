@@ -0,0 +1,110 @@
+//! lsof's `-F` field output: one `<code><value>` line per field, a `p` line
+//! opening each process set and an `f` line each file set within it. Unlike
+//! the column listing, nothing is truncated or space-separated, so command
+//! names like "Google Chrome Helper" and paths with spaces come through intact.
+
+use std::process::Command;
+
+/// Fields requested from lsof: PID, command, fd, type, device, protocol,
+/// name, and TCP/socket state (`T` lines also carry queue sizes we ignore).
+pub const FIELDS: &str = "pcftdPnT";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LsofProcess {
+    pub pid: i32,
+    pub command: String,
+    pub files: Vec<LsofFile>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LsofFile {
+    /// `3`, `cwd`, `txt`, `mem`, ...
+    pub fd: String,
+    /// `IPv4`, `IPv6`, `unix`, `REG`, `DIR`, ...
+    pub file_type: Option<String>,
+    /// Device numbers, or the kernel socket address for `unix` files.
+    pub device: Option<String>,
+    /// `TCP` or `UDP` for network files.
+    pub protocol: Option<String>,
+    pub name: Option<String>,
+    /// From `TST=`: `LISTEN`, `ESTABLISHED`, `CONNECTED`, ...
+    pub state: Option<String>,
+}
+
+/// Parses `lsof -F` output. Fields before the first `p` line and unknown
+/// field codes are skipped, so any `-F` field selection can be fed in.
+pub fn parse_fields(output: &str) -> Vec<LsofProcess> {
+    let mut processes = Vec::<LsofProcess>::new();
+
+    for line in output.lines() {
+        let mut chars = line.chars();
+        let Some(code) = chars.next() else {
+            continue;
+        };
+        let value = chars.as_str();
+
+        if code == 'p' {
+            if let Ok(pid) = value.parse() {
+                processes.push(LsofProcess {
+                    pid,
+                    ..LsofProcess::default()
+                });
+            }
+            continue;
+        }
+        let Some(process) = processes.last_mut() else {
+            continue;
+        };
+
+        match code {
+            'c' => process.command = value.to_string(),
+            'f' => process.files.push(LsofFile {
+                fd: value.to_string(),
+                ..LsofFile::default()
+            }),
+            _ => {
+                let Some(file) = process.files.last_mut() else {
+                    continue;
+                };
+                let value = Some(value.to_string());
+                match code {
+                    't' => file.file_type = value,
+                    'd' => file.device = value,
+                    'P' => file.protocol = value,
+                    'n' => file.name = value,
+                    'T' => {
+                        if let Some(state) = line[1..].strip_prefix("ST=") {
+                            file.state = Some(state.to_string());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    processes
+}
+
+/// Runs `lsof -nP -F <FIELDS>` with `args` appended and returns stdout.
+/// Exit status 1 only means a selector matched nothing, so it isn't an error.
+pub fn run_lsof<I, S>(args: I) -> Result<String, String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<std::ffi::OsStr>,
+{
+    let output = Command::new("lsof")
+        .args(["-nP", "-F", FIELDS])
+        .args(args)
+        .output()
+        .map_err(|error| format!("Failed to run lsof: {error}"))?;
+
+    if !matches!(output.status.code(), Some(0 | 1)) {
+        return Err(format!(
+            "lsof exited with status {:?}",
+            output.status.code()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -0,0 +1,146 @@
+//! A process's memory map, region by region: `/proc/<pid>/smaps` on Linux
+//! and `vmmap` on macOS.
+
+use crate::model::MemoryRegion;
+
+#[cfg(target_os = "linux")]
+pub fn collect_memory_maps(pid: i32) -> Result<Vec<MemoryRegion>, String> {
+    let smaps = std::fs::read_to_string(format!("/proc/{pid}/smaps"))
+        .map_err(|error| format!("Failed to read memory maps for {pid}: {error}"))?;
+    Ok(parse_smaps(&smaps))
+}
+
+/// vmmap needs the same user as the target (or root) and refuses
+/// processes under System Integrity Protection.
+#[cfg(target_os = "macos")]
+pub fn collect_memory_maps(pid: i32) -> Result<Vec<MemoryRegion>, String> {
+    let output = std::process::Command::new("vmmap")
+        .args(["-interleaved", &pid.to_string()])
+        .output()
+        .map_err(|error| format!("Failed to run vmmap: {error}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(if stderr.is_empty() {
+            format!("vmmap exited with {}", output.status)
+        } else {
+            stderr
+        });
+    }
+    Ok(parse_vmmap(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn collect_memory_maps(_pid: i32) -> Result<Vec<MemoryRegion>, String> {
+    Err("Memory maps are not supported on this platform".to_string())
+}
+
+/// Each region is a `start-end perms offset dev inode [path]` line followed
+/// by `Key: value kB` lines, of which only `Rss` and `Swap` are kept.
+pub fn parse_smaps(text: &str) -> Vec<MemoryRegion> {
+    let mut regions = Vec::<MemoryRegion>::new();
+
+    for line in text.lines() {
+        if let Some(region) = smaps_header(line) {
+            regions.push(region);
+            continue;
+        }
+        let (Some(region), Some((key, value))) = (regions.last_mut(), line.split_once(':')) else {
+            continue;
+        };
+        let slot = match key {
+            "Rss" => &mut region.resident_bytes,
+            "Swap" => &mut region.swapped_bytes,
+            _ => continue,
+        };
+        *slot = value
+            .split_whitespace()
+            .next()
+            .and_then(|kb| kb.parse::<u64>().ok())
+            .map(|kb| kb.saturating_mul(1024));
+    }
+
+    regions
+}
+
+fn smaps_header(line: &str) -> Option<MemoryRegion> {
+    let mut columns = line.splitn(6, char::is_whitespace);
+    let (start, end) = parse_range(columns.next()?)?;
+    let permissions = columns.next()?;
+    // offset, device and inode.
+    columns.nth(2)?;
+    let path = columns
+        .next()
+        .map(str::trim)
+        .filter(|path| !path.is_empty());
+
+    Some(MemoryRegion {
+        start: format!("{start:#x}"),
+        end: format!("{end:#x}"),
+        size_bytes: end.saturating_sub(start),
+        permissions: permissions.to_string(),
+        file_backed: path.is_some_and(|path| path.starts_with('/')),
+        path: path.map(str::to_string),
+        resident_bytes: None,
+        swapped_bytes: None,
+    })
+}
+
+/// Reads the region lines of `vmmap -interleaved`:
+///
+/// `__TEXT  102a1c000-102a20000  [ 16K 16K 0K 0K] r-x/r-x SM=COW  /usr/bin/sleep`
+///
+/// i.e. region type, range, `[virtual resident dirty swapped]`, current and
+/// maximum protection, share mode and the file or a description. Headers,
+/// summaries and anything else without that shape are skipped.
+pub fn parse_vmmap(text: &str) -> Vec<MemoryRegion> {
+    text.lines().filter_map(vmmap_region).collect()
+}
+
+fn vmmap_region(line: &str) -> Option<MemoryRegion> {
+    let (head, rest) = line.split_once('[')?;
+    let (sizes, tail) = rest.split_once(']')?;
+    let (start, end) = parse_range(head.split_whitespace().last()?)?;
+
+    let sizes = sizes
+        .split_whitespace()
+        .map(parse_vmmap_size)
+        .collect::<Vec<_>>();
+    let mut tail = tail.split_whitespace();
+    let protection = tail.next()?.split('/').next()?;
+    let share_mode = tail.next()?.strip_prefix("SM=")?;
+    let detail = tail.collect::<Vec<_>>().join(" ");
+
+    let shared = matches!(share_mode, "SHM" | "ALI" | "S/A");
+    Some(MemoryRegion {
+        start: format!("{start:#x}"),
+        end: format!("{end:#x}"),
+        size_bytes: end.saturating_sub(start),
+        permissions: format!("{protection}{}", if shared { 's' } else { 'p' }),
+        file_backed: detail.starts_with('/'),
+        path: (!detail.is_empty()).then_some(detail),
+        resident_bytes: sizes.get(1).copied().flatten(),
+        swapped_bytes: sizes.get(3).copied().flatten(),
+    })
+}
+
+fn parse_range(range: &str) -> Option<(u64, u64)> {
+    let (start, end) = range.split_once('-')?;
+    Some((
+        u64::from_str_radix(start, 16).ok()?,
+        u64::from_str_radix(end, 16).ok()?,
+    ))
+}
+
+/// `0K`, `16K`, `8192K`, `1.5M`, `2G`.
+fn parse_vmmap_size(size: &str) -> Option<u64> {
+    let (number, multiplier) = match size.char_indices().last()? {
+        (index, 'K') => (&size[..index], 1024f64),
+        (index, 'M') => (&size[..index], 1024f64.powi(2)),
+        (index, 'G') => (&size[..index], 1024f64.powi(3)),
+        _ => (size, 1.0),
+    };
+    number
+        .parse::<f64>()
+        .ok()
+        .map(|number| (number * multiplier) as u64)
+}
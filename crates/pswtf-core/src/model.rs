@@ -0,0 +1,762 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessInfo {
+    pub pid: i32,
+    pub parent_pid: Option<i32>,
+    /// Process group and session IDs; `None` where the OS won't say.
+    pub pgid: Option<i32>,
+    pub sid: Option<i32>,
+    /// Controlling terminal, e.g. `pts/3` or `ttys003`.
+    pub tty: Option<String>,
+    pub user_id: Option<u32>,
+    pub user: Option<String>,
+    pub name: String,
+    pub exe: Option<String>,
+    /// The executable was deleted or replaced after the process started,
+    /// e.g. a daemon still running the version from before an upgrade.
+    pub exe_outdated: bool,
+    pub cmd: String,
+    pub status: String,
+    /// Nice value, from -20 (most favourable) to 19; Unix only.
+    pub nice: Option<i32>,
+    pub cpu_percent: f32,
+    /// Cumulative CPU time in user space and in the kernel, in seconds. A
+    /// busy process that is mostly system time is usually stuck in syscalls
+    /// (polling, I/O thrash) rather than computing.
+    pub user_cpu_seconds: Option<f64>,
+    pub system_cpu_seconds: Option<f64>,
+    pub memory_bytes: u64,
+    pub virtual_memory_bytes: u64,
+    pub read_bytes: u64,
+    pub written_bytes: u64,
+    /// Disk throughput since the previous sample; `None` on a process's
+    /// first sample and wherever there is no previous one to compare with.
+    pub read_bytes_per_sec: Option<f64>,
+    pub written_bytes_per_sec: Option<f64>,
+    pub run_time_seconds: u64,
+    pub container: Option<ContainerInfo>,
+    pub multiplexer: Option<MultiplexerPane>,
+}
+
+/// The tmux pane or screen window a process was started from.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiplexerPane {
+    /// `tmux` or `screen`.
+    pub kind: &'static str,
+    pub session: String,
+    /// Window and pane are tmux-only; screen doesn't report them.
+    pub window_index: Option<u32>,
+    pub window_name: Option<String>,
+    pub pane_index: Option<u32>,
+    /// The pane's shell, i.e. the process tmux or screen started directly.
+    pub pane_pid: i32,
+}
+
+/// The container a process runs in: `docker`, `podman` or `kubernetes` on
+/// Linux, or `docker-desktop` for Docker Desktop's own processes on macOS.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerInfo {
+    pub runtime: &'static str,
+    pub id: Option<String>,
+    pub name: Option<String>,
+}
+
+/// What the executable on disk actually is, to tell a lookalike (say
+/// `kernel_task ` with a trailing space) from the real thing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutableIntegrity {
+    pub path: String,
+    /// Lowercase hex; `None` when the file can't be read.
+    pub sha256: Option<String>,
+    /// macOS only.
+    pub signature: Option<CodeSignature>,
+}
+
+/// The parts of `codesign -dv` worth showing.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeSignature {
+    pub signed: bool,
+    /// Signed without a certificate, as local builds are.
+    pub adhoc: bool,
+    pub identifier: Option<String>,
+    pub team_id: Option<String>,
+    /// The certificate chain, leaf first.
+    pub authorities: Vec<String>,
+}
+
+/// A Linux process's cgroup and the effective limits on it. Every limit is
+/// `None` when nothing up the hierarchy sets one.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CgroupInfo {
+    /// Relative to the cgroupfs root, e.g. `/system.slice/nginx.service`.
+    pub path: String,
+    /// 1 or 2.
+    pub version: u8,
+    pub memory_limit_bytes: Option<u64>,
+    pub memory_usage_bytes: Option<u64>,
+    /// CPU quota as a number of cores, e.g. 1.5.
+    pub cpu_limit_cores: Option<f64>,
+    pub pids_limit: Option<u64>,
+    /// Times the OOM killer fired inside this cgroup.
+    pub oom_kills: Option<u64>,
+    /// Scheduler periods in which the CPU quota ran out.
+    pub throttled_periods: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessSnapshot {
+    pub collected_at_epoch_ms: u128,
+    pub process_count: usize,
+    pub processes: Vec<ProcessInfo>,
+    /// How `cpu_percent` in `processes` is scaled.
+    pub cpu_normalization: CpuNormalization,
+    /// What `PerCore` divides by; lets the frontend convert either way.
+    pub logical_core_count: usize,
+    /// With `group_by: "app"`, `processes` clustered by application.
+    pub groups: Option<Vec<AppGroup>>,
+}
+
+/// Processes that belong to one application, with their combined cost.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppGroup {
+    /// Stable across refreshes: the bundle path, else the root PID.
+    pub key: String,
+    pub name: String,
+    /// macOS only.
+    pub bundle_id: Option<String>,
+    pub bundle_path: Option<String>,
+    pub pids: Vec<i32>,
+    pub cpu_percent: f32,
+    /// Summed RSS, so shared pages count once per process.
+    pub memory_bytes: u64,
+}
+
+/// The bare minimum for a live mini-view that polls several times a second.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopProcess {
+    pub pid: i32,
+    pub name: String,
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+}
+
+/// A process in both snapshots of a diff whose CPU or memory moved by at
+/// least the diff's thresholds.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessChange {
+    pub pid: i32,
+    pub name: String,
+    pub cmd: String,
+    pub cpu_before: f32,
+    pub cpu_after: f32,
+    pub memory_before_bytes: u64,
+    pub memory_after_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessDiff {
+    pub added: Vec<ProcessInfo>,
+    pub removed: Vec<ProcessInfo>,
+    pub changed: Vec<ProcessChange>,
+}
+
+/// The Linux I/O scheduling class used by `ionice`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IoPriorityClass {
+    /// Never set: the kernel derives a best-effort level from the nice value.
+    None,
+    /// Served before everyone else; setting it needs root.
+    RealTime,
+    BestEffort,
+    /// Only gets disk time when nothing else wants it.
+    Idle,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IoPriority {
+    pub class: IoPriorityClass,
+    /// 0 (highest) to 7 within `RealTime` and `BestEffort`.
+    pub level: Option<u8>,
+}
+
+/// sysinfo reports process CPU as a share of one core, so a busy
+/// multi-threaded process goes well past 100%.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CpuNormalization {
+    /// As sysinfo reports it: 100% is one core, up to 100% times the core count.
+    #[default]
+    Raw,
+    /// Divided by the logical core count, so 100% is the whole machine.
+    PerCore,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessDetails {
+    pub process: ProcessInfo,
+    pub open_file_handles: Option<u32>,
+    pub cwd: Option<String>,
+    pub root: Option<String>,
+    pub open_sockets: Option<Vec<SocketInfo>>,
+    pub memory: Option<MemoryBreakdown>,
+    pub counters: Option<ProcessCounters>,
+    /// Linux only.
+    pub limits: Option<Vec<ResourceLimit>>,
+    /// What the threads are doing, most common first; Linux and macOS.
+    pub waits: Option<Vec<ThreadWait>>,
+    /// Linux only; `None` elsewhere or when the process can't be read.
+    pub io_priority: Option<IoPriority>,
+    /// Linux only.
+    pub cgroup: Option<CgroupInfo>,
+    pub integrity: Option<ExecutableIntegrity>,
+    pub children: Vec<ProcessInfo>,
+    /// What SIGHUP does to this process, when pswtf knows.
+    pub reload: Option<KnownDaemon>,
+    /// The service manager entry that will restart this process if killed.
+    pub service: Option<ServiceUnit>,
+    /// The process manager that respawns this process, if any.
+    pub supervisor: Option<SupervisedProcess>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceUnit {
+    /// `systemd` or `launchd`.
+    pub manager: &'static str,
+    /// The systemd unit name or launchd job label.
+    pub unit: String,
+    /// A per-user unit (`systemctl --user`, launchd's `gui/<uid>` domain)
+    /// rather than a system one.
+    pub user: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryBreakdown {
+    pub resident_bytes: u64,
+    pub shared_bytes: Option<u64>,
+    pub swapped_bytes: Option<u64>,
+    pub unique_bytes: Option<u64>,
+}
+
+/// Lifetime scheduler and paging counters. Lots of involuntary switches
+/// (CPU contention) or major faults (paging from disk) explain a process
+/// that is slow without using much CPU.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessCounters {
+    /// Linux only: switches where the process gave up the CPU to wait.
+    pub voluntary_context_switches: Option<u64>,
+    /// Linux only: switches where the scheduler took the CPU away.
+    pub involuntary_context_switches: Option<u64>,
+    pub context_switches: Option<u64>,
+    pub minor_page_faults: Option<u64>,
+    pub major_page_faults: Option<u64>,
+}
+
+/// Threads of one process that are in the same state, waiting on the same
+/// thing.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreadWait {
+    /// `Run`, `Sleep`, `UninterruptibleDiskSleep` and so on.
+    pub state: String,
+    /// The kernel function a sleeping thread is blocked in (Linux `wchan`,
+    /// e.g. `futex_wait_queue` or `do_epoll_wait`). Missing on macOS and
+    /// where the kernel hides symbol names.
+    pub wait_channel: Option<String>,
+    /// A plain-language guess at what `wait_channel` means, e.g. `lock or
+    /// condition variable (futex)`.
+    pub reason: Option<&'static str>,
+    pub threads: u32,
+}
+
+/// One rlimit as the kernel applies it to the process, which can differ
+/// from what `ulimit` says in your shell. `None` means unlimited.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceLimit {
+    /// The kernel's label, e.g. `Max open files`.
+    pub name: String,
+    pub soft: Option<u64>,
+    pub hard: Option<u64>,
+    /// `bytes`, `files`, `seconds` and so on; some limits have none.
+    pub unit: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SocketInfo {
+    pub protocol: String,
+    pub local_address: String,
+    pub local_port: u16,
+    pub remote_address: Option<String>,
+    pub remote_port: Option<u16>,
+    pub state: Option<String>,
+}
+
+/// Who can reach a listener, judged from its bind address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PortExposure {
+    /// Bound to 127.0.0.0/8 or `::1`; only this machine can connect.
+    Loopback,
+    /// Bound to one specific non-loopback interface address.
+    Lan,
+    /// Bound to the wildcard address (`0.0.0.0`, `::`, `*`).
+    All,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortInfo {
+    pub protocol: String,
+    pub local_address: String,
+    pub exposure: PortExposure,
+    pub port: u16,
+    /// Well-known name for `port` (`postgres`, `redis`), user overrides first.
+    pub service: Option<String>,
+    pub state: Option<String>,
+    pub pid: Option<i32>,
+    pub process_name: Option<String>,
+    /// Set when the listener is Docker forwarding a published port.
+    pub docker: Option<DockerPortMapping>,
+    /// Set when the listener is a tunnel (kubectl, ssh, cloud-sql-proxy).
+    pub forward: Option<PortForward>,
+    pub origin: PortOrigin,
+}
+
+/// Which side of a WSL setup a listener lives on. Everything is `Local`
+/// outside WSL.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PortOrigin {
+    #[default]
+    Local,
+    /// Seen from inside WSL: a listener on the Windows host.
+    WindowsHost,
+    /// Seen from Windows: a listener inside the default WSL distro.
+    Wsl,
+}
+
+/// An established (or connected UDP) socket and the process holding it.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionInfo {
+    pub protocol: String,
+    pub local_address: String,
+    pub local_port: u16,
+    pub remote_address: String,
+    pub remote_port: u16,
+    /// Reverse-DNS name of `remote_address`; only filled in on request.
+    pub remote_host: Option<String>,
+    pub state: Option<String>,
+    pub pid: Option<i32>,
+    pub process_name: Option<String>,
+}
+
+/// What answered a `probe_port` connection on loopback.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortProbe {
+    pub port: u16,
+    /// The loopback address that accepted, `127.0.0.1` or `::1`.
+    pub address: Option<String>,
+    pub connected: bool,
+    pub connect_ms: Option<u64>,
+    /// `http`, `tls`, `ssh`, `redis`, `mysql`, `smtp/ftp`, or `unknown` for
+    /// a listener that replied with something else. `None` when it
+    /// accepted but never said anything, which is typical of a hung process.
+    pub protocol: Option<&'static str>,
+    pub banner: Option<String>,
+    pub error: Option<String>,
+}
+
+/// A Unix domain socket held open by a process.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnixSocketInfo {
+    /// Filesystem path, `@name` for Linux abstract sockets; `None` for
+    /// unnamed ends such as `socketpair` or accepted connections.
+    pub path: Option<String>,
+    /// `STREAM`, `DGRAM` or `SEQPACKET` where lsof reports it.
+    pub socket_type: Option<String>,
+    /// `LISTEN` or `CONNECTED` where lsof reports it.
+    pub state: Option<String>,
+    pub pid: Option<i32>,
+    pub process_name: Option<String>,
+    /// The processes on the other end of a connected socket.
+    pub peers: Vec<UnixSocketPeer>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnixSocketPeer {
+    pub pid: i32,
+    pub process_name: String,
+}
+
+/// Where a tunnel's local port actually leads.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortForward {
+    pub tool: &'static str,
+    /// `svc/api` for kubectl, `host:port` for ssh, the instance connection
+    /// name for cloud-sql-proxy.
+    pub target: String,
+    pub namespace: Option<String>,
+    pub remote_port: Option<u16>,
+    /// The host an ssh tunnel goes through.
+    pub via: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DockerPortMapping {
+    pub container_id: String,
+    pub container_name: String,
+    pub container_port: u16,
+    /// The container's main process, with its PID as the Docker host sees
+    /// it (on Docker Desktop that's the VM, not this machine).
+    pub process_pid: Option<i32>,
+    pub process_command: Option<String>,
+}
+
+/// How a process holds a path, after fuser's `c`/`r`/`e`/`m` flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FileUseKind {
+    Open,
+    Cwd,
+    Root,
+    /// The running program. lsof also reports loaded libraries as program
+    /// text, so outside Linux those land here too.
+    Executable,
+    Mapped,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileUse {
+    pub kind: FileUseKind,
+    /// Only for `Open`.
+    pub fd: Option<i32>,
+    pub path: String,
+}
+
+/// A process holding something at or under a path, like a line of
+/// `fuser -v`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileUser {
+    pub pid: i32,
+    pub name: String,
+    pub uses: Vec<FileUse>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadedModule {
+    pub path: String,
+    pub kind: String,
+    pub mapped_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenFile {
+    pub fd: i32,
+    /// `file`, `directory`, `socket`, `pipe`, `device`, ...
+    pub kind: String,
+    /// The file's path, or on Linux the kernel's label for pathless files
+    /// (`socket:[81234]`, `anon_inode:[eventfd]`).
+    pub path: Option<String>,
+}
+
+/// What `profile::sample_process` saw: the functions threads were in most
+/// often over `duration_ms`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessProfile {
+    pub pid: i32,
+    pub duration_ms: u64,
+    /// Thread stacks captured; each sample yields one per thread.
+    pub samples: u64,
+    /// Hottest first.
+    pub frames: Vec<HotFrame>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StackFrame {
+    /// `??` where the symbol couldn't be resolved.
+    pub function: String,
+    /// The library or binary, or `kernel` for kernel frames; `None` when
+    /// the sampler doesn't say.
+    pub module: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotFrame {
+    #[serde(flatten)]
+    pub frame: StackFrame,
+    /// Stacks with this frame on top, i.e. time spent in the function itself.
+    pub self_samples: u64,
+    /// Stacks with this frame anywhere in them; not available from macOS.
+    pub total_samples: Option<u64>,
+}
+
+/// One mapping in a process's address space.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryRegion {
+    /// `0x`-prefixed hex; addresses past 2^53 don't survive a JSON number.
+    pub start: String,
+    pub end: String,
+    pub size_bytes: u64,
+    /// `r`, `w`, `x` or `-` each, then `p`rivate or `s`hared, e.g. `rw-p`.
+    pub permissions: String,
+    /// The mapped file, or a label for anonymous memory: `[heap]` and
+    /// `[stack]` on Linux, vmmap's description (`MALLOC_SMALL` zones and
+    /// the like) on macOS.
+    pub path: Option<String>,
+    pub file_backed: bool,
+    pub resident_bytes: Option<u64>,
+    pub swapped_bytes: Option<u64>,
+}
+
+/// An open fd with what `/proc/<pid>/fdinfo` adds to the link target.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDescriptor {
+    #[serde(flatten)]
+    pub file: OpenFile,
+    /// The access mode first, then any other open flags that are set, e.g.
+    /// `["O_RDWR", "O_NONBLOCK", "O_CLOEXEC"]`.
+    pub flags: Vec<&'static str>,
+    pub offset: Option<u64>,
+    /// Watches on an inotify handle or fds registered with an epoll one.
+    pub watches: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreadInfo {
+    pub tid: i32,
+    pub name: String,
+    pub state: String,
+    pub cpu_percent: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CpuCoreInfo {
+    pub name: String,
+    pub cpu_percent: f32,
+    pub frequency_mhz: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkInterfaceInfo {
+    pub name: String,
+    pub mac_address: String,
+    pub received_bytes: u64,
+    pub transmitted_bytes: u64,
+    pub packets_received: u64,
+    pub packets_transmitted: u64,
+    pub errors_received: u64,
+    pub errors_transmitted: u64,
+    pub received_bytes_per_sec: Option<f64>,
+    pub transmitted_bytes_per_sec: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskInfo {
+    pub name: String,
+    pub mount_point: String,
+    pub file_system: String,
+    pub kind: String,
+    pub is_removable: bool,
+    pub total_bytes: u64,
+    pub available_bytes: u64,
+    pub read_bytes: Option<u64>,
+    pub written_bytes: Option<u64>,
+    pub read_bytes_per_sec: Option<f64>,
+    pub written_bytes_per_sec: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SensorReading {
+    pub label: String,
+    pub temperature_celsius: f32,
+    pub max_celsius: f32,
+    pub critical_celsius: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatteryInfo {
+    pub percent: f32,
+    pub state: String,
+    pub time_remaining_minutes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnergyImpact {
+    pub pid: i32,
+    pub name: Option<String>,
+    pub energy_impact: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadAverage {
+    pub one: f64,
+    pub five: f64,
+    pub fifteen: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemOverview {
+    pub total_memory_bytes: u64,
+    pub used_memory_bytes: u64,
+    pub available_memory_bytes: u64,
+    pub total_swap_bytes: u64,
+    pub used_swap_bytes: u64,
+    pub cpu_count: usize,
+    pub cpu_percent: f32,
+    pub load_average: LoadAverage,
+    pub uptime_seconds: u64,
+    pub boot_time_epoch_seconds: u64,
+    pub battery: Option<BatteryInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KillError {
+    pub pid: i32,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum KillRole {
+    /// Matched the query, PID or port directly.
+    Root,
+    /// Swept up as a child of a root.
+    Descendant,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KilledProcess {
+    pub pid: i32,
+    /// As seen just before signaling; empty if the process couldn't be read.
+    pub name: String,
+    pub cmd: String,
+    pub signal: String,
+    pub role: KillRole,
+}
+
+/// Whether any target needed an elevated (admin/root) retry to be signaled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum KillPrivilege {
+    #[default]
+    Unprivileged,
+    Elevated,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KillReport {
+    pub matched: usize,
+    pub attempted: usize,
+    pub killed: Vec<KilledProcess>,
+    pub failed: Vec<KillError>,
+    #[serde(default)]
+    pub privilege: KillPrivilege,
+    /// PIDs from `killed` that were still alive once `verify_terminated`
+    /// gave up waiting. Empty when the kill wasn't verified.
+    #[serde(default)]
+    pub still_running: Vec<i32>,
+    /// Killed processes a process manager will respawn.
+    #[serde(default)]
+    pub supervised: Vec<SupervisedProcess>,
+}
+
+/// A process whose process manager (pm2, supervisord, ...) restarts it
+/// when it dies, so killing it alone won't stick.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupervisedProcess {
+    pub pid: i32,
+    pub manager: String,
+    pub manager_pid: i32,
+    /// The manager's own command that stops this process for good, when
+    /// pswtf can work it out.
+    pub stop_command: Option<Vec<String>>,
+}
+
+/// How a recognized daemon treats SIGHUP.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KnownDaemon {
+    pub daemon: &'static str,
+    pub hot_reload: bool,
+    pub note: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReloadReport {
+    pub report: KillReport,
+    /// `None` for processes pswtf has no SIGHUP knowledge about; plenty of
+    /// programs simply exit on it.
+    pub daemon: Option<KnownDaemon>,
+}
+
+/// Zombies that share a parent. A zombie has already exited, so signaling it
+/// does nothing; it goes away once the parent calls wait(), or once the
+/// parent dies and init adopts and reaps it.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZombieGroup {
+    pub parent_pid: Option<i32>,
+    /// `None` when the parent isn't in the process table any more.
+    pub parent: Option<ProcessInfo>,
+    pub zombies: Vec<ProcessInfo>,
+    /// The parent is gone or is init itself, so there is no parent worth
+    /// nudging or killing.
+    pub orphaned: bool,
+}
+
+/// One pass of the sampler: everything that depends on two refreshes being
+/// spaced apart (CPU percentages) comes from here rather than a fresh System.
+pub struct ProcessSample {
+    pub collected_at_epoch_ms: u128,
+    pub processes: Vec<ProcessInfo>,
+    pub cpu_percent: f32,
+    pub cpu_cores: Vec<CpuCoreInfo>,
+}
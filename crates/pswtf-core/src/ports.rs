@@ -0,0 +1,731 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::RwLock;
+
+use crate::lsof::{parse_fields, run_lsof, LsofFile, LsofProcess};
+use crate::model::{
+    ConnectionInfo, PortExposure, PortInfo, PortOrigin, SocketInfo, UnixSocketInfo, UnixSocketPeer,
+};
+
+/// Labels for ports people actually meet in local development: the IANA
+/// assignments plus de facto defaults of common dev tools.
+const WELL_KNOWN_SERVICES: &[(u16, &str)] = &[
+    (21, "ftp"),
+    (22, "ssh"),
+    (25, "smtp"),
+    (53, "dns"),
+    (80, "http"),
+    (110, "pop3"),
+    (123, "ntp"),
+    (143, "imap"),
+    (443, "https"),
+    (445, "smb"),
+    (465, "smtps"),
+    (587, "submission"),
+    (631, "ipp"),
+    (993, "imaps"),
+    (995, "pop3s"),
+    (1080, "socks"),
+    (1433, "mssql"),
+    (1521, "oracle"),
+    (1883, "mqtt"),
+    (2049, "nfs"),
+    (2181, "zookeeper"),
+    (2375, "docker"),
+    (2376, "docker-tls"),
+    (2379, "etcd"),
+    (3000, "dev-server"),
+    (3128, "http-proxy"),
+    (3306, "mysql"),
+    (3389, "rdp"),
+    (4200, "angular"),
+    (4222, "nats"),
+    (4317, "otlp-grpc"),
+    (4318, "otlp-http"),
+    (5173, "vite"),
+    (5353, "mdns"),
+    (5432, "postgres"),
+    (5601, "kibana"),
+    (5672, "amqp"),
+    (5900, "vnc"),
+    (5984, "couchdb"),
+    (6379, "redis"),
+    (6443, "kubernetes-api"),
+    (7474, "neo4j"),
+    (8000, "http-alt"),
+    (8080, "http-alt"),
+    (8086, "influxdb"),
+    (8443, "https-alt"),
+    (8500, "consul"),
+    (8888, "jupyter"),
+    (9000, "php-fpm"),
+    (9042, "cassandra"),
+    (9090, "prometheus"),
+    (9092, "kafka"),
+    (9100, "node-exporter"),
+    (9200, "elasticsearch"),
+    (9229, "node-inspector"),
+    (11211, "memcached"),
+    (15672, "rabbitmq-mgmt"),
+    (16686, "jaeger"),
+    (27017, "mongodb"),
+];
+
+static SERVICE_NAME_OVERRIDES: RwLock<Option<HashMap<u16, String>>> = RwLock::new(None);
+
+/// User-defined labels that take precedence over the built-in table.
+pub fn service_name_overrides() -> HashMap<u16, String> {
+    SERVICE_NAME_OVERRIDES
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+        .unwrap_or_default()
+}
+
+pub fn set_service_name_overrides(overrides: HashMap<u16, String>) {
+    *SERVICE_NAME_OVERRIDES
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(overrides);
+}
+
+pub fn service_name(port: u16) -> Option<String> {
+    if let Some(name) = SERVICE_NAME_OVERRIDES
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .as_ref()
+        .and_then(|overrides| overrides.get(&port))
+    {
+        return Some(name.clone());
+    }
+
+    WELL_KNOWN_SERVICES
+        .binary_search_by_key(&port, |(known, _)| *known)
+        .ok()
+        .map(|index| WELL_KNOWN_SERVICES[index].1.to_string())
+}
+
+/// Classifies a bind address as printed by lsof (brackets already stripped).
+pub fn port_exposure(address: &str) -> PortExposure {
+    match address {
+        "*" | "0.0.0.0" | "::" => PortExposure::All,
+        "localhost" | "::1" => PortExposure::Loopback,
+        _ if address.starts_with("127.") || address.starts_with("::ffff:127.") => {
+            PortExposure::Loopback
+        }
+        _ => PortExposure::Lan,
+    }
+}
+
+fn parse_address_port(text: &str) -> Option<(String, u16)> {
+    let text = text.trim();
+    let separator = text.rfind(':')?;
+    let (address, port_text) = text.split_at(separator);
+    let port = port_text.trim_start_matches(':').parse::<u16>().ok()?;
+
+    let normalized_address = address.trim_matches(|c| c == '[' || c == ']').to_string();
+    let normalized = if normalized_address.is_empty() {
+        "*".to_string()
+    } else {
+        normalized_address
+    };
+
+    Some((normalized, port))
+}
+
+fn parse_endpoint(endpoint: &str) -> Option<(String, u16)> {
+    parse_address_port(endpoint.split("->").next()?.trim())
+}
+
+fn parse_remote_endpoint(endpoint: &str) -> Option<(String, u16)> {
+    parse_address_port(endpoint.split("->").nth(1)?.trim())
+}
+
+/// The network endpoint(s) of an lsof `TCP`/`UDP` file, or `None` for
+/// anything else.
+pub fn socket_from_lsof(file: &LsofFile) -> Option<SocketInfo> {
+    let protocol = file.protocol.as_deref()?.to_ascii_uppercase();
+    let endpoint = file.name.as_deref()?;
+    let (local_address, local_port) = parse_endpoint(endpoint)?;
+    let remote = parse_remote_endpoint(endpoint);
+
+    Some(SocketInfo {
+        protocol,
+        local_address,
+        local_port,
+        remote_address: remote.as_ref().map(|(address, _)| address.clone()),
+        remote_port: remote.map(|(_, port)| port),
+        state: file.state.clone(),
+    })
+}
+
+/// Every `(command, pid, socket)` in parsed lsof output.
+fn lsof_sockets(processes: &[LsofProcess]) -> impl Iterator<Item = (&str, i32, SocketInfo)> {
+    processes.iter().flat_map(|process| {
+        process.files.iter().filter_map(|file| {
+            Some((
+                process.command.as_str(),
+                process.pid,
+                socket_from_lsof(file)?,
+            ))
+        })
+    })
+}
+
+/// Narrows a `collect_ports` result; unset fields match everything.
+#[derive(Debug, Default)]
+pub struct PortFilter {
+    /// `TCP` or `UDP`, case-insensitive.
+    pub protocol: Option<String>,
+    /// Inclusive on both ends.
+    pub port_range: Option<(u16, u16)>,
+    pub pid: Option<i32>,
+    /// lsof's state name (`LISTEN`), case-insensitive.
+    pub state: Option<String>,
+}
+
+impl PortFilter {
+    pub fn matches(&self, port: &PortInfo) -> bool {
+        if let Some(protocol) = &self.protocol {
+            if !port.protocol.eq_ignore_ascii_case(protocol.trim()) {
+                return false;
+            }
+        }
+
+        if let Some((start, end)) = self.port_range {
+            if port.port < start || port.port > end {
+                return false;
+            }
+        }
+
+        // PIDs from the other side of WSL belong to a different process table.
+        if self.pid.is_some() && (port.pid != self.pid || port.origin != PortOrigin::Local) {
+            return false;
+        }
+
+        if let Some(state) = &self.state {
+            if !port
+                .state
+                .as_deref()
+                .is_some_and(|current| current.eq_ignore_ascii_case(state.trim()))
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+fn listening_port(
+    protocol: String,
+    local_address: String,
+    port: u16,
+    state: Option<String>,
+    pid: Option<i32>,
+    process_name: &str,
+) -> PortInfo {
+    PortInfo {
+        protocol,
+        exposure: port_exposure(&local_address),
+        local_address,
+        service: service_name(port),
+        port,
+        state,
+        pid,
+        process_name: Some(process_name.to_string()),
+        docker: None,
+        forward: None,
+        origin: PortOrigin::Local,
+    }
+}
+
+/// Listening ports from `lsof -F` output, deduplicated and sorted by port.
+pub fn ports_from_lsof(output: &str) -> Vec<PortInfo> {
+    let processes = parse_fields(output);
+    let ports = lsof_sockets(&processes)
+        .map(|(process_name, pid, socket)| {
+            listening_port(
+                socket.protocol,
+                socket.local_address,
+                socket.local_port,
+                socket.state,
+                Some(pid),
+                process_name,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    dedupe_and_sort_ports(ports)
+}
+
+/// Listening sockets from FreeBSD's `sockstat -46l`:
+/// `USER COMMAND PID FD PROTO LOCAL-ADDRESS FOREIGN-ADDRESS`. Columns are
+/// read from both ends so a command name with spaces stays whole.
+pub fn ports_from_sockstat(output: &str) -> Vec<PortInfo> {
+    let ports = output
+        .lines()
+        .filter_map(|line| {
+            let columns = line.split_whitespace().collect::<Vec<_>>();
+            let count = columns.len();
+            if count < 7 || columns[0] == "USER" {
+                return None;
+            }
+
+            let pid = columns[count - 5].parse::<i32>().ok()?;
+            let protocol = columns[count - 3].trim_end_matches(['4', '6']);
+            let (local_address, port) = parse_address_port(columns[count - 2])?;
+            let state = (protocol == "tcp").then(|| "LISTEN".to_string());
+            Some(listening_port(
+                protocol.to_ascii_uppercase(),
+                local_address,
+                port,
+                state,
+                Some(pid),
+                &columns[1..count - 5].join(" "),
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    dedupe_and_sort_ports(ports)
+}
+
+/// Bound sockets from OpenBSD's `fstat`, which prints e.g.
+/// `root sshd 8812 3* internet6 stream tcp 0xffff800000b1e000 *:22`.
+/// fstat has no listen state, so TCP sockets without a peer (`<--`/`-->`)
+/// count as listeners.
+pub fn ports_from_fstat(output: &str) -> Vec<PortInfo> {
+    let ports = output
+        .lines()
+        .filter_map(|line| {
+            let columns = line.split_whitespace().collect::<Vec<_>>();
+            let family = columns
+                .iter()
+                .position(|column| matches!(*column, "internet" | "internet6"))?;
+            if family < 4 || columns.len() < family + 5 {
+                return None;
+            }
+            let endpoint = &columns[family + 4..];
+            if endpoint.iter().any(|token| matches!(*token, "<--" | "-->")) {
+                return None;
+            }
+
+            let pid = columns[2].parse::<i32>().ok()?;
+            let protocol = columns[family + 2];
+            if !matches!(protocol, "tcp" | "udp") {
+                return None;
+            }
+            let (local_address, port) = parse_address_port(endpoint[0])?;
+            let state = (protocol == "tcp").then(|| "LISTEN".to_string());
+            Some(listening_port(
+                protocol.to_ascii_uppercase(),
+                local_address,
+                port,
+                state,
+                Some(pid),
+                columns[1],
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    dedupe_and_sort_ports(ports)
+}
+
+/// Listeners from Windows' `netstat -ano`, named through `tasklist /FO CSV
+/// /NH` output since netstat only prints PIDs.
+pub fn ports_from_netstat(netstat: &str, tasklist: &str) -> Vec<PortInfo> {
+    let names = tasklist
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.trim().trim_matches('"').split("\",\"");
+            let name = fields.next()?;
+            let pid = fields.next()?.parse::<i32>().ok()?;
+            Some((pid, name.to_string()))
+        })
+        .collect::<HashMap<_, _>>();
+
+    let ports = netstat
+        .lines()
+        .filter_map(|line| {
+            // TCP rows: Proto Local Foreign State PID; UDP rows have no state.
+            // The state is localized, but only listeners have no foreign port.
+            let columns = line.split_whitespace().collect::<Vec<_>>();
+            let (state, pid) = match columns.as_slice() {
+                ["TCP", _, foreign, _, pid] if foreign.ends_with(":0") => {
+                    (Some("LISTEN".to_string()), pid)
+                }
+                ["UDP", _, _, pid] => (None, pid),
+                _ => return None,
+            };
+            let pid = pid.parse::<i32>().ok()?;
+            let (local_address, port) = parse_address_port(columns[1])?;
+            Some(listening_port(
+                columns[0].to_string(),
+                local_address,
+                port,
+                state,
+                Some(pid),
+                names.get(&pid).map_or("", String::as_str),
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    dedupe_and_sort_ports(ports)
+}
+
+/// Listeners from `ss -Htulnp`. Sockets owned by other users come without
+/// the `users:((...))` column and so without a PID.
+pub fn ports_from_ss(output: &str) -> Vec<PortInfo> {
+    let ports = output
+        .lines()
+        .filter_map(|line| {
+            let columns = line.split_whitespace().collect::<Vec<_>>();
+            if columns.len() < 5 {
+                return None;
+            }
+            let protocol = match columns[0] {
+                "tcp" if columns[1] == "LISTEN" => "TCP",
+                "udp" => "UDP",
+                _ => return None,
+            };
+            // `127.0.0.53%lo:53`: drop the interface scope.
+            let local = columns[4];
+            let local = match (local.find('%'), local.rfind(':')) {
+                (Some(scope), Some(port)) if scope < port => {
+                    format!("{}{}", &local[..scope], &local[port..])
+                }
+                _ => local.to_string(),
+            };
+            let (local_address, port) = parse_address_port(&local)?;
+
+            let owner = line
+                .split_once("users:((\"")
+                .and_then(|(_, users)| users.split_once("\",pid="))
+                .and_then(|(name, rest)| {
+                    let pid = rest.split(|c: char| !c.is_ascii_digit()).next()?;
+                    Some((name, pid.parse::<i32>().ok()?))
+                });
+            let mut entry = listening_port(
+                protocol.to_string(),
+                local_address,
+                port,
+                (protocol == "TCP").then(|| "LISTEN".to_string()),
+                owner.map(|(_, pid)| pid),
+                owner.map_or("", |(name, _)| name),
+            );
+            if owner.is_none() {
+                entry.process_name = None;
+            }
+            Some(entry)
+        })
+        .collect::<Vec<_>>();
+
+    dedupe_and_sort_ports(ports)
+}
+
+fn dedupe_and_sort_ports(mut ports: Vec<PortInfo>) -> Vec<PortInfo> {
+    let mut seen = HashSet::new();
+    ports.retain(|entry| {
+        let key = format!(
+            "{}:{}:{}:{}:{:?}",
+            entry.protocol,
+            entry.local_address,
+            entry.port,
+            entry.pid.unwrap_or_default(),
+            entry.state
+        );
+        seen.insert(key)
+    });
+
+    ports.sort_by(|a, b| {
+        a.port
+            .cmp(&b.port)
+            .then_with(|| a.protocol.cmp(&b.protocol))
+            .then_with(|| a.pid.unwrap_or_default().cmp(&b.pid.unwrap_or_default()))
+    });
+
+    ports
+}
+
+#[cfg(not(any(target_os = "freebsd", target_os = "openbsd", windows)))]
+pub fn collect_ports() -> Result<Vec<PortInfo>, String> {
+    run_lsof(["-iTCP", "-sTCP:LISTEN", "-iUDP"]).map(|output| ports_from_lsof(&output))
+}
+
+// sockstat and fstat ship in the BSD base system, unlike lsof, which is a port.
+#[cfg(target_os = "freebsd")]
+pub fn collect_ports() -> Result<Vec<PortInfo>, String> {
+    run_tool("sockstat", &["-4", "-6", "-l"]).map(|output| ports_from_sockstat(&output))
+}
+
+#[cfg(target_os = "openbsd")]
+pub fn collect_ports() -> Result<Vec<PortInfo>, String> {
+    run_tool("fstat", &[]).map(|output| ports_from_fstat(&output))
+}
+
+#[cfg(windows)]
+pub fn collect_ports() -> Result<Vec<PortInfo>, String> {
+    let netstat = run_tool("netstat", &["-ano"])?;
+    let tasklist = run_tool("tasklist", &["/FO", "CSV", "/NH"]).unwrap_or_default();
+    Ok(ports_from_netstat(&netstat, &tasklist))
+}
+
+/// Runs `program` and returns its stdout, failing on a non-zero exit.
+pub(crate) fn run_tool(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = std::process::Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|error| format!("Failed to run {program}: {error}"))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "{program} exited with status {:?}",
+            output.status.code()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Established TCP connections and connected UDP sockets from `lsof -F`
+/// output, i.e. everything with a remote end, sorted by owning process.
+pub fn connections_from_lsof(output: &str) -> Vec<ConnectionInfo> {
+    let processes = parse_fields(output);
+    let mut seen = HashSet::new();
+    let mut connections = lsof_sockets(&processes)
+        .filter_map(|(process_name, pid, socket)| {
+            Some(ConnectionInfo {
+                remote_address: socket.remote_address?,
+                remote_port: socket.remote_port?,
+                remote_host: None,
+                protocol: socket.protocol,
+                local_address: socket.local_address,
+                local_port: socket.local_port,
+                state: socket.state,
+                pid: Some(pid),
+                process_name: Some(process_name.to_string()),
+            })
+        })
+        // Forked children share their parent's sockets; list each once per PID.
+        .filter(|entry| {
+            seen.insert(format!(
+                "{}:{}:{}:{}:{}:{}",
+                entry.protocol,
+                entry.local_address,
+                entry.local_port,
+                entry.remote_address,
+                entry.remote_port,
+                entry.pid.unwrap_or_default()
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    connections.sort_by(|a, b| {
+        a.pid
+            .unwrap_or_default()
+            .cmp(&b.pid.unwrap_or_default())
+            .then_with(|| a.remote_address.cmp(&b.remote_address))
+            .then_with(|| a.remote_port.cmp(&b.remote_port))
+    });
+
+    connections
+}
+
+pub fn collect_connections() -> Result<Vec<ConnectionInfo>, String> {
+    run_lsof(["-iTCP", "-sTCP:ESTABLISHED", "-iUDP"]).map(|output| connections_from_lsof(&output))
+}
+
+/// One `unix` file from lsof: the socket, its kernel address, and its peers
+/// either as `pid,command,fd` (Linux, with `+E`) or as kernel addresses (macOS).
+pub struct LsofUnixSocket {
+    pub socket: UnixSocketInfo,
+    pub device: String,
+    pub peer_devices: Vec<String>,
+}
+
+/// Splits a Unix socket's lsof name. Linux prints
+/// `[path ]type=STREAM[ ->INO=<inode> <pid>,<command>,<fd>...]`; macOS prints
+/// either the bound path or `-><peer address>`.
+pub fn unix_socket_from_lsof(process: &LsofProcess, file: &LsofFile) -> Option<LsofUnixSocket> {
+    if file.file_type.as_deref() != Some("unix") {
+        return None;
+    }
+
+    let mut socket = UnixSocketInfo {
+        path: None,
+        socket_type: None,
+        state: file.state.clone(),
+        pid: Some(process.pid),
+        process_name: Some(process.command.clone()),
+        peers: Vec::new(),
+    };
+    let mut peer_devices = Vec::new();
+    let name = file.name.as_deref().unwrap_or_default();
+
+    let type_at = name
+        .find(" type=")
+        .map(|index| index + 1)
+        .or_else(|| name.starts_with("type=").then_some(0));
+    if let Some(type_at) = type_at {
+        let path = name[..type_at].trim();
+        if !path.is_empty() {
+            socket.path = Some(path.to_string());
+        }
+        let rest = &name[type_at + "type=".len()..];
+        let (kind, peer) = rest.split_once(' ').unwrap_or((rest, ""));
+        socket.socket_type = Some(kind.to_string());
+
+        // `->INO=<inode>` is followed by one `pid,command,fd` per holder.
+        if let Some(holders) = peer.trim().strip_prefix("->INO=") {
+            let holders = holders.split_once(' ').map_or("", |(_, holders)| holders);
+            socket.peers = parse_unix_peer_holders(holders);
+        }
+    } else if let Some(device) = name.strip_prefix("->") {
+        peer_devices.push(device.to_string());
+    } else if name.starts_with('/') || name.starts_with('@') {
+        socket.path = Some(name.to_string());
+    }
+
+    Some(LsofUnixSocket {
+        socket,
+        device: file.device.clone().unwrap_or_default(),
+        peer_devices,
+    })
+}
+
+/// Splits `pid,command,fd` holders on spaces, rejoining tokens until each
+/// holder is complete so commands containing spaces survive.
+fn parse_unix_peer_holders(holders: &str) -> Vec<UnixSocketPeer> {
+    let mut peers = Vec::new();
+    let mut pending = String::new();
+    for token in holders.split_whitespace() {
+        if !pending.is_empty() {
+            pending.push(' ');
+        }
+        pending.push_str(token);
+
+        let Some((head, fd)) = pending.rsplit_once(',') else {
+            continue;
+        };
+        let Some((pid, command)) = head.split_once(',') else {
+            continue;
+        };
+        if !fd.starts_with(|c: char| c.is_ascii_digit()) {
+            continue;
+        }
+        if let Ok(pid) = pid.parse() {
+            peers.push(UnixSocketPeer {
+                pid,
+                process_name: command.to_string(),
+            });
+        }
+        pending.clear();
+    }
+    peers
+}
+
+/// Every Unix domain socket in `lsof -F -U` output, with its peers where
+/// the OS reports them, sorted by path.
+pub fn unix_sockets_from_lsof(output: &str) -> Vec<UnixSocketInfo> {
+    let processes = parse_fields(output);
+    let rows = processes
+        .iter()
+        .flat_map(|process| {
+            process
+                .files
+                .iter()
+                .filter_map(|file| unix_socket_from_lsof(process, file))
+        })
+        .collect::<Vec<_>>();
+
+    // macOS reports peers by kernel address; look those up among the rows.
+    let holders = rows.iter().fold(
+        HashMap::<&str, Vec<UnixSocketPeer>>::new(),
+        |mut holders, row| {
+            if let (Some(pid), Some(name)) = (row.socket.pid, &row.socket.process_name) {
+                holders
+                    .entry(row.device.as_str())
+                    .or_default()
+                    .push(UnixSocketPeer {
+                        pid,
+                        process_name: name.clone(),
+                    });
+            }
+            holders
+        },
+    );
+
+    let mut seen = HashSet::new();
+    let mut sockets = Vec::new();
+    for row in &rows {
+        // A socket shared across fds (or forked children) is listed once per PID.
+        if !seen.insert((row.device.as_str(), row.socket.pid)) {
+            continue;
+        }
+        let mut socket = row.socket.clone();
+        for device in &row.peer_devices {
+            socket
+                .peers
+                .extend(holders.get(device.as_str()).into_iter().flatten().cloned());
+        }
+        let mut unique = Vec::new();
+        for peer in socket.peers.drain(..) {
+            if !unique.contains(&peer) {
+                unique.push(peer);
+            }
+        }
+        socket.peers = unique;
+        sockets.push(socket);
+    }
+
+    sockets.sort_by(|a, b| {
+        // Named sockets first, then by path and PID.
+        a.path
+            .is_none()
+            .cmp(&b.path.is_none())
+            .then_with(|| a.path.cmp(&b.path))
+            .then_with(|| a.pid.cmp(&b.pid))
+    });
+
+    sockets
+}
+
+pub fn collect_unix_sockets() -> Result<Vec<UnixSocketInfo>, String> {
+    // +E has lsof name the process at the other end (Linux only).
+    let args: &[&str] = if cfg!(target_os = "linux") {
+        &["+E", "-U"]
+    } else {
+        &["-U"]
+    };
+    run_lsof(args).map(|output| unix_sockets_from_lsof(&output))
+}
+
+pub fn port_identity(port: &PortInfo) -> String {
+    format!(
+        "{}:{}:{}:{}",
+        port.protocol,
+        port.local_address,
+        port.port,
+        port.pid.unwrap_or_default()
+    )
+}
+
+pub fn find_port_holder<'a>(
+    ports: impl IntoIterator<Item = &'a PortInfo>,
+    port: u16,
+    protocol: &str,
+) -> Option<&'a PortInfo> {
+    ports
+        .into_iter()
+        .find(|entry| entry.port == port && entry.protocol == protocol)
+}
+
+pub fn collect_process_sockets(pid: i32) -> Option<Vec<SocketInfo>> {
+    // `-a` ANDs the PID and network selectors; lsof exits 1 when the
+    // process simply has no sockets, which `run_lsof` accepts.
+    let output = run_lsof(["-a", "-p", &pid.to_string(), "-i"]).ok()?;
+    let sockets = lsof_sockets(&parse_fields(&output))
+        .filter(|(_, socket_pid, _)| *socket_pid == pid)
+        .map(|(_, _, socket)| socket)
+        .collect::<Vec<_>>();
+
+    Some(sockets)
+}
@@ -0,0 +1,246 @@
+//! Scheduling priority, I/O priority and CPU affinity, for slowing a runaway
+//! process down or confining it to a few cores instead of killing it.
+
+use crate::model::{IoPriority, IoPriorityClass};
+
+/// Unix nice values: -20 is the most favourable, 19 the least.
+pub const NICE_MIN: i32 = -20;
+pub const NICE_MAX: i32 = 19;
+
+/// Sets `pid`'s nice value. Raising it is always allowed for your own
+/// processes; lowering it below where it started needs root on Unix.
+pub fn set_priority(pid: i32, nice: i32) -> Result<(), String> {
+    if pid <= 0 {
+        return Err("PID must be a positive integer".to_string());
+    }
+    if !(NICE_MIN..=NICE_MAX).contains(&nice) {
+        return Err(format!(
+            "Nice value must be between {NICE_MIN} and {NICE_MAX}"
+        ));
+    }
+
+    set_nice(pid, nice)
+}
+
+#[cfg(unix)]
+fn set_nice(pid: i32, nice: i32) -> Result<(), String> {
+    use nix::errno::Errno;
+
+    if unsafe { libc::setpriority(libc::PRIO_PROCESS, pid as libc::id_t, nice) } == 0 {
+        return Ok(());
+    }
+    match Errno::last() {
+        Errno::ESRCH => Err(format!("Process {pid} was not found")),
+        Errno::EACCES | Errno::EPERM => Err(format!(
+            "Not allowed to set the priority of process {pid}; lowering a nice value needs root"
+        )),
+        error => Err(format!(
+            "Failed to set the priority of process {pid}: {error}"
+        )),
+    }
+}
+
+/// Windows has priority classes rather than nice values, so the range is
+/// bucketed the way `start /low` ... `start /high` line up with `nice`.
+/// Realtime is never chosen: it can starve the input stack.
+#[cfg(windows)]
+fn set_nice(pid: i32, nice: i32) -> Result<(), String> {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, SetPriorityClass, ABOVE_NORMAL_PRIORITY_CLASS, BELOW_NORMAL_PRIORITY_CLASS,
+        HIGH_PRIORITY_CLASS, IDLE_PRIORITY_CLASS, NORMAL_PRIORITY_CLASS, PROCESS_SET_INFORMATION,
+    };
+
+    let class = match nice {
+        ..=-10 => HIGH_PRIORITY_CLASS,
+        -9..=-1 => ABOVE_NORMAL_PRIORITY_CLASS,
+        0 => NORMAL_PRIORITY_CLASS,
+        1..=9 => BELOW_NORMAL_PRIORITY_CLASS,
+        _ => IDLE_PRIORITY_CLASS,
+    };
+
+    let handle = unsafe { OpenProcess(PROCESS_SET_INFORMATION, 0, pid as u32) };
+    if handle == 0 {
+        return Err(format!(
+            "Failed to open process {pid}: {}",
+            std::io::Error::last_os_error()
+        ));
+    }
+    let result = if unsafe { SetPriorityClass(handle, class) } == 0 {
+        Err(format!(
+            "Failed to set the priority of process {pid}: {}",
+            std::io::Error::last_os_error()
+        ))
+    } else {
+        Ok(())
+    };
+    unsafe { CloseHandle(handle) };
+    result
+}
+
+#[cfg(not(any(unix, windows)))]
+fn set_nice(_pid: i32, _nice: i32) -> Result<(), String> {
+    Err("Changing process priority is not supported on this platform".to_string())
+}
+
+/// The logical CPUs `pid` is allowed to run on, in ascending order.
+#[cfg(target_os = "linux")]
+pub fn get_affinity(pid: i32) -> Result<Vec<usize>, String> {
+    if pid <= 0 {
+        return Err("PID must be a positive integer".to_string());
+    }
+
+    let mut set = unsafe { std::mem::zeroed::<libc::cpu_set_t>() };
+    let size = std::mem::size_of::<libc::cpu_set_t>();
+    if unsafe { libc::sched_getaffinity(pid, size, &mut set) } != 0 {
+        return Err(affinity_error(pid, "read"));
+    }
+
+    Ok((0..libc::CPU_SETSIZE as usize)
+        .filter(|cpu| unsafe { libc::CPU_ISSET(*cpu, &set) })
+        .collect())
+}
+
+/// Restricts `pid` to `cpus`. Only the main thread is re-pinned; threads
+/// spawned afterwards inherit it, existing ones keep their own mask.
+#[cfg(target_os = "linux")]
+pub fn set_affinity(pid: i32, cpus: &[usize]) -> Result<(), String> {
+    if pid <= 0 {
+        return Err("PID must be a positive integer".to_string());
+    }
+    if cpus.is_empty() {
+        return Err("Pick at least one CPU".to_string());
+    }
+    if let Some(cpu) = cpus.iter().find(|cpu| **cpu >= libc::CPU_SETSIZE as usize) {
+        return Err(format!("CPU {cpu} is out of range"));
+    }
+
+    let mut set = unsafe { std::mem::zeroed::<libc::cpu_set_t>() };
+    for cpu in cpus {
+        unsafe { libc::CPU_SET(*cpu, &mut set) };
+    }
+    let size = std::mem::size_of::<libc::cpu_set_t>();
+    if unsafe { libc::sched_setaffinity(pid, size, &set) } != 0 {
+        return Err(affinity_error(pid, "set"));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn affinity_error(pid: i32, action: &str) -> String {
+    use nix::errno::Errno;
+
+    match Errno::last() {
+        Errno::ESRCH => format!("Process {pid} was not found"),
+        Errno::EPERM => format!("Not allowed to {action} the CPU affinity of process {pid}"),
+        // The mask named no CPU that is online and allowed by the cpuset.
+        Errno::EINVAL => "None of those CPUs are available".to_string(),
+        error => format!("Failed to {action} the CPU affinity of process {pid}: {error}"),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn get_affinity(_pid: i32) -> Result<Vec<usize>, String> {
+    Err("CPU affinity is only supported on Linux".to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn set_affinity(_pid: i32, _cpus: &[usize]) -> Result<(), String> {
+    Err("CPU affinity is only supported on Linux".to_string())
+}
+
+/// `ioprio_get`/`ioprio_set` pack the class into the top bits and the
+/// level into the low 13; `who` 1 is IOPRIO_WHO_PROCESS. libc has no
+/// wrappers, so they go through `syscall`.
+#[cfg(target_os = "linux")]
+const IOPRIO_CLASS_SHIFT: u32 = 13;
+#[cfg(target_os = "linux")]
+const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+
+#[cfg(target_os = "linux")]
+pub fn get_io_priority(pid: i32) -> Option<IoPriority> {
+    let value =
+        unsafe { libc::syscall(libc::SYS_ioprio_get, IOPRIO_WHO_PROCESS, pid) } as libc::c_int;
+    if value < 0 {
+        return None;
+    }
+
+    let class = match value >> IOPRIO_CLASS_SHIFT {
+        0 => IoPriorityClass::None,
+        1 => IoPriorityClass::RealTime,
+        2 => IoPriorityClass::BestEffort,
+        3 => IoPriorityClass::Idle,
+        _ => return None,
+    };
+    let level = matches!(
+        class,
+        IoPriorityClass::RealTime | IoPriorityClass::BestEffort
+    )
+    .then_some((value & ((1 << IOPRIO_CLASS_SHIFT) - 1)) as u8);
+    Some(IoPriority { class, level })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn get_io_priority(_pid: i32) -> Option<IoPriority> {
+    None
+}
+
+/// Sets `pid`'s I/O class like `ionice -c <class> -n <level>`. `level`
+/// defaults to 4, the kernel's middle, and is ignored for `Idle` and `None`.
+#[cfg(target_os = "linux")]
+pub fn set_io_priority(pid: i32, class: IoPriorityClass, level: Option<u8>) -> Result<(), String> {
+    use nix::errno::Errno;
+
+    if pid <= 0 {
+        return Err("PID must be a positive integer".to_string());
+    }
+    let level = level.unwrap_or(4);
+    if level > 7 {
+        return Err("I/O priority level must be between 0 and 7".to_string());
+    }
+
+    let (class_bits, level) = match class {
+        IoPriorityClass::None => (0, 0),
+        IoPriorityClass::RealTime => (1, level),
+        IoPriorityClass::BestEffort => (2, level),
+        IoPriorityClass::Idle => (3, 0),
+    };
+    let value = (class_bits << IOPRIO_CLASS_SHIFT) | libc::c_int::from(level);
+    if unsafe { libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, pid, value) } == 0 {
+        return Ok(());
+    }
+    match Errno::last() {
+        Errno::ESRCH => Err(format!("Process {pid} was not found")),
+        Errno::EPERM => Err(format!(
+            "Not allowed to set the I/O priority of process {pid}; the real-time class needs root"
+        )),
+        error => Err(format!(
+            "Failed to set the I/O priority of process {pid}: {error}"
+        )),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn set_io_priority(
+    _pid: i32,
+    _class: IoPriorityClass,
+    _level: Option<u8>,
+) -> Result<(), String> {
+    Err("I/O priority is only supported on Linux".to_string())
+}
+
+/// `pid`'s current nice value. `getpriority` can legitimately return -1, so
+/// errno is cleared first to tell that apart from a failure.
+#[cfg(unix)]
+pub fn process_nice(pid: i32) -> Option<i32> {
+    use nix::errno::Errno;
+
+    Errno::clear();
+    let nice = unsafe { libc::getpriority(libc::PRIO_PROCESS, pid as libc::id_t) };
+    (nice != -1 || Errno::last() == Errno::UnknownErrno).then_some(nice)
+}
+
+#[cfg(not(unix))]
+pub fn process_nice(_pid: i32) -> Option<i32> {
+    None
+}
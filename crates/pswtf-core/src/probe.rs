@@ -0,0 +1,152 @@
+use std::io::{ErrorKind, Read, Write};
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, TcpStream};
+use std::time::{Duration, Instant};
+
+use crate::model::PortProbe;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(500);
+/// How long to wait for a server that speaks first (SSH, SMTP, MySQL).
+const GREETING_TIMEOUT: Duration = Duration::from_millis(300);
+const REPLY_TIMEOUT: Duration = Duration::from_millis(700);
+const MAX_BANNER_CHARS: usize = 200;
+
+/// Connects to `port` on loopback and works out what is answering: waits
+/// for a greeting, then tries an HTTP request and a Redis `PING`, each on
+/// its own connection so one protocol's error handling can't spoil the next.
+pub fn probe_port(port: u16) -> PortProbe {
+    let mut probe = PortProbe {
+        port,
+        address: None,
+        connected: false,
+        connect_ms: None,
+        protocol: None,
+        banner: None,
+        error: None,
+    };
+    if port == 0 {
+        probe.error = Some("Port must be between 1 and 65535".to_string());
+        return probe;
+    }
+
+    let addresses = [
+        SocketAddr::from((Ipv4Addr::LOCALHOST, port)),
+        SocketAddr::from((Ipv6Addr::LOCALHOST, port)),
+    ];
+    let mut last_error = None;
+    let Some((address, mut stream)) = addresses.iter().find_map(|address| {
+        let started = Instant::now();
+        match TcpStream::connect_timeout(address, CONNECT_TIMEOUT) {
+            Ok(stream) => {
+                probe.connect_ms = Some(started.elapsed().as_millis() as u64);
+                Some((*address, stream))
+            }
+            Err(error) => {
+                last_error = Some(error);
+                None
+            }
+        }
+    }) else {
+        probe.error = last_error.map(|error| match error.kind() {
+            ErrorKind::ConnectionRefused => "Connection refused".to_string(),
+            ErrorKind::TimedOut => "Connection timed out".to_string(),
+            _ => error.to_string(),
+        });
+        return probe;
+    };
+    probe.connected = true;
+    probe.address = Some(address.ip().to_string());
+
+    if let Some(greeting) = read_reply(&mut stream, GREETING_TIMEOUT) {
+        let protocol = if greeting.starts_with(b"SSH-") {
+            "ssh"
+        } else if greeting.starts_with(b"220") {
+            "smtp/ftp"
+        } else if greeting.get(4) == Some(&10) {
+            // MySQL's handshake: 3-byte length, sequence 0, protocol 10.
+            "mysql"
+        } else {
+            "unknown"
+        };
+        probe.protocol = Some(protocol);
+        probe.banner = banner_text(&greeting);
+        return probe;
+    }
+    drop(stream);
+
+    if let Some(reply) = exchange(address, b"HEAD / HTTP/1.0\r\nHost: localhost\r\n\r\n") {
+        if reply.starts_with(b"HTTP/") {
+            probe.protocol = Some("http");
+            probe.banner = http_banner(&reply);
+            return probe;
+        }
+        // A TLS server answers plaintext with an alert record.
+        if reply.len() >= 2 && reply[0] == 0x15 && reply[1] == 0x03 {
+            probe.protocol = Some("tls");
+            return probe;
+        }
+    }
+
+    if let Some(reply) = exchange(address, b"PING\r\n") {
+        if reply.starts_with(b"+PONG")
+            || reply.starts_with(b"-NOAUTH")
+            || reply.starts_with(b"-ERR")
+        {
+            probe.protocol = Some("redis");
+            probe.banner = banner_text(&reply);
+            return probe;
+        }
+        probe.protocol = Some("unknown");
+        probe.banner = banner_text(&reply);
+    }
+
+    probe
+}
+
+fn exchange(address: SocketAddr, request: &[u8]) -> Option<Vec<u8>> {
+    let mut stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT).ok()?;
+    stream.set_write_timeout(Some(REPLY_TIMEOUT)).ok()?;
+    stream.write_all(request).ok()?;
+    read_reply(&mut stream, REPLY_TIMEOUT)
+}
+
+/// The first chunk the server sends within `timeout`, if any.
+fn read_reply(stream: &mut TcpStream, timeout: Duration) -> Option<Vec<u8>> {
+    stream.set_read_timeout(Some(timeout)).ok()?;
+    let mut buffer = [0u8; 1024];
+    match stream.read(&mut buffer) {
+        Ok(0) | Err(_) => None,
+        Ok(read) => Some(buffer[..read].to_vec()),
+    }
+}
+
+/// The status line plus the `Server` header, e.g. `HTTP/1.1 200 OK (nginx)`.
+fn http_banner(reply: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(reply);
+    let mut lines = text.lines();
+    let status = lines.next()?.trim();
+    let server = lines.take_while(|line| !line.is_empty()).find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.eq_ignore_ascii_case("server").then(|| value.trim())
+    });
+    banner_text(
+        match server {
+            Some(server) => format!("{status} ({server})"),
+            None => status.to_string(),
+        }
+        .as_bytes(),
+    )
+}
+
+/// The first line, with anything unprintable dropped.
+fn banner_text(reply: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(reply);
+    let line = text
+        .lines()
+        .next()?
+        .chars()
+        .filter(|c| !c.is_control())
+        .take(MAX_BANNER_CHARS)
+        .collect::<String>();
+    let line = line.trim();
+    (!line.is_empty()).then(|| line.to_string())
+}
@@ -0,0 +1,802 @@
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Deserialize;
+use sysinfo::{CpuExt, Pid, PidExt, Process, ProcessExt, System, SystemExt, UserExt};
+
+use crate::container::annotate_containers;
+use crate::model::{
+    CpuCoreInfo, CpuNormalization, ProcessChange, ProcessDiff, ProcessInfo, ProcessSample,
+    ProcessSnapshot, TopProcess,
+};
+use crate::priority::process_nice;
+use crate::terminal::annotate_multiplexers;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SortKey {
+    #[default]
+    Cpu,
+    Memory,
+    Pid,
+    Name,
+}
+
+impl SortKey {
+    pub fn default_direction(self) -> SortDirection {
+        match self {
+            Self::Cpu | Self::Memory => SortDirection::Desc,
+            Self::Pid | Self::Name => SortDirection::Asc,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Default)]
+pub struct ProcessFilter {
+    pub name_filter: Option<String>,
+    pub min_cpu: Option<f32>,
+    pub min_memory: Option<u64>,
+    pub user: Option<String>,
+    pub exe_outdated: Option<bool>,
+}
+
+impl ProcessFilter {
+    pub fn matches(&self, process: &ProcessInfo) -> bool {
+        if let Some(name_filter) = &self.name_filter {
+            let needle = name_filter.trim().to_ascii_lowercase();
+            if !needle.is_empty()
+                && !process.name.to_ascii_lowercase().contains(&needle)
+                && !process.cmd.to_ascii_lowercase().contains(&needle)
+            {
+                return false;
+            }
+        }
+
+        if self
+            .min_cpu
+            .is_some_and(|min_cpu| process.cpu_percent < min_cpu)
+        {
+            return false;
+        }
+
+        if self
+            .min_memory
+            .is_some_and(|min_memory| process.memory_bytes < min_memory)
+        {
+            return false;
+        }
+
+        if let Some(user) = &self.user {
+            // Accept either a user name or a numeric UID.
+            let name_match = process
+                .user
+                .as_deref()
+                .is_some_and(|name| name.eq_ignore_ascii_case(user.trim()));
+            let uid_match = process
+                .user_id
+                .is_some_and(|uid| uid.to_string() == user.trim());
+            if !name_match && !uid_match {
+                return false;
+            }
+        }
+
+        if self
+            .exe_outdated
+            .is_some_and(|outdated| process.exe_outdated != outdated)
+        {
+            return false;
+        }
+
+        true
+    }
+}
+
+fn pid_to_i32(pid: Pid) -> i32 {
+    pid.as_u32() as i32
+}
+
+pub fn path_to_string(path: &Path) -> Option<String> {
+    if path.as_os_str().is_empty() {
+        None
+    } else {
+        Some(path.display().to_string())
+    }
+}
+
+#[cfg(unix)]
+fn process_user_id(process: &Process) -> Option<u32> {
+    process.user_id().map(|uid| **uid)
+}
+
+#[cfg(not(unix))]
+fn process_user_id(_process: &Process) -> Option<u32> {
+    None
+}
+
+/// The fields of `/proc/<pid>/stat` a sample uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProcStat {
+    pub pgrp: i32,
+    pub session: i32,
+    pub tty_nr: u32,
+    pub utime_ticks: u64,
+    pub stime_ticks: u64,
+}
+
+pub fn parse_proc_stat(stat: &str) -> Option<ProcStat> {
+    // The command name can hold spaces and parentheses, so skip past it.
+    let (_, rest) = stat.rsplit_once(')')?;
+    let fields = rest.split_whitespace().collect::<Vec<_>>();
+    Some(ProcStat {
+        pgrp: fields.get(2)?.parse().ok()?,
+        session: fields.get(3)?.parse().ok()?,
+        tty_nr: fields.get(4)?.parse().ok()?,
+        utime_ticks: fields.get(11)?.parse().ok()?,
+        stime_ticks: fields.get(12)?.parse().ok()?,
+    })
+}
+
+/// A `tty_nr` from `/proc/<pid>/stat` as `ps` shows it (`pts/3`, `tty2`).
+pub fn tty_name(tty_nr: u32) -> Option<String> {
+    if tty_nr == 0 {
+        return None;
+    }
+
+    let major = (tty_nr >> 8) & 0xfff;
+    let minor = (tty_nr & 0xff) | ((tty_nr >> 12) & 0xfff00);
+    match major {
+        136..=143 => Some(format!("pts/{}", (major - 136) * 256 + minor)),
+        4 if minor < 64 => Some(format!("tty{minor}")),
+        4 => Some(format!("ttyS{}", minor - 64)),
+        _ => None,
+    }
+}
+
+/// Process group, session, controlling terminal and `(user, system)` CPU
+/// seconds.
+#[derive(Default)]
+struct KernelFields {
+    pgid: Option<i32>,
+    sid: Option<i32>,
+    tty: Option<String>,
+    cpu_times: Option<(f64, f64)>,
+}
+
+/// Linux has all of them in `/proc/<pid>/stat`, so that is read once.
+#[cfg(target_os = "linux")]
+fn kernel_fields(pid: Pid) -> KernelFields {
+    let Some(stat) = std::fs::read_to_string(format!("/proc/{}/stat", pid.as_u32()))
+        .ok()
+        .and_then(|stat| parse_proc_stat(&stat))
+    else {
+        return KernelFields::default();
+    };
+
+    let ticks_per_second = unsafe { libc::sysconf(libc::_SC_CLK_TCK) }.max(1) as f64;
+    KernelFields {
+        pgid: Some(stat.pgrp),
+        sid: Some(stat.session),
+        tty: tty_name(stat.tty_nr),
+        cpu_times: Some((
+            stat.utime_ticks as f64 / ticks_per_second,
+            stat.stime_ticks as f64 / ticks_per_second,
+        )),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn kernel_fields(pid: Pid) -> KernelFields {
+    KernelFields {
+        pgid: process_group_id(pid),
+        sid: process_session_id(pid),
+        tty: controlling_tty(pid),
+        cpu_times: cpu_times(pid),
+    }
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn process_group_id(pid: Pid) -> Option<i32> {
+    nix::unistd::getpgid(Some(nix::unistd::Pid::from_raw(pid_to_i32(pid))))
+        .ok()
+        .map(|pgid| pgid.as_raw())
+}
+
+#[cfg(not(unix))]
+fn process_group_id(_pid: Pid) -> Option<i32> {
+    None
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn process_session_id(pid: Pid) -> Option<i32> {
+    nix::unistd::getsid(Some(nix::unistd::Pid::from_raw(pid_to_i32(pid))))
+        .ok()
+        .map(|sid| sid.as_raw())
+}
+
+#[cfg(not(unix))]
+fn process_session_id(_pid: Pid) -> Option<i32> {
+    None
+}
+
+/// The controlling terminal's name as `ps` shows it (`ttys003`).
+#[cfg(target_os = "macos")]
+fn controlling_tty(pid: Pid) -> Option<String> {
+    let mut info = std::mem::MaybeUninit::<libc::proc_bsdinfo>::zeroed();
+    let size = std::mem::size_of::<libc::proc_bsdinfo>() as libc::c_int;
+    let written = unsafe {
+        libc::proc_pidinfo(
+            pid_to_i32(pid),
+            libc::PROC_PIDTBSDINFO,
+            0,
+            info.as_mut_ptr() as *mut libc::c_void,
+            size,
+        )
+    };
+    if written != size {
+        return None;
+    }
+    let info = unsafe { info.assume_init() };
+    // NODEV: no controlling terminal.
+    if info.e_tdev == u32::MAX {
+        return None;
+    }
+
+    let name = unsafe { libc::devname(info.e_tdev as libc::dev_t, libc::S_IFCHR) };
+    if name.is_null() {
+        return None;
+    }
+    let name = unsafe { std::ffi::CStr::from_ptr(name) };
+    Some(name.to_string_lossy().into_owned())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn controlling_tty(_pid: Pid) -> Option<String> {
+    None
+}
+
+/// CPU time spent in user space and in the kernel since the process
+/// started, in seconds, summed over all of its threads. `proc_taskinfo`
+/// counts in Mach absolute time units, which are only nanoseconds on Intel.
+#[cfg(target_os = "macos")]
+#[allow(deprecated)]
+fn cpu_times(pid: Pid) -> Option<(f64, f64)> {
+    let mut info = std::mem::MaybeUninit::<libc::proc_taskinfo>::zeroed();
+    let size = std::mem::size_of::<libc::proc_taskinfo>() as libc::c_int;
+    let written = unsafe {
+        libc::proc_pidinfo(
+            pid_to_i32(pid),
+            libc::PROC_PIDTASKINFO,
+            0,
+            info.as_mut_ptr() as *mut libc::c_void,
+            size,
+        )
+    };
+    if written != size {
+        return None;
+    }
+    let info = unsafe { info.assume_init() };
+
+    let mut timebase = libc::mach_timebase_info { numer: 0, denom: 0 };
+    if unsafe { libc::mach_timebase_info(&mut timebase) } != 0 || timebase.denom == 0 {
+        return None;
+    }
+    let seconds =
+        |units: u64| units as f64 * f64::from(timebase.numer) / f64::from(timebase.denom) / 1e9;
+    Some((seconds(info.pti_total_user), seconds(info.pti_total_system)))
+}
+
+/// `GetProcessTimes` reports in 100 ns intervals.
+#[cfg(windows)]
+fn cpu_times(pid: Pid) -> Option<(f64, f64)> {
+    use windows_sys::Win32::Foundation::{CloseHandle, FILETIME};
+    use windows_sys::Win32::System::Threading::{
+        GetProcessTimes, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    let handle = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid.as_u32()) };
+    if handle == 0 {
+        return None;
+    }
+    let empty = FILETIME {
+        dwLowDateTime: 0,
+        dwHighDateTime: 0,
+    };
+    let (mut created, mut exited, mut kernel, mut user) = (empty, empty, empty, empty);
+    let ok = unsafe { GetProcessTimes(handle, &mut created, &mut exited, &mut kernel, &mut user) };
+    unsafe { CloseHandle(handle) };
+    if ok == 0 {
+        return None;
+    }
+
+    let seconds = |time: FILETIME| {
+        ((u64::from(time.dwHighDateTime) << 32) | u64::from(time.dwLowDateTime)) as f64 / 1e7
+    };
+    Some((seconds(user), seconds(kernel)))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn cpu_times(_pid: Pid) -> Option<(f64, f64)> {
+    None
+}
+
+#[cfg(unix)]
+pub fn current_user_id() -> Option<u32> {
+    Some(nix::unistd::Uid::current().as_raw())
+}
+
+#[cfg(not(unix))]
+pub fn current_user_id() -> Option<u32> {
+    None
+}
+
+/// Start times derived from boot time and clock ticks can be off by a second
+/// or so, and a binary launched right after a build would otherwise count
+/// as replaced.
+const EXE_MTIME_SLACK_SECS: u64 = 2;
+
+/// A binary only goes stale when something is installed over it, so there's
+/// no need to stat every process's executable on every sample; each answer
+/// is reused for this long.
+const EXE_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+struct ExeCheck {
+    exe: PathBuf,
+    checked: Instant,
+    outdated: bool,
+}
+
+/// Keyed by PID and start time, so a reused PID is checked afresh.
+static EXE_CHECKS: Mutex<Option<HashMap<(i32, u64), ExeCheck>>> = Mutex::new(None);
+
+/// A binary that is gone from disk (Linux shows `/usr/sbin/nginx (deleted)`
+/// for those) or was modified after `start_time`, in seconds since the epoch.
+fn exe_outdated(pid: i32, exe: &Path, start_time: u64) -> bool {
+    if exe.as_os_str().is_empty() || start_time == 0 {
+        return false;
+    }
+
+    let mut guard = EXE_CHECKS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let checks = guard.get_or_insert_with(HashMap::new);
+    if let Some(check) = checks.get(&(pid, start_time)) {
+        if check.exe == exe && check.checked.elapsed() < EXE_CHECK_INTERVAL {
+            return check.outdated;
+        }
+    }
+
+    let outdated = exe_modified_since(exe, start_time);
+    checks.insert(
+        (pid, start_time),
+        ExeCheck {
+            exe: exe.to_path_buf(),
+            checked: Instant::now(),
+            outdated,
+        },
+    );
+    outdated
+}
+
+/// Drops checks too old to be reused, which is also where the entries of
+/// exited processes go.
+fn prune_exe_checks() {
+    if let Some(checks) = EXE_CHECKS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .as_mut()
+    {
+        checks.retain(|_, check| check.checked.elapsed() < EXE_CHECK_INTERVAL);
+    }
+}
+
+fn exe_modified_since(exe: &Path, start_time: u64) -> bool {
+    match std::fs::metadata(exe) {
+        Ok(metadata) => metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .is_some_and(|modified| modified.as_secs() > start_time + EXE_MTIME_SLACK_SECS),
+        Err(error) => error.kind() == std::io::ErrorKind::NotFound,
+    }
+}
+
+pub fn process_to_info(system: &System, pid: Pid, process: &Process) -> ProcessInfo {
+    let disk_usage = process.disk_usage();
+    let kernel = kernel_fields(pid);
+    let (user_cpu_seconds, system_cpu_seconds) = kernel.cpu_times.unzip();
+
+    ProcessInfo {
+        pid: pid_to_i32(pid),
+        parent_pid: process.parent().map(pid_to_i32),
+        pgid: kernel.pgid,
+        sid: kernel.sid,
+        tty: kernel.tty,
+        user_id: process_user_id(process),
+        user: process
+            .user_id()
+            .and_then(|uid| system.get_user_by_id(uid))
+            .map(|user| user.name().to_string()),
+        name: process.name().to_string(),
+        exe: path_to_string(process.exe()),
+        exe_outdated: exe_outdated(pid_to_i32(pid), process.exe(), process.start_time()),
+        cmd: process.cmd().join(" "),
+        status: format!("{:?}", process.status()),
+        nice: process_nice(pid_to_i32(pid)),
+        cpu_percent: process.cpu_usage(),
+        user_cpu_seconds,
+        system_cpu_seconds,
+        memory_bytes: process.memory().saturating_mul(1024),
+        virtual_memory_bytes: process.virtual_memory().saturating_mul(1024),
+        read_bytes: disk_usage.total_read_bytes,
+        written_bytes: disk_usage.total_written_bytes,
+        read_bytes_per_sec: None,
+        written_bytes_per_sec: None,
+        run_time_seconds: process.run_time(),
+        container: None,
+        multiplexer: None,
+    }
+}
+
+/// Slack allowed when checking that a PID still belongs to a process seen
+/// earlier, since start times are derived from whole-second run times.
+pub const START_TIME_TOLERANCE_MS: u128 = 2_000;
+
+/// When `process` started, going by its run time as of `now`.
+pub fn process_started_at(process: &ProcessInfo, now: u128) -> u128 {
+    now.saturating_sub(process.run_time_seconds as u128 * 1000)
+}
+
+/// `pid` in `processes` (observed at `now`), but only while it's still the
+/// process called `name` that started at `started_at_epoch_ms`. Anything that
+/// acts on a PID remembered from earlier goes through this, so a PID that
+/// was freed and handed to something else is never signaled by mistake.
+pub fn find_same_process<'a>(
+    processes: &'a [ProcessInfo],
+    pid: i32,
+    name: &str,
+    started_at_epoch_ms: u128,
+    now: u128,
+) -> Option<&'a ProcessInfo> {
+    processes.iter().find(|process| {
+        process.pid == pid
+            && process.name == name
+            && process_started_at(process, now).abs_diff(started_at_epoch_ms)
+                <= START_TIME_TOLERANCE_MS
+    })
+}
+
+pub fn epoch_millis() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis())
+        .unwrap_or_default()
+}
+
+/// A `System` with process, user and CPU state loaded and the CPU counters
+/// primed, so the first `take_sample` already reports meaningful percentages
+/// instead of 0% everywhere.
+pub fn primed_system() -> System {
+    let mut system = System::new_all();
+    system.refresh_users_list();
+
+    system.refresh_cpu();
+    system.refresh_processes();
+    std::thread::sleep(System::MINIMUM_CPU_UPDATE_INTERVAL);
+
+    system
+}
+
+/// The current process table without CPU percentages, for callers that only
+/// need PIDs, names and parentage and can't afford the priming sleep.
+pub fn current_processes() -> Vec<ProcessInfo> {
+    let mut system = System::new();
+    system.refresh_users_list();
+    system.refresh_processes();
+    processes_from_system(&system)
+}
+
+pub fn take_sample(system: &mut System) -> ProcessSample {
+    system.refresh_cpu();
+    system.refresh_processes();
+
+    ProcessSample {
+        collected_at_epoch_ms: epoch_millis(),
+        processes: processes_from_system(system),
+        cpu_percent: system.global_cpu_info().cpu_usage(),
+        cpu_cores: system
+            .cpus()
+            .iter()
+            .map(|cpu| CpuCoreInfo {
+                name: cpu.name().to_string(),
+                cpu_percent: cpu.cpu_usage(),
+                frequency_mhz: Some(cpu.frequency()).filter(|mhz| *mhz > 0),
+            })
+            .collect(),
+    }
+}
+
+/// Fills in the per-second disk I/O rates of `current` from the lifetime
+/// totals in `previous`. A total that went backwards means the PID was
+/// reused, so that process is treated as new.
+pub fn apply_io_rates(previous: &ProcessSample, current: &mut ProcessSample) {
+    let elapsed = current
+        .collected_at_epoch_ms
+        .saturating_sub(previous.collected_at_epoch_ms) as f64
+        / 1000.0;
+    if elapsed <= 0.0 {
+        return;
+    }
+
+    let totals = previous
+        .processes
+        .iter()
+        .map(|process| (process.pid, (process.read_bytes, process.written_bytes)))
+        .collect::<HashMap<_, _>>();
+    for process in &mut current.processes {
+        let Some(&(read_before, written_before)) = totals.get(&process.pid) else {
+            continue;
+        };
+        if process.read_bytes < read_before || process.written_bytes < written_before {
+            continue;
+        }
+        process.read_bytes_per_sec = Some((process.read_bytes - read_before) as f64 / elapsed);
+        process.written_bytes_per_sec =
+            Some((process.written_bytes - written_before) as f64 / elapsed);
+    }
+}
+
+pub fn processes_from_system(system: &System) -> Vec<ProcessInfo> {
+    let mut processes = system
+        .processes()
+        .iter()
+        .map(|(pid, process)| process_to_info(system, *pid, process))
+        .collect::<Vec<_>>();
+    prune_exe_checks();
+    annotate_containers(&mut processes);
+    annotate_multiplexers(&mut processes);
+
+    sort_processes(
+        &mut processes,
+        SortKey::Cpu,
+        SortKey::Cpu.default_direction(),
+    );
+
+    processes
+}
+
+/// `pid` and its parents, outermost first, e.g. init, zsh, tmux, node,
+/// esbuild. Stops early at a parent that has already exited.
+pub fn process_ancestry(processes: &[ProcessInfo], pid: i32) -> Result<Vec<ProcessInfo>, String> {
+    if pid <= 0 {
+        return Err("PID must be a positive integer".to_string());
+    }
+
+    let by_pid = processes
+        .iter()
+        .map(|process| (process.pid, process))
+        .collect::<HashMap<_, _>>();
+    let mut current = Some(
+        *by_pid
+            .get(&pid)
+            .ok_or_else(|| format!("Process {pid} was not found"))?,
+    );
+
+    let mut chain = Vec::new();
+    let mut seen = HashSet::new();
+    while let Some(process) = current.filter(|process| seen.insert(process.pid)) {
+        chain.push(process.clone());
+        current = process
+            .parent_pid
+            .and_then(|parent_pid| by_pid.get(&parent_pid).copied());
+    }
+    chain.reverse();
+    Ok(chain)
+}
+
+pub fn sort_processes(processes: &mut [ProcessInfo], key: SortKey, direction: SortDirection) {
+    processes.sort_by(|a, b| compare_processes(a, b, key, direction));
+}
+
+fn compare_processes(
+    a: &ProcessInfo,
+    b: &ProcessInfo,
+    key: SortKey,
+    direction: SortDirection,
+) -> std::cmp::Ordering {
+    let ordering = match key {
+        SortKey::Cpu => a
+            .cpu_percent
+            .partial_cmp(&b.cpu_percent)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.memory_bytes.cmp(&b.memory_bytes)),
+        SortKey::Memory => a.memory_bytes.cmp(&b.memory_bytes),
+        SortKey::Pid => a.pid.cmp(&b.pid),
+        SortKey::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+    };
+
+    let ordering = match direction {
+        SortDirection::Asc => ordering,
+        SortDirection::Desc => ordering.reverse(),
+    };
+
+    ordering.then_with(|| a.pid.cmp(&b.pid))
+}
+
+/// The first `n` processes by `metric`, in its default direction. Only the
+/// top `n` are sorted and cloned, so this stays cheap enough to poll.
+pub fn top_processes(processes: &[ProcessInfo], n: usize, metric: SortKey) -> Vec<TopProcess> {
+    let direction = metric.default_direction();
+    let compare = |a: &&ProcessInfo, b: &&ProcessInfo| compare_processes(a, b, metric, direction);
+
+    let mut candidates = processes.iter().collect::<Vec<_>>();
+    if n < candidates.len() {
+        candidates.select_nth_unstable_by(n, compare);
+        candidates.truncate(n);
+    }
+    candidates.sort_by(compare);
+
+    candidates
+        .into_iter()
+        .map(|process| TopProcess {
+            pid: process.pid,
+            name: process.name.clone(),
+            cpu_percent: process.cpu_percent,
+            memory_bytes: process.memory_bytes,
+        })
+        .collect()
+}
+
+/// What happened between two process lists: processes that appeared or
+/// went away, and those whose CPU moved by `min_cpu_delta` points or
+/// memory by `min_memory_delta` bytes, in either direction. A PID that was
+/// reused in between counts as one process removed and another added.
+///
+/// `added` and `removed` are sorted by memory, `changed` by how far memory
+/// moved, largest first.
+pub fn diff_processes(
+    before: &[ProcessInfo],
+    after: &[ProcessInfo],
+    min_cpu_delta: f32,
+    min_memory_delta: u64,
+) -> ProcessDiff {
+    let same_process = |old: &ProcessInfo, new: &ProcessInfo| {
+        old.name == new.name && new.run_time_seconds >= old.run_time_seconds
+    };
+    let before_by_pid = before
+        .iter()
+        .map(|process| (process.pid, process))
+        .collect::<HashMap<_, _>>();
+    let after_by_pid = after
+        .iter()
+        .map(|process| (process.pid, process))
+        .collect::<HashMap<_, _>>();
+
+    let mut added = Vec::new();
+    let mut changed = Vec::new();
+    for process in after {
+        let Some(old) = before_by_pid
+            .get(&process.pid)
+            .filter(|old| same_process(old, process))
+        else {
+            added.push(process.clone());
+            continue;
+        };
+        let cpu_moved = (process.cpu_percent - old.cpu_percent).abs() >= min_cpu_delta;
+        let memory_moved = process.memory_bytes.abs_diff(old.memory_bytes) >= min_memory_delta;
+        if cpu_moved || memory_moved {
+            changed.push(ProcessChange {
+                pid: process.pid,
+                name: process.name.clone(),
+                cmd: process.cmd.clone(),
+                cpu_before: old.cpu_percent,
+                cpu_after: process.cpu_percent,
+                memory_before_bytes: old.memory_bytes,
+                memory_after_bytes: process.memory_bytes,
+            });
+        }
+    }
+    let mut removed = before
+        .iter()
+        .filter(|old| {
+            !after_by_pid
+                .get(&old.pid)
+                .is_some_and(|process| same_process(old, process))
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+
+    added.sort_by_key(|process| Reverse(process.memory_bytes));
+    removed.sort_by_key(|process| Reverse(process.memory_bytes));
+    changed.sort_by_key(|change| {
+        Reverse(
+            change
+                .memory_after_bytes
+                .abs_diff(change.memory_before_bytes),
+        )
+    });
+
+    ProcessDiff {
+        added,
+        removed,
+        changed,
+    }
+}
+
+pub fn process_metrics_changed(previous: &ProcessInfo, current: &ProcessInfo) -> bool {
+    // run_time_seconds ticks every second for every process, so it is
+    // deliberately left out or every row would count as changed.
+    previous.cpu_percent != current.cpu_percent
+        || previous.memory_bytes != current.memory_bytes
+        || previous.virtual_memory_bytes != current.virtual_memory_bytes
+        || previous.read_bytes != current.read_bytes
+        || previous.written_bytes != current.written_bytes
+        // A process that stops writing keeps its totals but not its rate.
+        || previous.read_bytes_per_sec != current.read_bytes_per_sec
+        || previous.written_bytes_per_sec != current.written_bytes_per_sec
+        || previous.status != current.status
+        || previous.nice != current.nice
+        || previous.parent_pid != current.parent_pid
+        || previous.name != current.name
+        || previous.cmd != current.cmd
+}
+
+/// `min_cpu` in `filter` is compared against the normalized percentages.
+pub fn build_snapshot(
+    sample: &ProcessSample,
+    filter: &ProcessFilter,
+    sort_by: Option<SortKey>,
+    sort_dir: Option<SortDirection>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+    cpu_normalization: Option<CpuNormalization>,
+) -> ProcessSnapshot {
+    let cpu_normalization = cpu_normalization.unwrap_or_default();
+    let logical_core_count = sample.cpu_cores.len().max(1);
+    let mut processes = sample
+        .processes
+        .iter()
+        .cloned()
+        .map(|mut process| {
+            if cpu_normalization == CpuNormalization::PerCore {
+                process.cpu_percent /= logical_core_count as f32;
+            }
+            process
+        })
+        .filter(|process| filter.matches(process))
+        .collect::<Vec<_>>();
+
+    let sort_key = sort_by.unwrap_or_default();
+    sort_processes(
+        &mut processes,
+        sort_key,
+        sort_dir.unwrap_or_else(|| sort_key.default_direction()),
+    );
+
+    // process_count is the number of matches before paging, so callers can
+    // tell how many pages there are.
+    let process_count = processes.len();
+    let processes = processes
+        .into_iter()
+        .skip(offset.unwrap_or(0))
+        .take(limit.unwrap_or(usize::MAX))
+        .collect();
+
+    ProcessSnapshot {
+        collected_at_epoch_ms: sample.collected_at_epoch_ms,
+        process_count,
+        processes,
+        cpu_normalization,
+        logical_core_count,
+        groups: None,
+    }
+}
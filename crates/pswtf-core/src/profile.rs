@@ -0,0 +1,271 @@
+//! A quick, no-setup stack sampler: where a process's threads are, taken
+//! a few times a second and tallied into the functions seen most.
+//!
+//! Linux runs `eu-stack` (from elfutils) for user-space frames and reads
+//! each thread's kernel stack from `/proc/<pid>/task/<tid>/stack` (root
+//! only), using whichever of the two works. macOS runs `sample`.
+
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use crate::model::{HotFrame, ProcessProfile, StackFrame};
+
+/// More than anyone reads; the long tail is mostly one-off frames.
+const MAX_HOT_FRAMES: usize = 50;
+
+#[cfg(target_os = "linux")]
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(50);
+
+#[cfg(target_os = "linux")]
+pub fn sample_process(pid: i32, duration: Duration) -> Result<ProcessProfile, String> {
+    let started = std::time::Instant::now();
+    let deadline = started + duration;
+    let mut stacks = Vec::<Vec<StackFrame>>::new();
+    let mut user_error = None;
+
+    loop {
+        let mut user = match run_eu_stack(pid) {
+            Ok(output) => parse_eu_stack(&output).into_iter().collect(),
+            Err(error) => {
+                user_error.get_or_insert(error);
+                HashMap::new()
+            }
+        };
+        let tids = std::fs::read_dir(format!("/proc/{pid}/task"))
+            .map_err(|error| format!("Failed to read threads of {pid}: {error}"))?
+            .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse::<i32>().ok())
+            .collect::<Vec<_>>();
+
+        for tid in tids {
+            // Kernel frames are innermost, so they go on top of the user ones.
+            let mut stack = std::fs::read_to_string(format!("/proc/{pid}/task/{tid}/stack"))
+                .map(|text| parse_kernel_stack(&text))
+                .unwrap_or_default();
+            stack.extend(user.remove(&tid).unwrap_or_default());
+            if !stack.is_empty() {
+                stacks.push(stack);
+            }
+        }
+
+        if std::time::Instant::now() + SAMPLE_INTERVAL >= deadline {
+            break;
+        }
+        std::thread::sleep(SAMPLE_INTERVAL);
+    }
+
+    if stacks.is_empty() {
+        return Err(match user_error {
+            Some(error) => format!("No stacks could be read for {pid}: {error}"),
+            None => format!("No stacks could be read for {pid}"),
+        });
+    }
+    Ok(ProcessProfile {
+        pid,
+        // eu-stack can take longer than a sample interval, so this is the
+        // time actually spent rather than what was asked for.
+        duration_ms: started.elapsed().as_millis() as u64,
+        samples: stacks.len() as u64,
+        frames: summarize_stacks(&stacks),
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn run_eu_stack(pid: i32) -> Result<String, String> {
+    let output = std::process::Command::new("eu-stack")
+        .args(["-p", &pid.to_string()])
+        .output()
+        .map_err(|error| match error.kind() {
+            std::io::ErrorKind::NotFound => {
+                "eu-stack is not installed (it comes with elfutils)".to_string()
+            }
+            _ => format!("Failed to run eu-stack: {error}"),
+        })?;
+    // eu-stack exits non-zero when any one thread couldn't be unwound, so
+    // only treat it as a failure when nothing came out.
+    if output.stdout.is_empty() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(if stderr.is_empty() {
+            format!("eu-stack exited with {}", output.status)
+        } else {
+            stderr
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// `sample` takes whole seconds and writes its report to a file.
+#[cfg(target_os = "macos")]
+pub fn sample_process(pid: i32, duration: Duration) -> Result<ProcessProfile, String> {
+    let seconds = duration.as_secs().max(1);
+    let report_path =
+        std::env::temp_dir().join(format!("pswtf-sample-{pid}-{}.txt", std::process::id()));
+    let started = std::time::Instant::now();
+    let output = std::process::Command::new("sample")
+        .args([
+            &pid.to_string(),
+            &seconds.to_string(),
+            "10",
+            "-mayDie",
+            "-file",
+        ])
+        .arg(&report_path)
+        .output()
+        .map_err(|error| format!("Failed to run sample: {error}"))?;
+    // sample stops early when the process exits, and can overrun too.
+    let elapsed = started.elapsed();
+    let report = std::fs::read_to_string(&report_path);
+    let _ = std::fs::remove_file(&report_path);
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(if stderr.is_empty() {
+            format!("sample exited with {}", output.status)
+        } else {
+            stderr
+        });
+    }
+    let report = report.map_err(|error| format!("Failed to read the sample report: {error}"))?;
+
+    let frames = parse_sample_report(&report);
+    Ok(ProcessProfile {
+        pid,
+        duration_ms: elapsed.as_millis() as u64,
+        samples: frames.iter().map(|frame| frame.self_samples).sum(),
+        frames,
+    })
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn sample_process(_pid: i32, _duration: Duration) -> Result<ProcessProfile, String> {
+    Err("Stack sampling is not supported on this platform".to_string())
+}
+
+/// `eu-stack -p` output, one stack per thread:
+///
+/// ```text
+/// TID 4242:
+/// #0  0x00007f1d0a1e4d7e __GI___poll
+/// #1  0x000055d8fdb0d7a2 - 1 main
+/// ```
+///
+/// Frames eu-stack couldn't name come back as `??`.
+pub fn parse_eu_stack(output: &str) -> Vec<(i32, Vec<StackFrame>)> {
+    let mut threads = Vec::<(i32, Vec<StackFrame>)>::new();
+
+    for line in output.lines() {
+        let line = line.trim();
+        if let Some(tid) = line
+            .strip_prefix("TID ")
+            .and_then(|rest| rest.trim_end_matches(':').parse::<i32>().ok())
+        {
+            threads.push((tid, Vec::new()));
+            continue;
+        }
+        let (Some((_, stack)), true) = (threads.last_mut(), line.starts_with('#')) else {
+            continue;
+        };
+
+        // `#n`, the address, an optional `- 1` (the pc of a call site is
+        // one past the call), then the function.
+        let mut words = line.split_whitespace().skip(2).peekable();
+        if words.peek() == Some(&"-") {
+            words.nth(1);
+        }
+        let function = words.collect::<Vec<_>>().join(" ");
+        stack.push(StackFrame {
+            function: if function.is_empty() {
+                "??".to_string()
+            } else {
+                function
+            },
+            module: None,
+        });
+    }
+
+    threads
+}
+
+/// `/proc/<pid>/task/<tid>/stack`: `[<0>] do_select+0x6a4/0x7f0` lines.
+pub fn parse_kernel_stack(text: &str) -> Vec<StackFrame> {
+    text.lines()
+        .filter_map(|line| {
+            let symbol = line.split_once("] ").map_or(line, |(_, symbol)| symbol);
+            let function = symbol.split('+').next()?.trim();
+            (!function.is_empty()).then(|| StackFrame {
+                function: function.to_string(),
+                module: Some("kernel".to_string()),
+            })
+        })
+        .collect()
+}
+
+/// The top frame of each stack counts toward `self_samples` and every
+/// distinct frame in it toward `total_samples`, so recursion isn't counted
+/// twice.
+pub fn summarize_stacks(stacks: &[Vec<StackFrame>]) -> Vec<HotFrame> {
+    let mut counts = HashMap::<&StackFrame, (u64, u64)>::new();
+    for stack in stacks {
+        if let Some(top) = stack.first() {
+            counts.entry(top).or_default().0 += 1;
+        }
+        for frame in stack.iter().collect::<HashSet<_>>() {
+            counts.entry(frame).or_default().1 += 1;
+        }
+    }
+
+    let mut frames = counts
+        .into_iter()
+        .map(|(frame, (self_samples, total_samples))| HotFrame {
+            frame: frame.clone(),
+            self_samples,
+            total_samples: Some(total_samples),
+        })
+        .collect::<Vec<_>>();
+    frames.sort_by(|a, b| {
+        b.self_samples
+            .cmp(&a.self_samples)
+            .then_with(|| b.total_samples.cmp(&a.total_samples))
+            .then_with(|| a.frame.function.cmp(&b.frame.function))
+    });
+    frames.truncate(MAX_HOT_FRAMES);
+    frames
+}
+
+/// The "Sort by top of stack" section of a macOS `sample` report:
+///
+/// `        __psynch_cvwait  (in libsystem_kernel.dylib)        2934`
+///
+/// sample leaves out frames seen fewer than five times there, and its
+/// inclusive counts double-count recursion, so `total_samples` stays empty.
+pub fn parse_sample_report(report: &str) -> Vec<HotFrame> {
+    let Some((_, section)) = report.split_once("Sort by top of stack") else {
+        return Vec::new();
+    };
+
+    let mut frames = section
+        .lines()
+        .skip(1)
+        .take_while(|line| !line.trim().is_empty())
+        .filter_map(|line| {
+            let (symbol, count) = line.trim().rsplit_once(char::is_whitespace)?;
+            let self_samples = count.parse::<u64>().ok()?;
+            let symbol = symbol.trim();
+            let (function, module) = match symbol.split_once(" (in ") {
+                Some((function, module)) => (
+                    function.trim(),
+                    Some(module.trim_end_matches(')').to_string()),
+                ),
+                None => (symbol, None),
+            };
+            Some(HotFrame {
+                frame: StackFrame {
+                    function: function.to_string(),
+                    module,
+                },
+                self_samples,
+                total_samples: None,
+            })
+        })
+        .collect::<Vec<_>>();
+    frames.truncate(MAX_HOT_FRAMES);
+    frames
+}
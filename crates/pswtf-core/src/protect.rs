@@ -0,0 +1,116 @@
+use std::collections::HashSet;
+use std::sync::RwLock;
+
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, PidExt, ProcessExt, System, SystemExt};
+
+/// Reason recorded in `KillReport.failed` for targets that were skipped.
+pub const PROTECTED_REASON: &str = "protected";
+
+/// Processes whose death takes the session (or the whole machine) with it.
+const DEFAULT_PROTECTED_NAMES: &[&str] = &[
+    "kernel_task",
+    "launchd",
+    "loginwindow",
+    "WindowServer",
+    "init",
+    "systemd",
+    "kthreadd",
+];
+
+/// Processes `perform_kill` refuses to signal. The app's own ancestry is
+/// always protected on top of this, since killing it would take pswtf down
+/// with its parent terminal or launcher.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtectedList {
+    /// Matched case-insensitively against the full process name.
+    pub names: Vec<String>,
+    pub pids: Vec<i32>,
+}
+
+impl Default for ProtectedList {
+    fn default() -> Self {
+        Self {
+            names: DEFAULT_PROTECTED_NAMES
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+            pids: Vec::new(),
+        }
+    }
+}
+
+static PROTECTED: RwLock<Option<ProtectedList>> = RwLock::new(None);
+
+pub fn protected_list() -> ProtectedList {
+    PROTECTED
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+        .unwrap_or_default()
+}
+
+pub fn set_protected_list(list: ProtectedList) {
+    *PROTECTED
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(list);
+}
+
+/// Looks targets up one at a time rather than refreshing the whole process
+/// table, since kills usually touch a handful of PIDs.
+pub struct ProtectionCheck {
+    system: System,
+    names: HashSet<String>,
+    pids: HashSet<i32>,
+}
+
+impl ProtectionCheck {
+    pub fn new() -> Self {
+        let list = protected_list();
+        let mut check = Self {
+            system: System::new(),
+            names: list.names.iter().map(|name| name.to_lowercase()).collect(),
+            pids: list.pids.into_iter().collect(),
+        };
+
+        // PID 0 is the kernel (or its idle task) on every platform.
+        check.pids.insert(0);
+        let mut visited = HashSet::new();
+        let mut pid = Some(std::process::id() as i32);
+        while let Some(current) = pid.filter(|pid| *pid > 0 && visited.insert(*pid)) {
+            check.pids.insert(current);
+            pid = check
+                .process_info(current)
+                .and_then(|(_, parent_pid)| parent_pid);
+        }
+
+        check
+    }
+
+    pub fn is_protected(&mut self, pid: i32) -> bool {
+        if self.pids.contains(&pid) {
+            return true;
+        }
+
+        self.process_info(pid)
+            .is_some_and(|(name, _)| self.names.contains(&name.to_lowercase()))
+    }
+
+    fn process_info(&mut self, pid: i32) -> Option<(String, Option<i32>)> {
+        let pid = Pid::from_u32(u32::try_from(pid).ok()?);
+        self.system.refresh_process(pid);
+        self.system.process(pid).map(|process| {
+            (
+                process.name().to_string(),
+                process.parent().map(|parent| parent.as_u32() as i32),
+            )
+        })
+    }
+}
+
+impl Default for ProtectionCheck {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,323 @@
+//! A compact filter syntax for power users, e.g.
+//! `cpu>50 mem>1gb name~node user:me port:3000`.
+//!
+//! Terms are separated by whitespace and must all match. Each is
+//! `<field><op><value>`, a bare word (matched against name and command line
+//! like the search box), and either can be negated with a leading `-` or
+//! `!`. Values with spaces go in double quotes: `cmd~"npm run dev"`.
+//!
+//! - `cpu`, `mem`/`memory`, `vmem`, `pid`, `ppid`, `nice`, `uptime`, `utime`,
+//!   `stime` compare with `>`, `>=`, `<`, `<=`, `=` or `:`. Memory takes
+//!   `kb`/`mb`/`gb`/`tb` suffixes, the times `s`/`m`/`h`/`d`. `utime` and
+//!   `stime` are cumulative user and system CPU time.
+//! - `name`, `cmd`, `exe`, `user`, `status`, `tty` take `:` or `=` for a
+//!   case-insensitive exact match and `~` for a substring. `user:me` is the
+//!   current user.
+//! - `port:<n>` matches processes listening on port `n`.
+
+use crate::model::{PortInfo, ProcessInfo};
+use crate::process::current_user_id;
+
+#[derive(Debug, Clone)]
+pub struct ProcessQuery {
+    terms: Vec<Term>,
+}
+
+#[derive(Debug, Clone)]
+struct Term {
+    negated: bool,
+    test: Test,
+}
+
+#[derive(Debug, Clone)]
+enum Test {
+    Number {
+        field: NumberField,
+        op: Comparison,
+        value: f64,
+    },
+    Text {
+        field: TextField,
+        exact: bool,
+        value: String,
+    },
+    Search(String),
+    CurrentUser,
+    Port(u16),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum NumberField {
+    Cpu,
+    Memory,
+    VirtualMemory,
+    Pid,
+    ParentPid,
+    Nice,
+    Uptime,
+    UserTime,
+    SystemTime,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum TextField {
+    Name,
+    Cmd,
+    Exe,
+    User,
+    Status,
+    Tty,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+    Equal,
+    Contains,
+}
+
+/// Two-character operators come first so `>=` isn't read as `>` and `=5`.
+const OPERATORS: [(&str, Comparison); 7] = [
+    (">=", Comparison::GreaterOrEqual),
+    ("<=", Comparison::LessOrEqual),
+    (">", Comparison::Greater),
+    ("<", Comparison::Less),
+    ("=", Comparison::Equal),
+    (":", Comparison::Equal),
+    ("~", Comparison::Contains),
+];
+
+impl ProcessQuery {
+    /// True when evaluating the query needs the listening ports, which are
+    /// worth skipping when it doesn't.
+    pub fn uses_ports(&self) -> bool {
+        self.terms
+            .iter()
+            .any(|term| matches!(term.test, Test::Port(_)))
+    }
+
+    /// `ports` only matters for `port:` terms and can be empty otherwise.
+    pub fn matches(&self, process: &ProcessInfo, ports: &[PortInfo]) -> bool {
+        self.terms
+            .iter()
+            .all(|term| term.test.matches(process, ports) != term.negated)
+    }
+}
+
+impl Test {
+    fn matches(&self, process: &ProcessInfo, ports: &[PortInfo]) -> bool {
+        match self {
+            Self::Number { field, op, value } => {
+                field.value(process).is_some_and(|actual| match op {
+                    Comparison::Greater => actual > *value,
+                    Comparison::GreaterOrEqual => actual >= *value,
+                    Comparison::Less => actual < *value,
+                    Comparison::LessOrEqual => actual <= *value,
+                    Comparison::Equal | Comparison::Contains => actual == *value,
+                })
+            }
+            Self::Text {
+                field,
+                exact,
+                value,
+            } => field.value(process).is_some_and(|actual| {
+                let actual = actual.to_lowercase();
+                if *exact {
+                    actual == *value
+                } else {
+                    actual.contains(value.as_str())
+                }
+            }),
+            Self::Search(needle) => {
+                process.name.to_lowercase().contains(needle.as_str())
+                    || process.cmd.to_lowercase().contains(needle.as_str())
+            }
+            Self::CurrentUser => current_user_id().is_some_and(|uid| process.user_id == Some(uid)),
+            Self::Port(port) => ports
+                .iter()
+                .any(|entry| entry.port == *port && entry.pid == Some(process.pid)),
+        }
+    }
+}
+
+impl NumberField {
+    fn value(self, process: &ProcessInfo) -> Option<f64> {
+        match self {
+            Self::Cpu => Some(f64::from(process.cpu_percent)),
+            Self::Memory => Some(process.memory_bytes as f64),
+            Self::VirtualMemory => Some(process.virtual_memory_bytes as f64),
+            Self::Pid => Some(f64::from(process.pid)),
+            Self::ParentPid => process.parent_pid.map(f64::from),
+            Self::Nice => process.nice.map(f64::from),
+            Self::Uptime => Some(process.run_time_seconds as f64),
+            Self::UserTime => process.user_cpu_seconds,
+            Self::SystemTime => process.system_cpu_seconds,
+        }
+    }
+}
+
+impl TextField {
+    fn value(self, process: &ProcessInfo) -> Option<&str> {
+        match self {
+            Self::Name => Some(&process.name),
+            Self::Cmd => Some(&process.cmd),
+            Self::Exe => process.exe.as_deref(),
+            Self::User => process.user.as_deref(),
+            Self::Status => Some(&process.status),
+            Self::Tty => process.tty.as_deref(),
+        }
+    }
+}
+
+pub fn parse_query(expr: &str) -> Result<ProcessQuery, String> {
+    let terms = tokenize(expr)?
+        .iter()
+        .map(|token| parse_term(token))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ProcessQuery { terms })
+}
+
+/// Splits on whitespace outside double quotes and drops the quotes.
+fn tokenize(expr: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+
+    for ch in expr.chars() {
+        match ch {
+            '"' => quoted = !quoted,
+            ch if ch.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            ch => current.push(ch),
+        }
+    }
+    if quoted {
+        return Err("Unterminated quote in query".to_string());
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    Ok(tokens)
+}
+
+fn parse_term(token: &str) -> Result<Term, String> {
+    let (negated, body) = match token.strip_prefix(['-', '!']) {
+        Some(rest) if !rest.is_empty() => (true, rest),
+        _ => (false, token),
+    };
+
+    let operator = body
+        .char_indices()
+        .find_map(|(index, _)| {
+            OPERATORS
+                .iter()
+                .find(|(symbol, _)| body[index..].starts_with(symbol))
+                .map(|(symbol, op)| (index, *symbol, *op))
+        })
+        .filter(|(index, _, _)| *index > 0);
+    let Some((index, symbol, op)) = operator else {
+        return Ok(Term {
+            negated,
+            test: Test::Search(body.to_lowercase()),
+        });
+    };
+
+    let field = body[..index].to_ascii_lowercase();
+    let value = &body[index + symbol.len()..];
+    if value.is_empty() {
+        return Err(format!("Expected a value after \"{}\"", &body[..index + 1]));
+    }
+
+    let test = match field.as_str() {
+        "cpu" => number(NumberField::Cpu, op, value, parse_plain)?,
+        "mem" | "memory" => number(NumberField::Memory, op, value, parse_bytes)?,
+        "vmem" => number(NumberField::VirtualMemory, op, value, parse_bytes)?,
+        "pid" => number(NumberField::Pid, op, value, parse_plain)?,
+        "ppid" => number(NumberField::ParentPid, op, value, parse_plain)?,
+        "nice" => number(NumberField::Nice, op, value, parse_plain)?,
+        "uptime" => number(NumberField::Uptime, op, value, parse_duration)?,
+        "utime" => number(NumberField::UserTime, op, value, parse_duration)?,
+        "stime" => number(NumberField::SystemTime, op, value, parse_duration)?,
+        "user" if op != Comparison::Contains && value.eq_ignore_ascii_case("me") => {
+            Test::CurrentUser
+        }
+        "name" => text(TextField::Name, op, value)?,
+        "cmd" => text(TextField::Cmd, op, value)?,
+        "exe" => text(TextField::Exe, op, value)?,
+        "user" => text(TextField::User, op, value)?,
+        "status" => text(TextField::Status, op, value)?,
+        "tty" => text(TextField::Tty, op, value)?,
+        "port" if op == Comparison::Equal => Test::Port(
+            value
+                .parse()
+                .map_err(|_| format!("Invalid port in query: {value}"))?,
+        ),
+        "port" => return Err("port only supports port:<n>".to_string()),
+        _ => return Err(format!("Unknown query field \"{field}\"")),
+    };
+    Ok(Term { negated, test })
+}
+
+fn number(
+    field: NumberField,
+    op: Comparison,
+    value: &str,
+    parse: fn(&str) -> Option<f64>,
+) -> Result<Test, String> {
+    if op == Comparison::Contains {
+        return Err("~ only works on text fields".to_string());
+    }
+    let value = parse(value).ok_or_else(|| format!("Invalid number in query: {value}"))?;
+    Ok(Test::Number { field, op, value })
+}
+
+fn text(field: TextField, op: Comparison, value: &str) -> Result<Test, String> {
+    let exact = match op {
+        Comparison::Equal => true,
+        Comparison::Contains => false,
+        _ => return Err("Text fields only support :, = and ~".to_string()),
+    };
+    Ok(Test::Text {
+        field,
+        exact,
+        value: value.to_lowercase(),
+    })
+}
+
+fn parse_plain(value: &str) -> Option<f64> {
+    value.trim_end_matches('%').parse().ok()
+}
+
+/// `512`, `200kb`, `1.5gb`; the trailing `b` is optional and units are
+/// powers of 1024 like the rest of the app.
+fn parse_bytes(value: &str) -> Option<f64> {
+    let lower = value.to_ascii_lowercase();
+    let lower = lower.strip_suffix('b').unwrap_or(&lower);
+    let (number, multiplier) = match lower.char_indices().last()? {
+        (index, 'k') => (&lower[..index], 1024f64),
+        (index, 'm') => (&lower[..index], 1024f64.powi(2)),
+        (index, 'g') => (&lower[..index], 1024f64.powi(3)),
+        (index, 't') => (&lower[..index], 1024f64.powi(4)),
+        _ => (lower, 1.0),
+    };
+    number.parse::<f64>().ok().map(|number| number * multiplier)
+}
+
+/// Seconds, or a number with an `s`/`m`/`h`/`d` suffix.
+fn parse_duration(value: &str) -> Option<f64> {
+    let lower = value.to_ascii_lowercase();
+    let (number, multiplier) = match lower.char_indices().last()? {
+        (index, 's') => (&lower[..index], 1.0),
+        (index, 'm') => (&lower[..index], 60.0),
+        (index, 'h') => (&lower[..index], 3600.0),
+        (index, 'd') => (&lower[..index], 86400.0),
+        _ => (lower.as_str(), 1.0),
+    };
+    number.parse::<f64>().ok().map(|number| number * multiplier)
+}
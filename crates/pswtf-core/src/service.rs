@@ -0,0 +1,159 @@
+use std::process::Command;
+
+use crate::model::ServiceUnit;
+
+/// Finds the systemd unit that owns a process from its cgroup path, e.g.
+/// `0::/system.slice/nginx.service` or, for user services,
+/// `0::/user.slice/user-1000.slice/user@1000.service/app.slice/foo.service`.
+/// Session scopes and the per-user manager itself aren't reported, since
+/// stopping those would end the login session rather than one service.
+pub fn parse_cgroup_unit(contents: &str) -> Option<ServiceUnit> {
+    // cgroup v2's unified line, or the v1 hierarchy systemd names itself.
+    let path = contents
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))
+        .or_else(|| {
+            contents
+                .lines()
+                .find_map(|line| line.split_once(":name=systemd:").map(|(_, path)| path))
+        })?;
+
+    let segments = path.split('/').collect::<Vec<_>>();
+    let index = segments
+        .iter()
+        .rposition(|segment| segment.ends_with(".service") && !segment.starts_with("user@"))?;
+    let user = segments[..index]
+        .iter()
+        .any(|segment| segment.starts_with("user@") && segment.ends_with(".service"));
+
+    Some(ServiceUnit {
+        manager: "systemd",
+        unit: segments[index].to_string(),
+        user,
+    })
+}
+
+#[cfg(target_os = "linux")]
+pub fn service_unit(pid: i32) -> Option<ServiceUnit> {
+    let contents = std::fs::read_to_string(format!("/proc/{pid}/cgroup")).ok()?;
+    parse_cgroup_unit(&contents)
+}
+
+/// Maps a PID to its launchd job label via `launchctl list`, which covers
+/// the caller's own domain: the GUI session's agents, or system daemons
+/// when pswtf runs as root.
+#[cfg(target_os = "macos")]
+pub fn service_unit(pid: i32) -> Option<ServiceUnit> {
+    let output = Command::new("launchctl").arg("list").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let pid = pid.to_string();
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .skip(1)
+        .find_map(|line| {
+            let mut columns = line.split('\t');
+            if columns.next()? != pid {
+                return None;
+            }
+            let label = columns.nth(1)?.trim();
+            Some(ServiceUnit {
+                manager: "launchd",
+                unit: label.to_string(),
+                user: !nix::unistd::geteuid().is_root(),
+            })
+        })
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn service_unit(_pid: i32) -> Option<ServiceUnit> {
+    None
+}
+
+/// Stops a unit through systemctl. Killing a service's main PID usually
+/// just gets it restarted by systemd, so this is the action that sticks.
+pub fn stop_unit(unit: &str, user: bool) -> Result<(), String> {
+    systemctl("stop", unit, user)
+}
+
+pub fn restart_unit(unit: &str, user: bool) -> Result<(), String> {
+    systemctl("restart", unit, user)
+}
+
+/// Removes a job from launchd so KeepAlive can't bring it straight back.
+/// The job returns at next login unless it is also disabled.
+pub fn bootout_job(label: &str, user: bool) -> Result<(), String> {
+    launchctl("bootout", label, user)
+}
+
+/// Marks a job as disabled so launchd won't load it again, persisting
+/// across reboots. Doesn't stop a job that is already running.
+pub fn disable_job(label: &str, user: bool) -> Result<(), String> {
+    launchctl("disable", label, user)
+}
+
+fn launchctl(verb: &str, label: &str, user: bool) -> Result<(), String> {
+    let valid = !label.is_empty()
+        && !label.starts_with('-')
+        && label
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(format!("Invalid launchd label: {label}"));
+    }
+
+    let target = if user {
+        let uid = crate::process::current_user_id()
+            .ok_or_else(|| "launchd is not available on this platform".to_string())?;
+        format!("gui/{uid}/{label}")
+    } else {
+        format!("system/{label}")
+    };
+    let output = Command::new("launchctl")
+        .args([verb, &target])
+        .output()
+        .map_err(|error| format!("Failed to run launchctl: {error}"))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "launchctl {verb} {target} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+fn systemctl(verb: &str, unit: &str, user: bool) -> Result<(), String> {
+    // Unit names are limited to ASCII letters, digits and ":-_.\@".
+    let valid = unit.ends_with(".service")
+        && !unit.starts_with('-')
+        && unit
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, ':' | '-' | '_' | '.' | '\\' | '@'));
+    if !valid {
+        return Err(format!("Invalid systemd unit: {unit}"));
+    }
+
+    let mut command = Command::new("systemctl");
+    if user {
+        command.arg("--user");
+    }
+    // System units need polkit authorization when pswtf isn't root; let
+    // systemctl ask through the desktop's agent.
+    let output = command
+        .args([verb, unit])
+        .output()
+        .map_err(|error| format!("Failed to run systemctl: {error}"))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "systemctl {verb} {unit} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
@@ -0,0 +1,51 @@
+//! Rebuilding a process's invocation as something that can be pasted into
+//! a POSIX shell.
+
+/// `arg` quoted for `sh`: left alone when it only has characters the shell
+/// never treats specially, single-quoted otherwise. `=` is only special in
+/// something that reads as an assignment, like `NAME=value`.
+pub fn shell_quote(arg: &str) -> String {
+    let assignment = arg
+        .split_once('=')
+        .is_some_and(|(name, _)| is_variable_name(name));
+    let plain = !arg.is_empty()
+        && !assignment
+        && arg.bytes().all(|byte| {
+            byte.is_ascii_alphanumeric()
+                || matches!(
+                    byte,
+                    b'_' | b'-' | b'.' | b'/' | b':' | b',' | b'+' | b'@' | b'%' | b'='
+                )
+        });
+    if plain {
+        return arg.to_string();
+    }
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
+/// `cd <cwd> && KEY=value ... <args>`, with every piece quoted. Variables
+/// whose names the shell can't assign (exported bash functions, for
+/// example) are skipped.
+pub fn shell_command(args: &[String], cwd: Option<&str>, env: &[(String, String)]) -> String {
+    let mut parts = Vec::new();
+    for (key, value) in env {
+        if is_variable_name(key) {
+            parts.push(format!("{key}={}", shell_quote(value)));
+        }
+    }
+    parts.extend(args.iter().map(|arg| shell_quote(arg)));
+
+    let command = parts.join(" ");
+    match cwd {
+        Some(cwd) => format!("cd {} && {command}", shell_quote(cwd)),
+        None => command,
+    }
+}
+
+fn is_variable_name(name: &str) -> bool {
+    let mut bytes = name.bytes();
+    bytes
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == b'_')
+        && bytes.all(|byte| byte.is_ascii_alphanumeric() || byte == b'_')
+}
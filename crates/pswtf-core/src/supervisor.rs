@@ -0,0 +1,182 @@
+use std::collections::{HashMap, HashSet};
+use std::process::Command;
+
+use sysinfo::{Pid, PidExt, ProcessExt, System, SystemExt};
+
+use crate::model::{ProcessInfo, SupervisedProcess};
+
+/// How many ancestors to climb looking for a manager. nodemon and forever
+/// usually sit one or two `sh -c` wrappers above the real process.
+const MAX_SUPERVISOR_DEPTH: usize = 4;
+
+/// Process managers that respawn their children when they die.
+struct KnownSupervisor {
+    manager: &'static str,
+    is_manager: fn(&ProcessInfo) -> bool,
+    /// The manager's own stop command for one child, when one exists. Gets
+    /// the child's PID and environment (pm2 and supervisord tag children
+    /// with their app name there).
+    stop_command: fn(i32, &HashMap<String, String>) -> Option<Vec<String>>,
+}
+
+const KNOWN_SUPERVISORS: &[KnownSupervisor] = &[
+    KnownSupervisor {
+        manager: "pm2",
+        // pm2 retitles its daemon to "PM2 v5.3.0: God Daemon (...)".
+        is_manager: |process| process.name.starts_with("PM2") || process.cmd.starts_with("PM2 "),
+        stop_command: |_, env| Some(vec!["pm2".into(), "stop".into(), env.get("pm_id")?.clone()]),
+    },
+    KnownSupervisor {
+        manager: "supervisord",
+        is_manager: |process| {
+            process.name == "supervisord"
+                || process
+                    .cmd
+                    .split_whitespace()
+                    .take(2)
+                    .any(|arg| arg.ends_with("/supervisord") || arg == "supervisord")
+        },
+        stop_command: |_, env| {
+            let process = env.get("SUPERVISOR_PROCESS_NAME")?;
+            let program = match env.get("SUPERVISOR_GROUP_NAME") {
+                Some(group) if group != process => format!("{group}:{process}"),
+                _ => process.clone(),
+            };
+            Some(vec!["supervisorctl".into(), "stop".into(), program])
+        },
+    },
+    KnownSupervisor {
+        manager: "forever",
+        is_manager: |process| {
+            process.name == "forever" || process.cmd.contains("forever/bin/monitor")
+        },
+        stop_command: |pid, _| Some(vec!["forever".into(), "stop".into(), pid.to_string()]),
+    },
+    KnownSupervisor {
+        manager: "nodemon",
+        is_manager: |process| process.name == "nodemon" || runs_nodemon(&process.cmd),
+        // nodemon has no per-child stop; stopping it means killing it.
+        stop_command: |_, _| None,
+    },
+];
+
+/// nodemon runs as `node .../nodemon/bin/nodemon.js`, or as `nodemon` via
+/// its npm shim. Only the program and script are looked at, since "nodemon"
+/// anywhere else on the command line (a project path, say) means nothing.
+fn runs_nodemon(cmd: &str) -> bool {
+    let basename = |arg: &str| {
+        arg.rsplit(['/', '\\'])
+            .next()
+            .unwrap_or(arg)
+            .to_ascii_lowercase()
+    };
+    let mut args = cmd.split_whitespace().map(basename);
+    let program = args.next().unwrap_or_default();
+    let script = if matches!(program.as_str(), "node" | "node.exe") {
+        args.next().unwrap_or_default()
+    } else {
+        program
+    };
+    matches!(
+        script.as_str(),
+        "nodemon" | "nodemon.js" | "nodemon.cmd" | "nodemon.exe"
+    )
+}
+
+/// Finds the manager that will restart `pid`, if one of its close
+/// ancestors is a known respawning supervisor.
+pub fn find_supervisor(processes: &[ProcessInfo], pid: i32) -> Option<SupervisedProcess> {
+    let by_pid = processes
+        .iter()
+        .map(|process| (process.pid, process))
+        .collect::<HashMap<_, _>>();
+
+    let mut current = by_pid.get(&pid)?.parent_pid;
+    for _ in 0..MAX_SUPERVISOR_DEPTH {
+        let ancestor = by_pid.get(&current?)?;
+        if let Some(known) = KNOWN_SUPERVISORS
+            .iter()
+            .find(|known| (known.is_manager)(ancestor))
+        {
+            return Some(SupervisedProcess {
+                pid,
+                manager: known.manager.to_string(),
+                manager_pid: ancestor.pid,
+                stop_command: (known.stop_command)(pid, &process_environment(pid)),
+            });
+        }
+        current = ancestor.parent_pid;
+    }
+
+    None
+}
+
+/// The targets of a kill that their manager will bring straight back. A
+/// manager that is itself among the targets doesn't count.
+pub fn find_supervised(processes: &[ProcessInfo], targets: &[i32]) -> Vec<SupervisedProcess> {
+    let target_set = targets.iter().copied().collect::<HashSet<_>>();
+    targets
+        .iter()
+        .filter_map(|pid| find_supervisor(processes, *pid))
+        .filter(|supervised| !target_set.contains(&supervised.manager_pid))
+        .collect()
+}
+
+/// Stops `pid` through its manager's own command so it stays stopped.
+pub fn stop_supervised(processes: &[ProcessInfo], pid: i32) -> Result<SupervisedProcess, String> {
+    if pid <= 0 {
+        return Err("PID must be a positive integer".to_string());
+    }
+
+    let supervised = find_supervisor(processes, pid)
+        .ok_or_else(|| format!("Process {pid} isn't managed by a known process manager"))?;
+    let Some((program, args)) = supervised
+        .stop_command
+        .as_ref()
+        .and_then(|command| command.split_first())
+    else {
+        return Err(format!(
+            "{} has no stop command for individual processes; stop {} (PID {}) instead",
+            supervised.manager, supervised.manager, supervised.manager_pid
+        ));
+    };
+
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|error| format!("Failed to run {program}: {error}"))?;
+    if output.status.success() {
+        Ok(supervised)
+    } else {
+        Err(format!(
+            "{} failed: {}",
+            supervised
+                .stop_command
+                .as_deref()
+                .unwrap_or_default()
+                .join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+fn process_environment(pid: i32) -> HashMap<String, String> {
+    let Ok(raw_pid) = u32::try_from(pid) else {
+        return HashMap::new();
+    };
+    let target = Pid::from_u32(raw_pid);
+    let mut system = System::new();
+    system.refresh_process(target);
+
+    system
+        .process(target)
+        .map(|process| {
+            process
+                .environ()
+                .iter()
+                .filter_map(|entry| entry.split_once('='))
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
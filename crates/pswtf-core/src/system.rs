@@ -0,0 +1,359 @@
+use std::collections::HashMap;
+#[cfg(target_os = "macos")]
+use std::process::Command;
+use std::time::Instant;
+
+use sysinfo::{ComponentExt, DiskExt, NetworkExt, NetworksExt, System, SystemExt};
+
+use crate::model::{
+    BatteryInfo, DiskInfo, EnergyImpact, LoadAverage, NetworkInterfaceInfo, ProcessSample,
+    SensorReading, SystemOverview,
+};
+
+/// Pairs of cumulative byte counters from the previous call, keyed by device,
+/// used to turn lifetime totals into per-second rates.
+#[derive(Default)]
+pub struct CounterRates {
+    previous: HashMap<String, (Instant, u64, u64)>,
+}
+
+impl CounterRates {
+    pub fn observe(
+        &mut self,
+        key: &str,
+        now: Instant,
+        first: u64,
+        second: u64,
+    ) -> (Option<f64>, Option<f64>) {
+        let rates = match self.previous.get(key) {
+            Some((at, first_before, second_before)) => {
+                let elapsed = now.duration_since(*at).as_secs_f64();
+                let per_second = |current: u64, before: u64| {
+                    (elapsed > 0.0).then(|| current.saturating_sub(before) as f64 / elapsed)
+                };
+                (
+                    per_second(first, *first_before),
+                    per_second(second, *second_before),
+                )
+            }
+            None => (None, None),
+        };
+
+        self.previous.insert(key.to_string(), (now, first, second));
+        rates
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn collect_battery() -> Option<BatteryInfo> {
+    let entries = std::fs::read_dir("/sys/class/power_supply").ok()?;
+
+    for entry in entries.flatten() {
+        let base = entry.path();
+        let read = |name: &str| {
+            std::fs::read_to_string(base.join(name))
+                .ok()
+                .map(|text| text.trim().to_string())
+        };
+
+        if read("type").as_deref() != Some("Battery") {
+            continue;
+        }
+
+        let Some(percent) = read("capacity").and_then(|text| text.parse::<f32>().ok()) else {
+            continue;
+        };
+        let state = read("status").unwrap_or_else(|| "Unknown".to_string());
+
+        let energy_now = read("energy_now").and_then(|text| text.parse::<f64>().ok());
+        let power_now = read("power_now").and_then(|text| text.parse::<f64>().ok());
+        let time_remaining_minutes = match (state.as_str(), energy_now, power_now) {
+            ("Discharging", Some(energy), Some(power)) if power > 0.0 => {
+                Some((energy / power * 60.0) as u64)
+            }
+            _ => None,
+        };
+
+        return Some(BatteryInfo {
+            percent,
+            state,
+            time_remaining_minutes,
+        });
+    }
+
+    None
+}
+
+#[cfg(target_os = "macos")]
+pub fn collect_battery() -> Option<BatteryInfo> {
+    // " -InternalBattery-0 (id=1234)\t85%; discharging; 3:20 remaining present: true"
+    let output = Command::new("pmset").args(["-g", "batt"]).output().ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let line = stdout
+        .lines()
+        .find(|line| line.contains("InternalBattery"))?;
+    let details = line.split('\t').nth(1)?;
+
+    let mut parts = details.split(';').map(str::trim);
+    let percent = parts.next()?.trim_end_matches('%').parse::<f32>().ok()?;
+    let state = parts.next().unwrap_or("unknown").to_string();
+    let time_remaining_minutes = parts
+        .next()
+        .and_then(|text| text.split_whitespace().next())
+        .and_then(|clock| clock.split_once(':'))
+        .and_then(|(hours, minutes)| {
+            Some(hours.parse::<u64>().ok()? * 60 + minutes.parse::<u64>().ok()?)
+        });
+
+    Some(BatteryInfo {
+        percent,
+        state,
+        time_remaining_minutes,
+    })
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn collect_battery() -> Option<BatteryInfo> {
+    None
+}
+
+#[cfg(target_os = "macos")]
+pub fn collect_energy_impact() -> Result<Vec<EnergyImpact>, String> {
+    // The first `top` sample has no baseline, so take two and keep the last.
+    let output = Command::new("top")
+        .args([
+            "-l",
+            "2",
+            "-o",
+            "power",
+            "-n",
+            "50",
+            "-stats",
+            "pid,command,power",
+        ])
+        .output()
+        .map_err(|error| format!("Failed to run top: {error}"))?;
+
+    if !output.status.success() {
+        return Err(format!("top exited with status {:?}", output.status.code()));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let last_sample = stdout
+        .rsplit_once("\nPID")
+        .map(|(_, sample)| sample)
+        .ok_or_else(|| "Unexpected top output".to_string())?;
+
+    let impacts = last_sample
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let columns = line.split_whitespace().collect::<Vec<_>>();
+            if columns.len() < 3 {
+                return None;
+            }
+            let pid = columns[0].parse::<i32>().ok()?;
+            let energy_impact = columns[columns.len() - 1].parse::<f32>().ok()?;
+            let name = columns[1..columns.len() - 1].join(" ");
+
+            Some(EnergyImpact {
+                pid,
+                name: Some(name).filter(|name| !name.is_empty()),
+                energy_impact,
+            })
+        })
+        .collect();
+
+    Ok(impacts)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn collect_energy_impact() -> Result<Vec<EnergyImpact>, String> {
+    Err("Energy impact is only available on macOS".to_string())
+}
+
+pub fn collect_system_overview(sample: &ProcessSample) -> SystemOverview {
+    let mut system = System::new();
+    system.refresh_memory();
+
+    let load = system.load_average();
+
+    SystemOverview {
+        total_memory_bytes: system.total_memory(),
+        used_memory_bytes: system.used_memory(),
+        available_memory_bytes: system.available_memory(),
+        total_swap_bytes: system.total_swap(),
+        used_swap_bytes: system.used_swap(),
+        cpu_count: sample.cpu_cores.len(),
+        cpu_percent: sample.cpu_percent,
+        load_average: LoadAverage {
+            one: load.one,
+            five: load.five,
+            fifteen: load.fifteen,
+        },
+        uptime_seconds: system.uptime(),
+        boot_time_epoch_seconds: system.boot_time(),
+        battery: collect_battery(),
+    }
+}
+
+pub fn collect_network_interfaces(rates: &mut CounterRates) -> Vec<NetworkInterfaceInfo> {
+    let mut system = System::new();
+    system.refresh_networks_list();
+
+    let now = Instant::now();
+
+    let mut interfaces = system
+        .networks()
+        .iter()
+        .map(|(name, data)| {
+            let received_bytes = data.total_received();
+            let transmitted_bytes = data.total_transmitted();
+            let (received_bytes_per_sec, transmitted_bytes_per_sec) =
+                rates.observe(name, now, received_bytes, transmitted_bytes);
+
+            NetworkInterfaceInfo {
+                name: name.clone(),
+                mac_address: data.mac_address().to_string(),
+                received_bytes,
+                transmitted_bytes,
+                packets_received: data.total_packets_received(),
+                packets_transmitted: data.total_packets_transmitted(),
+                errors_received: data.total_errors_on_received(),
+                errors_transmitted: data.total_errors_on_transmitted(),
+                received_bytes_per_sec,
+                transmitted_bytes_per_sec,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+    interfaces
+}
+
+/// Cumulative `(read, written)` bytes per block device from /proc/diskstats.
+#[cfg(target_os = "linux")]
+fn read_disk_io_counters() -> HashMap<String, (u64, u64)> {
+    const SECTOR_BYTES: u64 = 512;
+
+    let Ok(diskstats) = std::fs::read_to_string("/proc/diskstats") else {
+        return HashMap::new();
+    };
+
+    diskstats
+        .lines()
+        .filter_map(|line| {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let name = fields.get(2)?;
+            let sectors_read = fields.get(5)?.parse::<u64>().ok()?;
+            let sectors_written = fields.get(9)?.parse::<u64>().ok()?;
+            Some((
+                name.to_string(),
+                (
+                    sectors_read.saturating_mul(SECTOR_BYTES),
+                    sectors_written.saturating_mul(SECTOR_BYTES),
+                ),
+            ))
+        })
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_disk_io_counters() -> HashMap<String, (u64, u64)> {
+    HashMap::new()
+}
+
+/// The /proc/diskstats name for a mount source. `/dev/mapper/*` and
+/// `/dev/disk/by-*` are symlinks to the `dm-N` or `sdXN` node diskstats
+/// uses, so the path is resolved first.
+fn block_device_name(source: &str) -> String {
+    let resolved = std::fs::canonicalize(source)
+        .map(|path| path.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| source.to_string());
+    resolved.trim_start_matches("/dev/").to_string()
+}
+
+/// Read and write rates per device, each device observed once however many
+/// mounts it backs. Observing it again for a second mount would measure
+/// the zero bytes moved since the first.
+pub fn disk_rates<'a>(
+    devices: impl IntoIterator<Item = &'a str>,
+    io_counters: &HashMap<String, (u64, u64)>,
+    rates: &mut CounterRates,
+    now: Instant,
+) -> HashMap<&'a str, (Option<f64>, Option<f64>)> {
+    let mut per_device = HashMap::new();
+    for device in devices {
+        if per_device.contains_key(device) {
+            continue;
+        }
+        let rate = match io_counters.get(device) {
+            Some(&(read, written)) => rates.observe(device, now, read, written),
+            None => (None, None),
+        };
+        per_device.insert(device, rate);
+    }
+    per_device
+}
+
+pub fn collect_disks(rates: &mut CounterRates) -> Vec<DiskInfo> {
+    let mut system = System::new();
+    system.refresh_disks_list();
+
+    let io_counters = read_disk_io_counters();
+    let now = Instant::now();
+
+    let devices = system
+        .disks()
+        .iter()
+        .map(|disk| block_device_name(&disk.name().to_string_lossy()))
+        .collect::<Vec<_>>();
+    let device_rates = disk_rates(devices.iter().map(String::as_str), &io_counters, rates, now);
+
+    system
+        .disks()
+        .iter()
+        .zip(&devices)
+        .map(|(disk, device)| {
+            let name = disk.name().to_string_lossy().into_owned();
+            let counters = io_counters.get(device).copied();
+            let (read_bytes_per_sec, written_bytes_per_sec) = device_rates
+                .get(device.as_str())
+                .copied()
+                .unwrap_or((None, None));
+
+            DiskInfo {
+                mount_point: disk.mount_point().display().to_string(),
+                file_system: String::from_utf8_lossy(disk.file_system()).into_owned(),
+                kind: format!("{:?}", disk.kind()),
+                is_removable: disk.is_removable(),
+                total_bytes: disk.total_space(),
+                available_bytes: disk.available_space(),
+                read_bytes: counters.map(|(read, _)| read),
+                written_bytes: counters.map(|(_, written)| written),
+                read_bytes_per_sec,
+                written_bytes_per_sec,
+                name,
+            }
+        })
+        .collect()
+}
+
+pub fn collect_sensors() -> Vec<SensorReading> {
+    let mut system = System::new();
+    system.refresh_components_list();
+
+    let mut sensors = system
+        .components()
+        .iter()
+        .map(|component| SensorReading {
+            label: component.label().to_string(),
+            temperature_celsius: component.temperature(),
+            max_celsius: component.max(),
+            critical_celsius: component.critical(),
+        })
+        .collect::<Vec<_>>();
+
+    sensors.sort_by(|a, b| a.label.cmp(&b.label));
+    sensors
+}
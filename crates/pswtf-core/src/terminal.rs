@@ -0,0 +1,185 @@
+use std::collections::{HashMap, HashSet};
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::model::{MultiplexerPane, ProcessInfo};
+
+/// `tmux list-panes` and `screen -ls` each start a process, which is too
+/// much to do every sample, so their answers are reused for this long.
+const PANE_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Default)]
+struct PaneCache {
+    tmux_panes: Vec<MultiplexerPane>,
+    tmux_refreshed: Option<Instant>,
+    /// SCREEN server PID to session name.
+    screen_sessions: HashMap<i32, String>,
+    screen_refreshed: Option<Instant>,
+}
+
+static PANES: Mutex<Option<PaneCache>> = Mutex::new(None);
+
+fn is_stale(refreshed: Option<Instant>) -> bool {
+    refreshed.map_or(true, |at| at.elapsed() >= PANE_REFRESH_INTERVAL)
+}
+
+/// Fills in `ProcessInfo.multiplexer` for everything running under a tmux
+/// pane or screen window. The multiplexer CLIs are only consulted when a
+/// tmux or screen server is actually in the process table.
+pub fn annotate_multiplexers(processes: &mut [ProcessInfo]) {
+    let has_tmux = processes
+        .iter()
+        .any(|process| process.name.starts_with("tmux"));
+    let has_screen = processes
+        .iter()
+        .any(|process| process.name.eq_ignore_ascii_case("screen"));
+    if !has_tmux && !has_screen {
+        return;
+    }
+
+    let mut guard = PANES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let cache = guard.get_or_insert_with(PaneCache::default);
+
+    let mut panes = HashMap::<i32, MultiplexerPane>::new();
+    if has_tmux {
+        if is_stale(cache.tmux_refreshed) {
+            cache.tmux_panes = list_tmux_panes();
+            cache.tmux_refreshed = Some(Instant::now());
+        }
+        panes.extend(
+            cache
+                .tmux_panes
+                .iter()
+                .map(|pane| (pane.pane_pid, pane.clone())),
+        );
+    }
+    if has_screen {
+        if is_stale(cache.screen_refreshed) {
+            cache.screen_sessions = list_screen_sessions();
+            cache.screen_refreshed = Some(Instant::now());
+        }
+        panes.extend(
+            screen_windows(&cache.screen_sessions, processes)
+                .into_iter()
+                .map(|pane| (pane.pane_pid, pane)),
+        );
+    }
+    drop(guard);
+    if panes.is_empty() {
+        return;
+    }
+
+    let parents = processes
+        .iter()
+        .map(|process| (process.pid, process.parent_pid))
+        .collect::<HashMap<_, _>>();
+    for process in processes.iter_mut() {
+        let mut visited = HashSet::new();
+        let mut current = Some(process.pid);
+        while let Some(pid) = current.filter(|pid| visited.insert(*pid)) {
+            if let Some(pane) = panes.get(&pid) {
+                process.multiplexer = Some(pane.clone());
+                break;
+            }
+            current = parents.get(&pid).copied().flatten();
+        }
+    }
+}
+
+fn list_tmux_panes() -> Vec<MultiplexerPane> {
+    let Ok(output) = Command::new("tmux")
+        .args([
+            "list-panes",
+            "-a",
+            "-F",
+            "#{session_name}\t#{window_index}\t#{window_name}\t#{pane_index}\t#{pane_pid}",
+        ])
+        .output()
+    else {
+        return Vec::new();
+    };
+    if !output.status.success() {
+        return Vec::new();
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let session = fields.next()?.to_string();
+            let window_index = fields.next()?.parse().ok();
+            let window_name = fields.next().map(str::to_string);
+            let pane_index = fields.next()?.parse().ok();
+            let pane_pid = fields.next()?.parse().ok()?;
+            Some(MultiplexerPane {
+                kind: "tmux",
+                session,
+                window_index,
+                window_name,
+                pane_index,
+                pane_pid,
+            })
+        })
+        .collect()
+}
+
+/// `screen -ls` lists sessions as `<pid>.<name>`, where the PID is the
+/// SCREEN server.
+fn list_screen_sessions() -> HashMap<i32, String> {
+    // screen -ls exits non-zero even when it lists sessions, so ignore
+    // the status and go by the output.
+    let Ok(output) = Command::new("screen").arg("-ls").output() else {
+        return HashMap::new();
+    };
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (pid, name) = line.split_whitespace().next()?.split_once('.')?;
+            Some((pid.parse::<i32>().ok()?, name.to_string()))
+        })
+        .collect()
+}
+
+/// Each child of a SCREEN server is one window's shell.
+fn screen_windows(
+    sessions: &HashMap<i32, String>,
+    processes: &[ProcessInfo],
+) -> Vec<MultiplexerPane> {
+    processes
+        .iter()
+        .filter_map(|process| {
+            let session = sessions.get(&process.parent_pid?)?;
+            Some(MultiplexerPane {
+                kind: "screen",
+                session: session.clone(),
+                window_index: None,
+                window_name: None,
+                pane_index: None,
+                pane_pid: process.pid,
+            })
+        })
+        .collect()
+}
+
+/// The processes running inside a multiplexer session, or one tmux window
+/// of it. Pane shells are left out so the panes themselves stay open.
+pub fn multiplexer_members<'a>(
+    processes: &'a [ProcessInfo],
+    session: &str,
+    window_index: Option<u32>,
+) -> Vec<&'a ProcessInfo> {
+    processes
+        .iter()
+        .filter(|process| {
+            process.multiplexer.as_ref().is_some_and(|pane| {
+                pane.session == session
+                    && pane.pane_pid != process.pid
+                    && (window_index.is_none() || pane.window_index == window_index)
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,119 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::model::{PortInfo, PortOrigin};
+use crate::ports::{ports_from_netstat, ports_from_ss, run_tool};
+
+/// Crossing the WSL boundary means starting a Windows (or Linux) program,
+/// which takes a few hundred milliseconds, so the other side's listeners
+/// are reused for this long.
+const OTHER_SIDE_TTL: Duration = Duration::from_secs(5);
+
+/// `None` in the cache is a failed query, kept for the same TTL so a
+/// machine without the other side isn't asked again on every refresh.
+static OTHER_SIDE: Mutex<Option<(Instant, Option<Vec<PortInfo>>)>> = Mutex::new(None);
+
+/// WSL 1 and 2 kernels both report a "microsoft" release string.
+#[cfg(target_os = "linux")]
+pub fn is_wsl() -> bool {
+    std::fs::read_to_string("/proc/sys/kernel/osrelease")
+        .is_ok_and(|release| release.to_ascii_lowercase().contains("microsoft"))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn is_wsl() -> bool {
+    false
+}
+
+/// The Windows processes that forward a distro's ports to the host.
+fn is_wsl_relay(process_name: Option<&str>) -> bool {
+    process_name.is_some_and(|name| {
+        let name = name.to_ascii_lowercase();
+        name.starts_with("wslrelay") || name.starts_with("vmmem")
+    })
+}
+
+/// Adds the listeners on the other side of a WSL setup: the Windows host's
+/// when running inside WSL, and the default distro's when running on
+/// Windows with WSL installed. Relay entries for ports that are already in
+/// `ports` are dropped, since they're the same listener seen twice.
+/// Does nothing elsewhere, or when the other side can't be queried.
+pub fn merge_wsl_ports(ports: &mut Vec<PortInfo>) {
+    let Some(other) = other_side_ports() else {
+        return;
+    };
+
+    let local = ports
+        .iter()
+        .map(|entry| (entry.port, entry.protocol.clone()))
+        .collect::<Vec<_>>();
+    ports.extend(other.into_iter().filter(|entry| {
+        !(is_wsl_relay(entry.process_name.as_deref())
+            && local.contains(&(entry.port, entry.protocol.clone())))
+    }));
+    ports.sort_by(|a, b| {
+        a.port
+            .cmp(&b.port)
+            .then_with(|| a.protocol.cmp(&b.protocol))
+    });
+}
+
+fn other_side_ports() -> Option<Vec<PortInfo>> {
+    let origin = if is_wsl() {
+        PortOrigin::WindowsHost
+    } else if cfg!(windows) {
+        PortOrigin::Wsl
+    } else {
+        return None;
+    };
+
+    let mut cache = OTHER_SIDE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some((at, ports)) = cache.as_ref() {
+        if at.elapsed() < OTHER_SIDE_TTL {
+            return ports.clone();
+        }
+    }
+
+    let ports = query_other_side(origin);
+    *cache = Some((Instant::now(), ports.clone()));
+    ports
+}
+
+fn query_other_side(origin: PortOrigin) -> Option<Vec<PortInfo>> {
+    let mut ports = match origin {
+        PortOrigin::WindowsHost => {
+            let netstat = run_tool("netstat.exe", &["-ano"]).ok()?;
+            let tasklist = run_tool("tasklist.exe", &["/FO", "CSV", "/NH"]).unwrap_or_default();
+            ports_from_netstat(&netstat, &tasklist)
+        }
+        _ => {
+            // `wsl.exe --exec` would boot a stopped distro just to list its
+            // ports, so only ask when one is already running.
+            if !wsl_distro_running() {
+                return None;
+            }
+            let output = run_tool("wsl.exe", &["--exec", "ss", "-Htulnp"]).ok()?;
+            ports_from_ss(&output)
+        }
+    };
+    for entry in &mut ports {
+        entry.origin = origin;
+    }
+    Some(ports)
+}
+
+/// wsl.exe writes its own output as UTF-16, which comes through the lossy
+/// UTF-8 decode as the names with NULs in between, so those are dropped.
+/// It exits non-zero when WSL isn't installed or nothing is running.
+fn wsl_distro_running() -> bool {
+    run_tool("wsl.exe", &["--list", "--running", "--quiet"]).is_ok_and(|output| {
+        output
+            .chars()
+            .filter(|c| !matches!(c, '\0' | '\u{feff}' | '\u{fffd}'))
+            .collect::<String>()
+            .lines()
+            .any(|line| !line.trim().is_empty())
+    })
+}
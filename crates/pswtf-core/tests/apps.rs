@@ -0,0 +1,89 @@
+//! Grouping helper processes under their application.
+
+use pswtf_core::apps::{bundle_path, group_by_app, plist_bundle_id};
+use pswtf_core::ProcessInfo;
+
+fn process(pid: i32, parent_pid: Option<i32>, name: &str, exe: &str, cpu: f32) -> ProcessInfo {
+    ProcessInfo {
+        pid,
+        parent_pid,
+        name: name.to_string(),
+        exe: Some(exe.to_string()),
+        cpu_percent: cpu,
+        memory_bytes: 100,
+        ..ProcessInfo::default()
+    }
+}
+
+#[test]
+fn helpers_roll_up_under_their_outermost_bundle() {
+    let chrome = "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome";
+    let helper = "/Applications/Google Chrome.app/Contents/Frameworks/Google Chrome Framework.framework/Versions/122.0/Helpers/Google Chrome Helper (Renderer).app/Contents/MacOS/Google Chrome Helper (Renderer)";
+    let processes = [
+        process(1, None, "launchd", "/sbin/launchd", 0.5),
+        process(500, Some(1), "Google Chrome", chrome, 4.0),
+        process(
+            501,
+            Some(500),
+            "Google Chrome Helper (Renderer)",
+            helper,
+            30.0,
+        ),
+        process(
+            502,
+            Some(500),
+            "Google Chrome Helper (Renderer)",
+            helper,
+            6.0,
+        ),
+        // Linux-style: renderers fork from the browser and keep its exe.
+        process(800, Some(1), "firefox", "/usr/lib/firefox/firefox", 2.0),
+        process(
+            801,
+            Some(800),
+            "Isolated Web Co",
+            "/usr/lib/firefox/firefox",
+            1.0,
+        ),
+        process(900, Some(801), "sh", "/bin/sh", 0.0),
+    ];
+
+    let groups = group_by_app(&processes);
+    let summary = groups
+        .iter()
+        .map(|group| (group.name.as_str(), group.pids.clone(), group.cpu_percent))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        [
+            ("Google Chrome", vec![500, 501, 502], 40.0),
+            ("firefox", vec![800, 801], 3.0),
+            ("launchd", vec![1], 0.5),
+            ("sh", vec![900], 0.0),
+        ]
+    );
+    assert_eq!(
+        groups[0].bundle_path.as_deref(),
+        Some("/Applications/Google Chrome.app")
+    );
+    assert_eq!(groups[0].memory_bytes, 300);
+    assert_eq!(groups[1].key, "pid:800");
+}
+
+#[test]
+fn bundle_paths_and_ids() {
+    assert_eq!(
+        bundle_path(
+            "/System/Applications/Utilities/Activity Monitor.app/Contents/MacOS/Activity Monitor"
+        ),
+        Some("/System/Applications/Utilities/Activity Monitor.app")
+    );
+    assert_eq!(bundle_path("/usr/local/bin/node"), None);
+
+    let plist = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<plist version=\"1.0\">\n<dict>\n\t<key>CFBundleExecutable</key>\n\t<string>Slack</string>\n\t<key>CFBundleIdentifier</key>\n\t<string>com.tinyspeck.slackmacgap</string>\n</dict>\n</plist>\n";
+    assert_eq!(
+        plist_bundle_id(plist).as_deref(),
+        Some("com.tinyspeck.slackmacgap")
+    );
+    assert_eq!(plist_bundle_id("bplist00\u{1}\u{2}"), None);
+}
@@ -0,0 +1,85 @@
+//! Listening-port parsing for the BSD backends, against captured
+//! `sockstat -46l` (FreeBSD) and `fstat` (OpenBSD) output.
+
+use pswtf_core::ports::{ports_from_fstat, ports_from_sockstat};
+use pswtf_core::{PortExposure, PortInfo};
+
+fn summary(port: &PortInfo) -> (u16, &str, &str, Option<i32>, &str, Option<&str>) {
+    (
+        port.port,
+        port.protocol.as_str(),
+        port.local_address.as_str(),
+        port.pid,
+        port.process_name.as_deref().unwrap_or_default(),
+        port.state.as_deref(),
+    )
+}
+
+#[test]
+fn freebsd_sockstat() {
+    let ports = ports_from_sockstat(include_str!("fixtures/sockstat_freebsd.txt"));
+
+    // The ownerless `?` row is skipped and the tcp4/tcp6 wildcard pairs
+    // collapse into one entry each.
+    assert_eq!(
+        ports.iter().map(summary).collect::<Vec<_>>(),
+        [
+            (22, "TCP", "*", Some(812), "sshd", Some("LISTEN")),
+            (80, "TCP", "*", Some(1310), "nginx", Some("LISTEN")),
+            (514, "UDP", "*", Some(610), "syslogd", None),
+            (
+                3000,
+                "TCP",
+                "192.168.1.40",
+                Some(2240),
+                "node",
+                Some("LISTEN")
+            ),
+            (5432, "TCP", "::1", Some(1104), "postgres", Some("LISTEN")),
+            (
+                5432,
+                "TCP",
+                "127.0.0.1",
+                Some(1104),
+                "postgres",
+                Some("LISTEN")
+            ),
+            (
+                8080,
+                "TCP",
+                "127.0.0.1",
+                Some(3012),
+                "my app",
+                Some("LISTEN")
+            ),
+        ]
+    );
+    assert_eq!(ports[3].exposure, PortExposure::Lan);
+    assert_eq!(ports[4].service.as_deref(), Some("postgres"));
+}
+
+#[test]
+fn openbsd_fstat() {
+    let ports = ports_from_fstat(include_str!("fixtures/fstat_openbsd.txt"));
+
+    // Connected sockets (`<--`, `-->`) and non-internet files are skipped.
+    assert_eq!(
+        ports.iter().map(summary).collect::<Vec<_>>(),
+        [
+            (22, "TCP", "*", Some(48213), "sshd", Some("LISTEN")),
+            (
+                53,
+                "TCP",
+                "127.0.0.1",
+                Some(61930),
+                "unbound",
+                Some("LISTEN")
+            ),
+            (53, "UDP", "::1", Some(61930), "unbound", None),
+            (80, "TCP", "*", Some(70211), "httpd", Some("LISTEN")),
+            (123, "UDP", "127.0.0.1", Some(27431), "ntpd", None),
+        ]
+    );
+    assert_eq!(ports[0].exposure, PortExposure::All);
+    assert_eq!(ports[2].exposure, PortExposure::Loopback);
+}
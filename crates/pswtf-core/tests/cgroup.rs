@@ -0,0 +1,57 @@
+//! cgroup limits resolved against small cgroupfs trees under
+//! `fixtures/cgroup_v1` and `fixtures/cgroup_v2`.
+
+use std::path::PathBuf;
+
+use pswtf_core::cgroup::{cgroup_limits, parse_cpu_max};
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+#[test]
+fn unified_hierarchy_takes_the_tightest_ancestor_limit() {
+    let info = cgroup_limits("0::/system.slice/docker-abc.scope\n", &fixture("cgroup_v2"))
+        .expect("cgroup resolves");
+
+    assert_eq!(info.version, 2);
+    assert_eq!(info.path, "/system.slice/docker-abc.scope");
+    // The scope itself says `max`; the slice above it caps memory at 512 MiB.
+    assert_eq!(info.memory_limit_bytes, Some(512 * 1024 * 1024));
+    assert_eq!(info.memory_usage_bytes, Some(384 * 1024 * 1024));
+    assert_eq!(info.cpu_limit_cores, Some(2.0));
+    assert_eq!(info.pids_limit, Some(128));
+    assert_eq!(info.oom_kills, Some(2));
+    assert_eq!(info.throttled_periods, Some(411));
+}
+
+#[test]
+fn v1_controllers_are_read_from_their_own_hierarchies() {
+    let membership = "\
+12:pids:/
+5:memory:/build
+4:cpu,cpuacct:/build
+1:name=systemd:/user.slice
+0::/user.slice
+";
+    let info = cgroup_limits(membership, &fixture("cgroup_v1")).expect("cgroup resolves");
+
+    assert_eq!(info.version, 1);
+    assert_eq!(info.path, "/build");
+    assert_eq!(info.memory_limit_bytes, Some(2 * 1024 * 1024 * 1024));
+    assert_eq!(info.memory_usage_bytes, Some(1_288_490_188));
+    assert_eq!(info.cpu_limit_cores, Some(0.5));
+    assert_eq!(info.pids_limit, None);
+    assert_eq!(info.oom_kills, Some(1));
+    assert_eq!(info.throttled_periods, Some(12));
+}
+
+#[test]
+fn cpu_max_formats() {
+    assert_eq!(parse_cpu_max("150000 100000"), Some(1.5));
+    assert_eq!(parse_cpu_max("max 100000"), None);
+    assert_eq!(parse_cpu_max("-1 100000"), None);
+    assert_eq!(parse_cpu_max("50000"), Some(0.5));
+}
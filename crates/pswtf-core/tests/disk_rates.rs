@@ -0,0 +1,34 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use pswtf_core::system::{disk_rates, CounterRates};
+
+#[test]
+fn mounts_on_one_device_share_its_rate() {
+    let mut rates = CounterRates::default();
+    let start = Instant::now();
+    let mounts = ["nvme0n1p2", "nvme0n1p2", "sda1"];
+
+    let before = HashMap::from([
+        ("nvme0n1p2".to_string(), (1_000, 4_000)),
+        ("sda1".to_string(), (0, 0)),
+    ]);
+    let first = disk_rates(mounts, &before, &mut rates, start);
+    assert_eq!(first["nvme0n1p2"], (None, None));
+
+    let after = HashMap::from([
+        ("nvme0n1p2".to_string(), (3_000, 5_000)),
+        ("sda1".to_string(), (512, 0)),
+    ]);
+    let second = disk_rates(mounts, &after, &mut rates, start + Duration::from_secs(2));
+    assert_eq!(second.len(), 2);
+    assert_eq!(second["nvme0n1p2"], (Some(1_000.0), Some(500.0)));
+    assert_eq!(second["sda1"], (Some(256.0), Some(0.0)));
+}
+
+#[test]
+fn devices_without_counters_have_no_rate() {
+    let mut rates = CounterRates::default();
+    let rates = disk_rates(["disk3s1"], &HashMap::new(), &mut rates, Instant::now());
+    assert_eq!(rates["disk3s1"], (None, None));
+}
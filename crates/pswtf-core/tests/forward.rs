@@ -0,0 +1,30 @@
+use pswtf_core::forward::parse_forward;
+
+fn args(command: &str) -> Vec<String> {
+    command.split_whitespace().map(str::to_string).collect()
+}
+
+#[test]
+fn ssh_forward_in_a_flag_cluster() {
+    let forward = parse_forward(&args("ssh -fNL 8080:db:5432 bastion"), 8080).unwrap();
+    assert_eq!(forward.target, "db:5432");
+    assert_eq!(forward.remote_port, Some(5432));
+    assert_eq!(forward.via.as_deref(), Some("bastion"));
+
+    let attached = parse_forward(&args("ssh -NL8080:db:5432 bastion"), 8080).unwrap();
+    assert_eq!(attached.target, "db:5432");
+    assert_eq!(attached.via.as_deref(), Some("bastion"));
+}
+
+#[test]
+fn ssh_valued_flag_ends_the_cluster() {
+    // -p takes the port, so the L after it belongs to the value.
+    let forward = parse_forward(
+        &args("ssh -Np 2222 -L 127.0.0.1:9000:[::1]:9001 user@bastion"),
+        9000,
+    )
+    .unwrap();
+    assert_eq!(forward.target, "[::1]:9001");
+    assert_eq!(forward.via.as_deref(), Some("user@bastion"));
+    assert!(parse_forward(&args("ssh -pL 8080:db:5432 bastion"), 8080).is_none());
+}
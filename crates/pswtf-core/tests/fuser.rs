@@ -0,0 +1,153 @@
+//! Finding the processes that hold files under a path.
+
+use std::path::Path;
+
+use pswtf_core::fuser::file_users_from_lsof;
+use pswtf_core::{FileUse, FileUseKind};
+
+fn file_use(kind: FileUseKind, fd: Option<i32>, path: &str) -> FileUse {
+    FileUse {
+        kind,
+        fd,
+        path: path.to_string(),
+    }
+}
+
+#[test]
+fn lsof_files_under_a_volume() {
+    let users = file_users_from_lsof(
+        include_str!("fixtures/lsof_file_users_macos.txt"),
+        Path::new("/Volumes/Backup"),
+    );
+
+    let summary = users
+        .iter()
+        .map(|user| (user.pid, user.name.as_str(), user.uses.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        [
+            (
+                412,
+                "mds_stores",
+                vec![
+                    file_use(
+                        FileUseKind::Open,
+                        Some(5),
+                        "/Volumes/Backup/.Spotlight-V100/Store-V2/store.db"
+                    ),
+                    file_use(
+                        FileUseKind::Open,
+                        Some(6),
+                        "/Volumes/Backup/.Spotlight-V100/Store-V2/store.db"
+                    ),
+                ]
+            ),
+            (
+                8123,
+                "zsh",
+                vec![file_use(FileUseKind::Cwd, None, "/Volumes/Backup/photos")]
+            ),
+            (
+                9004,
+                "Preview",
+                vec![file_use(
+                    FileUseKind::Open,
+                    Some(12),
+                    "/Volumes/Backup/photos/IMG 0001.jpg"
+                )]
+            ),
+            (
+                9100,
+                "rg",
+                vec![file_use(
+                    FileUseKind::Executable,
+                    None,
+                    "/Volumes/Backup/tools/bin/rg"
+                )]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn paths_are_matched_by_component() {
+    // `/Volumes/Backup 2` is a different volume, not a child of `/Volumes/Backup`.
+    let output = "p1\ncbash\nfcwd\ntDIR\nn/Volumes/Backup 2/docs\n";
+    assert!(file_users_from_lsof(output, Path::new("/Volumes/Backup")).is_empty());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn finds_this_process_holding_a_file() {
+    let dir = std::env::temp_dir().join(format!("pswtf-fuser-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("held.txt");
+    let _file = std::fs::File::create(&path).unwrap();
+
+    let users = pswtf_core::fuser::find_file_users(&dir).unwrap();
+    let own = users
+        .iter()
+        .find(|user| user.pid == std::process::id() as i32)
+        .expect("the test process holds the file");
+    assert!(own
+        .uses
+        .iter()
+        .any(|entry| entry.kind == FileUseKind::Open && entry.path.ends_with("held.txt")));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn maps_lines_carry_their_device() {
+    use pswtf_core::fuser::parse_maps_file;
+
+    let line = "7f1d0a000000-7f1d0a021000 r--p 00000000 fe:01 280542                     /usr/lib/x86_64-linux-gnu/libc.so.6";
+    assert_eq!(
+        parse_maps_file(line),
+        Some((makedev(0xfe, 0x01), "/usr/lib/x86_64-linux-gnu/libc.so.6"))
+    );
+    assert_eq!(
+        parse_maps_file("7ffd5c1e0000-7ffd5c201000 rw-p 00000000 00:00 0    [stack]"),
+        None
+    );
+}
+
+/// glibc's `makedev` layout, spelled out so the test doesn't lean on the
+/// same function the code uses.
+#[cfg(target_os = "linux")]
+fn makedev(major: u64, minor: u64) -> u64 {
+    ((major & 0xfffff000) << 32)
+        | ((major & 0xfff) << 8)
+        | ((minor & 0xffffff00) << 12)
+        | (minor & 0xff)
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn mount_blockers_skip_files_on_nested_mounts() {
+    use std::os::unix::fs::MetadataExt;
+
+    // /proc is its own filesystem mounted inside /, so holding a file there
+    // doesn't keep / busy even though the path is under it.
+    let root_device = std::fs::metadata("/").unwrap().dev();
+    let proc_device = std::fs::metadata("/proc/self/status").unwrap().dev();
+    assert_ne!(root_device, proc_device);
+    let _nested = std::fs::File::open("/proc/self/status").unwrap();
+
+    let holds_proc_file = |users: Vec<pswtf_core::FileUser>| {
+        users
+            .iter()
+            .filter(|user| user.pid == std::process::id() as i32)
+            .flat_map(|user| &user.uses)
+            .any(|entry| entry.path.starts_with("/proc/"))
+    };
+    // By path it is under /; as a mount blocker it isn't.
+    assert!(holds_proc_file(
+        pswtf_core::fuser::find_file_users(Path::new("/")).unwrap()
+    ));
+    assert!(!holds_proc_file(
+        pswtf_core::fuser::find_mount_blockers(Path::new("/")).unwrap()
+    ));
+}
@@ -0,0 +1,112 @@
+//! Per-process details read straight from the OS, checked against the test
+//! process itself.
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+#[test]
+fn counters_for_a_running_process() {
+    // Touch fresh pages and yield a few times so every counter has moved.
+    let pages = vec![1u8; 4 * 1024 * 1024];
+    for _ in 0..5 {
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+    assert_eq!(
+        pages.iter().map(|&byte| u64::from(byte)).sum::<u64>(),
+        4 * 1024 * 1024
+    );
+
+    let counters =
+        pswtf_core::inspect::collect_process_counters(std::process::id() as i32).unwrap();
+    assert!(counters.minor_page_faults.unwrap() > 0);
+    assert!(counters.major_page_faults.is_some());
+    assert!(counters.context_switches.unwrap() > 0);
+    #[cfg(target_os = "linux")]
+    assert!(counters.voluntary_context_switches.unwrap() >= 5);
+}
+
+#[test]
+fn proc_limits_are_split_on_the_header_columns() {
+    let limits = pswtf_core::inspect::parse_proc_limits(
+        "Limit                     Soft Limit           Hard Limit           Units     \n\
+         Max cpu time              unlimited            unlimited            seconds   \n\
+         Max core file size        0                    unlimited            bytes     \n\
+         Max open files            1024                 524288               files     \n\
+         Max nice priority         0                    0                    \n",
+    );
+
+    let open_files = limits
+        .iter()
+        .find(|limit| limit.name == "Max open files")
+        .unwrap();
+    assert_eq!(open_files.soft, Some(1024));
+    assert_eq!(open_files.hard, Some(524288));
+    assert_eq!(open_files.unit.as_deref(), Some("files"));
+    assert_eq!(limits[0].soft, None);
+    assert_eq!(limits[1].soft, Some(0));
+    assert_eq!(limits[1].hard, None);
+    assert_eq!(limits[3].unit, None);
+    assert_eq!(limits.len(), 4);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn fd_table_reports_flags_and_offsets() {
+    use std::io::{Seek, SeekFrom, Write};
+    use std::os::fd::AsRawFd;
+
+    let path = std::env::temp_dir().join(format!("pswtf-fd-table-{}", std::process::id()));
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .unwrap();
+    file.write_all(b"hello world").unwrap();
+    file.seek(SeekFrom::Start(5)).unwrap();
+
+    let table = pswtf_core::inspect::collect_fd_table(std::process::id() as i32).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let entry = table
+        .iter()
+        .find(|entry| entry.file.fd == file.as_raw_fd())
+        .unwrap();
+    assert_eq!(entry.file.kind, "file");
+    assert_eq!(entry.offset, Some(5));
+    assert_eq!(entry.flags[0], "O_WRONLY");
+    assert!(entry.flags.contains(&"O_APPEND"));
+    // Rust opens everything close-on-exec.
+    assert!(entry.flags.contains(&"O_CLOEXEC"));
+    assert_eq!(entry.watches, None);
+}
+
+#[test]
+fn wait_channels_get_a_plain_reason() {
+    use pswtf_core::inspect::wait_reason;
+
+    assert_eq!(
+        wait_reason("futex_wait_queue_me"),
+        Some("lock or condition variable (futex)")
+    );
+    assert_eq!(
+        wait_reason("do_epoll_wait"),
+        Some("waiting for I/O on fds (epoll)")
+    );
+    assert_eq!(
+        wait_reason("inet_csk_accept"),
+        Some("waiting for a connection (accept)")
+    );
+    assert_eq!(wait_reason("some_driver_ioctl"), None);
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+#[test]
+fn thread_waits_cover_every_thread() {
+    let parked = std::thread::spawn(std::thread::park);
+    let waits = pswtf_core::inspect::collect_thread_waits(std::process::id() as i32).unwrap();
+    let threads = waits.iter().map(|wait| wait.threads).sum::<u32>();
+    parked.thread().unpark();
+    parked.join().unwrap();
+
+    assert!(threads >= 2);
+    assert!(waits
+        .windows(2)
+        .all(|pair| pair[0].threads >= pair[1].threads));
+}
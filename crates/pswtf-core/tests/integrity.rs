@@ -0,0 +1,49 @@
+//! Executable fingerprints: SHA-256 of a file and captured `codesign -dv
+//! --verbose=2` output from macOS 14.
+
+use pswtf_core::integrity::{parse_codesign, sha256_file};
+
+#[test]
+fn sha256_of_a_file() {
+    let path = std::env::temp_dir().join(format!("pswtf-integrity-{}", std::process::id()));
+    std::fs::write(&path, b"abc").unwrap();
+    let hash = sha256_file(path.to_str().unwrap());
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(
+        hash.as_deref(),
+        Some("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+    );
+    assert_eq!(sha256_file("/nonexistent/pswtf-integrity"), None);
+}
+
+#[test]
+fn developer_id_signature() {
+    let signature = parse_codesign(include_str!("fixtures/codesign_developer_id.txt"));
+
+    assert!(signature.signed);
+    assert!(!signature.adhoc);
+    assert_eq!(signature.identifier.as_deref(), Some("com.docker.backend"));
+    assert_eq!(signature.team_id.as_deref(), Some("9BNSXJN65R"));
+    assert_eq!(
+        signature.authorities,
+        [
+            "Developer ID Application: Docker Inc (9BNSXJN65R)",
+            "Developer ID Certification Authority",
+            "Apple Root CA",
+        ]
+    );
+}
+
+#[test]
+fn adhoc_and_unsigned_binaries() {
+    let adhoc = parse_codesign(include_str!("fixtures/codesign_adhoc.txt"));
+    assert!(adhoc.signed);
+    assert!(adhoc.adhoc);
+    assert_eq!(adhoc.team_id, None);
+    assert!(adhoc.authorities.is_empty());
+
+    let unsigned = parse_codesign(include_str!("fixtures/codesign_unsigned.txt"));
+    assert!(!unsigned.signed);
+    assert_eq!(unsigned.identifier, None);
+}
@@ -0,0 +1,38 @@
+//! Working out per PID what a partly failed elevated kill did.
+
+use pswtf_core::kill::elevated_kill_failures;
+
+#[test]
+fn only_the_pids_kill_complained_about_failed() {
+    let error = "kill: (4243): No such process";
+    let failures = elevated_kill_failures(&[4242, 4243, 4244], error, |_| true);
+    assert_eq!(
+        failures,
+        [
+            None,
+            Some("kill: (4243): No such process".to_string()),
+            None
+        ]
+    );
+}
+
+#[test]
+fn a_dismissed_prompt_fails_whatever_is_still_running() {
+    let error = "Error executing command as another user: Request dismissed";
+    let failures = elevated_kill_failures(&[4242, 4243], error, |pid| pid == 4243);
+    assert_eq!(failures, [None, Some(error.to_string())]);
+}
+
+#[test]
+fn pids_are_matched_as_whole_numbers() {
+    // 424 is a prefix of 4242 but isn't what kill named.
+    let error = "kill: 4242: Operation not permitted";
+    let failures = elevated_kill_failures(&[424, 4242], error, |_| true);
+    assert_eq!(
+        failures,
+        [
+            None,
+            Some("kill: 4242: Operation not permitted".to_string())
+        ]
+    );
+}
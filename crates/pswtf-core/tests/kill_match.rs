@@ -0,0 +1,121 @@
+//! Matching kills: shell-style globs, case folding and exclusions.
+
+use pswtf_core::kill::{find_matching_processes, MatchMode, MatchOptions, ProcessMatcher};
+use pswtf_core::ProcessInfo;
+
+fn process(name: &str, cmd: &str) -> ProcessInfo {
+    ProcessInfo {
+        name: name.to_string(),
+        cmd: cmd.to_string(),
+        ..ProcessInfo::default()
+    }
+}
+
+#[test]
+fn globs_match_the_whole_name_or_command_line() {
+    let python = process("python3.11", "/usr/bin/python3.11 manage.py runserver");
+    let webpack = process(
+        "node",
+        "node /app/node_modules/.bin/webpack-dev-server --hot",
+    );
+    let pytest = process("Python", "/usr/local/bin/python3.12 -m pytest");
+
+    let glob = |pattern: &str| ProcessMatcher::new(pattern, MatchMode::Glob).unwrap();
+
+    assert!(glob("python3.*").matches(&python));
+    assert!(glob("python3.*").matches(&pytest));
+    assert!(!glob("python3.*").matches(&webpack));
+    assert!(glob("*webpack*dev*").matches(&webpack));
+    // Anchored: a bare word only matches a process named exactly that.
+    assert!(glob("NODE").matches(&webpack));
+    assert!(!glob("webpack").matches(&webpack));
+    assert!(glob("python3.1[!0]").matches(&python));
+    assert!(!glob("python3.1[!12]").matches(&pytest));
+    assert!(glob("python?.??").matches(&python));
+}
+
+#[test]
+fn malformed_globs_are_rejected() {
+    assert!(ProcessMatcher::new("python[3", MatchMode::Glob).is_err());
+}
+
+#[test]
+fn case_folding_is_unicode_aware_and_can_be_turned_off() {
+    let app = process("Ärger-Sync", "/opt/Ärger/Ärger-Sync --daemon");
+    let matcher = |query: &str, mode, case_sensitive| {
+        ProcessMatcher::with_case(query, mode, case_sensitive).unwrap()
+    };
+
+    assert!(matcher("ärger", MatchMode::Substring, false).matches(&app));
+    assert!(matcher("ärger-sync", MatchMode::Exact, false).matches(&app));
+    assert!(!matcher("ärger", MatchMode::Substring, true).matches(&app));
+    assert!(matcher("Ärger", MatchMode::Substring, true).matches(&app));
+    assert!(!matcher("ärger-sync", MatchMode::Exact, true).matches(&app));
+    assert!(!matcher("ärger-*", MatchMode::Glob, true).matches(&app));
+    assert!(matcher("ärger-*", MatchMode::Glob, false).matches(&app));
+}
+
+#[test]
+fn exclusions_spare_matches_and_everything_below_them() {
+    let tree = |pid, parent_pid, name: &str, cmd: &str| ProcessInfo {
+        pid,
+        parent_pid,
+        ..process(name, cmd)
+    };
+    let processes = [
+        tree(100, None, "node", "node server.js"),
+        tree(101, Some(100), "node", "node worker.js"),
+        tree(200, None, "nvim", "nvim src/main.rs"),
+        tree(
+            201,
+            Some(200),
+            "node",
+            "node typescript-language-server --stdio",
+        ),
+        tree(300, None, "node", "node eslint-lsp --stdio"),
+    ];
+    let options = MatchOptions {
+        match_mode: MatchMode::Regex,
+        include_children: true,
+        only_current_user: false,
+        case_sensitive: false,
+        exclude: vec!["nvim|lsp".to_string()],
+        include_self: false,
+    };
+
+    let set = find_matching_processes(&processes, "node", &options).unwrap();
+    let mut targets = set
+        .roots
+        .iter()
+        .chain(&set.descendants)
+        .map(|process| process.pid)
+        .collect::<Vec<_>>();
+    targets.sort_unstable();
+    // 201 matches "node" itself but runs under the excluded nvim.
+    assert_eq!(targets, [100, 101]);
+}
+
+#[test]
+fn every_mode_folds_case_the_same_way() {
+    let street = process("straße-sync", "/opt/straße-sync --watch");
+    let greek = process("ΟΔΥΣΣΕΥΣ", "/opt/odysseus/ΟΔΥΣΣΕΥΣ");
+    let modes = [
+        MatchMode::Substring,
+        MatchMode::Exact,
+        MatchMode::Word,
+        MatchMode::Regex,
+        MatchMode::Glob,
+    ];
+    let all_modes = |query: &str, target: &ProcessInfo| {
+        modes
+            .iter()
+            .map(|mode| ProcessMatcher::new(query, *mode).unwrap().matches(target))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(all_modes("STRASSE-SYNC", &street), [false; 5]);
+    assert_eq!(all_modes("STRAßE-SYNC", &street), [true; 5]);
+    // Final and medial sigma both fold to Σ.
+    assert_eq!(all_modes("οδυσσευς", &greek), [true; 5]);
+    assert_eq!(all_modes("οδυσσευσ", &greek), [true; 5]);
+}
@@ -0,0 +1,36 @@
+//! Killing everything on a terminal.
+
+use pswtf_core::kill::kill_by_tty;
+use pswtf_core::ProcessInfo;
+
+fn on_tty(pid: i32, parent_pid: Option<i32>, tty: &str) -> ProcessInfo {
+    ProcessInfo {
+        pid,
+        parent_pid,
+        name: "zsh".to_string(),
+        tty: Some(tty.to_string()),
+        ..ProcessInfo::default()
+    }
+}
+
+#[test]
+fn empty_or_idle_terminals_are_refused() {
+    let processes = [on_tty(900_001, None, "pts/7")];
+
+    assert!(kill_by_tty(&processes, " ", None, false).is_err());
+    let error = kill_by_tty(&processes, "/dev/pts/8", None, false).unwrap_err();
+    assert_eq!(error, "Nothing is running on pts/8");
+}
+
+#[test]
+fn pswtf_and_its_ancestors_are_left_off_their_terminal() {
+    // The test binary standing in for pswtf, started from a shell on pts/7.
+    let self_pid = std::process::id() as i32;
+    let processes = [
+        on_tty(900_001, None, "pts/7"),
+        on_tty(self_pid, Some(900_001), "pts/7"),
+    ];
+
+    let error = kill_by_tty(&processes, "pts/7", None, false).unwrap_err();
+    assert_eq!(error, "Nothing is running on pts/7");
+}
@@ -0,0 +1,228 @@
+//! Parsing of captured `lsof -F pcftdPnT` output. The Linux fixtures come
+//! from lsof 4.95; the macOS ones follow the format lsof prints there, where
+//! devices are kernel addresses and Unix sockets name their peer by address.
+
+use pswtf_core::lsof::parse_fields;
+use pswtf_core::ports::{
+    connections_from_lsof, ports_from_lsof, socket_from_lsof, unix_sockets_from_lsof,
+};
+use pswtf_core::{PortExposure, PortInfo, UnixSocketPeer};
+
+fn summary(port: &PortInfo) -> (u16, &str, &str, Option<i32>, &str) {
+    (
+        port.port,
+        port.protocol.as_str(),
+        port.local_address.as_str(),
+        port.pid,
+        port.process_name.as_deref().unwrap_or_default(),
+    )
+}
+
+#[test]
+fn listening_ports_on_linux() {
+    let ports = ports_from_lsof(include_str!("fixtures/lsof_listen_linux.txt"));
+
+    assert_eq!(
+        ports.iter().map(summary).collect::<Vec<_>>(),
+        [
+            (53, "TCP", "127.0.0.53", Some(633), "systemd-resolve"),
+            (53, "UDP", "127.0.0.53", Some(633), "systemd-resolve"),
+            // The IPv4 and IPv6 wildcard listeners collapse into one entry.
+            (80, "TCP", "*", Some(812), "nginx"),
+            (5432, "TCP", "::1", Some(1450), "postgres"),
+            (5432, "TCP", "127.0.0.1", Some(1450), "postgres"),
+            (8000, "TCP", "192.168.1.20", Some(30115), "python3"),
+            (18778, "TCP", "*", Some(30117), "My Server"),
+        ]
+    );
+
+    assert_eq!(
+        ports.iter().map(|port| port.exposure).collect::<Vec<_>>(),
+        [
+            PortExposure::Loopback,
+            PortExposure::Loopback,
+            PortExposure::All,
+            PortExposure::Loopback,
+            PortExposure::Loopback,
+            PortExposure::Lan,
+            PortExposure::All,
+        ]
+    );
+    assert_eq!(ports[0].state.as_deref(), Some("LISTEN"));
+    assert_eq!(ports[1].state, None);
+    assert_eq!(ports[3].service.as_deref(), Some("postgres"));
+}
+
+#[test]
+fn listening_ports_on_macos_keep_command_names_with_spaces() {
+    let ports = ports_from_lsof(include_str!("fixtures/lsof_listen_macos.txt"));
+
+    assert_eq!(
+        ports.iter().map(summary).collect::<Vec<_>>(),
+        [
+            (3000, "TCP", "::1", Some(8123), "node"),
+            (5353, "UDP", "*", Some(734), "Google Chrome Helper"),
+            (7000, "TCP", "*", Some(999), "ControlCenter"),
+            (49152, "TCP", "*", Some(501), "rapportd"),
+        ]
+    );
+}
+
+#[test]
+fn established_connections() {
+    let connections = connections_from_lsof(include_str!("fixtures/lsof_established_linux.txt"));
+
+    let remotes = connections
+        .iter()
+        .map(|entry| {
+            (
+                entry.pid,
+                entry.protocol.as_str(),
+                entry.local_port,
+                entry.remote_address.as_str(),
+                entry.remote_port,
+            )
+        })
+        .collect::<Vec<_>>();
+    // The duplicated fd 53 is listed once and the unconnected UDP socket
+    // is dropped.
+    assert_eq!(
+        remotes,
+        [
+            (Some(2211), "UDP", 40112, "1.1.1.1", 53),
+            (Some(2211), "TCP", 51522, "2606:4700::1111", 443),
+            (Some(2211), "TCP", 51530, "93.184.216.34", 443),
+            (Some(30115), "TCP", 18777, "127.0.0.1", 42368),
+            (Some(30160), "TCP", 42368, "127.0.0.1", 18777),
+        ]
+    );
+    assert_eq!(connections[0].process_name.as_deref(), Some("Web Content"));
+    assert_eq!(connections[1].local_address, "2001:db8::5");
+    assert_eq!(connections[1].state.as_deref(), Some("ESTABLISHED"));
+    assert_eq!(connections[0].state, None);
+}
+
+fn peer(pid: i32, process_name: &str) -> UnixSocketPeer {
+    UnixSocketPeer {
+        pid,
+        process_name: process_name.to_string(),
+    }
+}
+
+#[test]
+fn unix_sockets_on_linux_name_peers_inline() {
+    let sockets = unix_sockets_from_lsof(include_str!("fixtures/lsof_unix_linux.txt"));
+
+    let rows = sockets
+        .iter()
+        .map(|socket| {
+            (
+                socket.path.as_deref(),
+                socket.socket_type.as_deref(),
+                socket.state.as_deref(),
+                socket.pid,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        rows,
+        [
+            (
+                Some("/run/docker.sock"),
+                Some("STREAM"),
+                Some("LISTEN"),
+                Some(1800)
+            ),
+            (
+                Some("/run/docker.sock"),
+                Some("STREAM"),
+                Some("CONNECTED"),
+                Some(1800)
+            ),
+            (
+                Some("/run/user/1000/My Socket.sock"),
+                Some("DGRAM"),
+                Some("UNCONNECTED"),
+                Some(1044)
+            ),
+            (
+                Some("/tmp/app.sock"),
+                Some("STREAM"),
+                Some("LISTEN"),
+                Some(28425)
+            ),
+            (
+                Some("/tmp/app.sock"),
+                Some("STREAM"),
+                Some("CONNECTED"),
+                Some(28425)
+            ),
+            (
+                Some("@/tmp/.X11-unix/X0"),
+                Some("STREAM"),
+                Some("CONNECTED"),
+                Some(1044)
+            ),
+            (None, Some("STREAM"), Some("CONNECTED"), Some(28425)),
+        ]
+    );
+
+    assert!(sockets[0].peers.is_empty());
+    assert_eq!(
+        sockets[1].peers,
+        [peer(3310, "Docker Desktop"), peer(3312, "docker")]
+    );
+    assert_eq!(sockets[4].peers, [peer(28425, "python3")]);
+    assert_eq!(sockets[6].peers, [peer(28425, "python3")]);
+}
+
+#[test]
+fn unix_sockets_on_macos_resolve_peers_by_address() {
+    let sockets = unix_sockets_from_lsof(include_str!("fixtures/lsof_unix_macos.txt"));
+
+    assert_eq!(sockets.len(), 2);
+    assert_eq!(sockets[0].path.as_deref(), Some("/var/run/mDNSResponder"));
+    assert_eq!(sockets[0].pid, Some(212));
+    assert!(sockets[0].peers.is_empty());
+
+    assert_eq!(sockets[1].path, None);
+    assert_eq!(
+        sockets[1].process_name.as_deref(),
+        Some("Google Chrome Helper")
+    );
+    assert_eq!(sockets[1].peers, [peer(212, "mDNSResponder")]);
+}
+
+#[test]
+fn file_sets_keep_every_field() {
+    let processes = parse_fields(include_str!("fixtures/lsof_files_macos.txt"));
+
+    assert_eq!(processes.len(), 1);
+    let files = &processes[0].files;
+    assert_eq!(files.len(), 6);
+    assert_eq!(files[0].fd, "cwd");
+    assert_eq!(files[0].file_type.as_deref(), Some("DIR"));
+    assert_eq!(files[0].name.as_deref(), Some("/Users/dev/Projects/My App"));
+    assert_eq!(files[4].device.as_deref(), Some("16,3"));
+
+    assert!(socket_from_lsof(&files[0]).is_none());
+    let socket = socket_from_lsof(&files[5]).expect("TCP file is a socket");
+    assert_eq!(
+        (socket.local_address.as_str(), socket.local_port),
+        ("::1", 3000)
+    );
+    assert_eq!(socket.state.as_deref(), Some("LISTEN"));
+}
+
+#[test]
+fn fields_outside_a_process_set_are_ignored() {
+    let processes = parse_fields(
+        "lsof: WARNING: can't stat() fuse file system\nf3\nn/tmp/x\np42\ncsleep\nX9\nf1\ntCHR\n\n",
+    );
+
+    assert_eq!(processes.len(), 1);
+    assert_eq!(processes[0].pid, 42);
+    assert_eq!(processes[0].command, "sleep");
+    assert_eq!(processes[0].files.len(), 1);
+    assert_eq!(processes[0].files[0].file_type.as_deref(), Some("CHR"));
+}
@@ -0,0 +1,71 @@
+//! Memory maps from Linux smaps and macOS vmmap output.
+
+use pswtf_core::memmap::{parse_smaps, parse_vmmap};
+
+#[test]
+fn smaps_regions_carry_their_resident_and_swapped_sizes() {
+    let regions = parse_smaps(
+        "55d0c8a00000-55d0c8a02000 r--p 00000000 fe:00 280762                     /usr/bin/sleep\n\
+         Size:                  8 kB\n\
+         Rss:                   8 kB\n\
+         Swap:                  0 kB\n\
+         VmFlags: rd mr mw me\n\
+         7f0e40000000-7f0e48000000 rw-p 00000000 00:00 0 \n\
+         Size:             131072 kB\n\
+         Rss:              120000 kB\n\
+         Swap:               2048 kB\n\
+         7ffc1a3e0000-7ffc1a401000 rw-p 00000000 00:00 0                          [stack]\n\
+         Rss:                  16 kB\n\
+         ffffffffff600000-ffffffffff601000 --xp 00000000 00:00 0                  [vsyscall]\n",
+    );
+
+    assert_eq!(regions.len(), 4);
+    assert_eq!(regions[0].path.as_deref(), Some("/usr/bin/sleep"));
+    assert!(regions[0].file_backed);
+    assert_eq!(regions[0].resident_bytes, Some(8 * 1024));
+
+    let anonymous = &regions[1];
+    assert_eq!(anonymous.start, "0x7f0e40000000");
+    assert_eq!(anonymous.size_bytes, 128 * 1024 * 1024);
+    assert_eq!(anonymous.permissions, "rw-p");
+    assert_eq!(anonymous.path, None);
+    assert!(!anonymous.file_backed);
+    assert_eq!(anonymous.resident_bytes, Some(120_000 * 1024));
+    assert_eq!(anonymous.swapped_bytes, Some(2048 * 1024));
+
+    assert_eq!(regions[2].path.as_deref(), Some("[stack]"));
+    assert!(!regions[2].file_backed);
+    assert_eq!(regions[2].swapped_bytes, None);
+    assert_eq!(regions[3].end, "0xffffffffff601000");
+}
+
+#[test]
+fn vmmap_region_lines_are_parsed_and_the_rest_skipped() {
+    let regions = parse_vmmap(
+        "Process:         sleep [4242]\n\
+         ==== Writable regions for process 4242\n\
+         REGION TYPE                    START - END         [ VSIZE  RSDNT  DIRTY   SWAP] PRT/MAX SHRMOD PURGE    REGION DETAIL\n\
+         __TEXT                      102a1c000-102a20000    [   16K    16K     0K     0K] r-x/r-x SM=COW          /bin/sleep\n\
+         MALLOC_SMALL                128000000-128800000    [ 8192K  1.5M   32K     0K] rw-/rwx SM=PRV          DefaultMallocZone_0x102a2c000\n\
+         shared memory               102a30000-102a34000    [   16K    16K    16K     0K] rw-/rw- SM=SHM\n\
+         ==== Summary for process 4242\n\
+         TOTAL                                               [ 8224K  1.5M   48K     0K]\n",
+    );
+
+    assert_eq!(regions.len(), 3);
+    assert_eq!(regions[0].path.as_deref(), Some("/bin/sleep"));
+    assert!(regions[0].file_backed);
+    assert_eq!(regions[0].permissions, "r-xp");
+    assert_eq!(regions[0].resident_bytes, Some(16 * 1024));
+
+    assert_eq!(regions[1].size_bytes, 8 * 1024 * 1024);
+    assert_eq!(regions[1].resident_bytes, Some(1536 * 1024));
+    assert!(!regions[1].file_backed);
+    assert_eq!(
+        regions[1].path.as_deref(),
+        Some("DefaultMallocZone_0x102a2c000")
+    );
+
+    assert_eq!(regions[2].permissions, "rw-s");
+    assert_eq!(regions[2].path, None);
+}
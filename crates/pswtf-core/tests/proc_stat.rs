@@ -0,0 +1,38 @@
+//! The one read of `/proc/<pid>/stat` behind group, session, tty and CPU time.
+
+use pswtf_core::process::{parse_proc_stat, tty_name, ProcStat};
+
+#[test]
+fn stat_fields_after_an_awkward_command_name() {
+    let stat = "4242 (tmux: server) (x) S 1 4242 4242 34816 4242 4194560 1203 0 0 0 \
+                1530 412 0 0 20 0 1 0 98765 12345678 900 18446744073709551615";
+    assert_eq!(
+        parse_proc_stat(stat),
+        Some(ProcStat {
+            pgrp: 4242,
+            session: 4242,
+            tty_nr: 34816,
+            utime_ticks: 1530,
+            stime_ticks: 412,
+        })
+    );
+    assert_eq!(parse_proc_stat("4242 (cut short) S 1 4242"), None);
+}
+
+#[test]
+fn tty_numbers_as_ps_names_them() {
+    assert_eq!(tty_name(34816).as_deref(), Some("pts/0"));
+    assert_eq!(tty_name((136 << 8) | 3).as_deref(), Some("pts/3"));
+    assert_eq!(tty_name((4 << 8) | 2).as_deref(), Some("tty2"));
+    assert_eq!(tty_name((4 << 8) | 65).as_deref(), Some("ttyS1"));
+    assert_eq!(tty_name(0), None);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn this_process_reads_back_its_own_ids() {
+    let stat = std::fs::read_to_string("/proc/self/stat").unwrap();
+    let stat = parse_proc_stat(&stat).unwrap();
+    assert_eq!(stat.pgrp, nix::unistd::getpgrp().as_raw());
+    assert_eq!(stat.session, nix::unistd::getsid(None).unwrap().as_raw());
+}
@@ -0,0 +1,68 @@
+//! `diff_processes`: what changed between two process lists.
+
+use pswtf_core::process::diff_processes;
+use pswtf_core::ProcessInfo;
+
+const MB: u64 = 1024 * 1024;
+
+fn process(
+    pid: i32,
+    name: &str,
+    cpu: f32,
+    memory_bytes: u64,
+    run_time_seconds: u64,
+) -> ProcessInfo {
+    ProcessInfo {
+        pid,
+        name: name.to_string(),
+        cpu_percent: cpu,
+        memory_bytes,
+        run_time_seconds,
+        ..ProcessInfo::default()
+    }
+}
+
+#[test]
+fn reports_added_removed_and_large_moves_only() {
+    let before = [
+        process(10, "node", 5.0, 200 * MB, 600),
+        process(11, "postgres", 1.0, 100 * MB, 9000),
+        process(12, "zsh", 0.0, 5 * MB, 9000),
+        process(13, "esbuild", 90.0, 80 * MB, 30),
+    ];
+    let after = [
+        process(10, "node", 6.0, 900 * MB, 660),
+        process(11, "postgres", 45.0, 110 * MB, 9060),
+        process(12, "zsh", 2.0, 6 * MB, 9060),
+        process(14, "webpack", 120.0, 400 * MB, 20),
+    ];
+
+    let diff = diff_processes(&before, &after, 10.0, 50 * MB);
+
+    let pids = |processes: &[ProcessInfo]| processes.iter().map(|p| p.pid).collect::<Vec<_>>();
+    assert_eq!(pids(&diff.added), [14]);
+    assert_eq!(pids(&diff.removed), [13]);
+    // node for memory, postgres for CPU; zsh's wobble is under both thresholds.
+    let changed = diff
+        .changed
+        .iter()
+        .map(|change| change.pid)
+        .collect::<Vec<_>>();
+    assert_eq!(changed, [10, 11]);
+    assert_eq!(diff.changed[0].memory_before_bytes, 200 * MB);
+    assert_eq!(diff.changed[0].memory_after_bytes, 900 * MB);
+}
+
+#[test]
+fn a_reused_pid_is_a_different_process() {
+    let before = [process(10, "node", 5.0, 200 * MB, 600)];
+    let restarted = [process(10, "node", 5.0, 200 * MB, 3)];
+    let renamed = [process(10, "python3", 5.0, 200 * MB, 660)];
+
+    for after in [&restarted, &renamed] {
+        let diff = diff_processes(&before, after, 10.0, 50 * MB);
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.removed.len(), 1);
+        assert!(diff.changed.is_empty());
+    }
+}
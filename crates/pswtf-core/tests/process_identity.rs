@@ -0,0 +1,37 @@
+//! Telling a remembered process from whatever holds its PID now.
+
+use pswtf_core::process::{find_same_process, process_started_at};
+use pswtf_core::ProcessInfo;
+
+fn process(pid: i32, name: &str, run_time_seconds: u64) -> ProcessInfo {
+    ProcessInfo {
+        pid,
+        name: name.to_string(),
+        run_time_seconds,
+        ..ProcessInfo::default()
+    }
+}
+
+#[test]
+fn a_reused_pid_is_not_the_watched_process() {
+    let watched_at = 1_700_000_000_000u128;
+    let watched = process(4242, "node", 600);
+    let started_at = process_started_at(&watched, watched_at);
+
+    // Ten minutes later: still running, one second of rounding drift.
+    let later = watched_at + 600_000;
+    let still_there = [process(4242, "node", 1201)];
+    assert!(find_same_process(&still_there, 4242, "node", started_at, later).is_some());
+
+    // node exited and the PID went to something else, which a memory watch
+    // with a kill action must not touch.
+    let reused = [process(4242, "postgres", 30)];
+    assert!(find_same_process(&reused, 4242, "node", started_at, later).is_none());
+
+    // Same name, but a fresh process: a restarted node isn't the one that
+    // was watched either.
+    let restarted = [process(4242, "node", 30)];
+    assert!(find_same_process(&restarted, 4242, "node", started_at, later).is_none());
+
+    assert!(find_same_process(&[], 4242, "node", started_at, later).is_none());
+}
@@ -0,0 +1,89 @@
+//! Stack sampler output parsing and the hot-frame tally.
+
+use pswtf_core::profile::{
+    parse_eu_stack, parse_kernel_stack, parse_sample_report, summarize_stacks,
+};
+use pswtf_core::StackFrame;
+
+fn frame(function: &str) -> StackFrame {
+    StackFrame {
+        function: function.to_string(),
+        module: None,
+    }
+}
+
+#[test]
+fn eu_stack_output_is_split_per_thread() {
+    let threads = parse_eu_stack(
+        "PID 4242 - process\n\
+         TID 4242:\n\
+         #0  0x00007f1d0a1e4d7e __GI___poll\n\
+         #1  0x000055d8fdb0d7a2 - 1 main\n\
+         TID 4243:\n\
+         #0  0x00007f1d0a1b0a3c\n",
+    );
+
+    assert_eq!(threads.len(), 2);
+    assert_eq!(threads[0].0, 4242);
+    assert_eq!(threads[0].1, [frame("__GI___poll"), frame("main")]);
+    assert_eq!(threads[1].1, [frame("??")]);
+}
+
+#[test]
+fn kernel_stacks_drop_offsets() {
+    let stack =
+        parse_kernel_stack("[<0>] do_select+0x6a4/0x7f0\n[<0>] core_sys_select+0x1c1/0x3a0\n");
+    assert_eq!(stack.len(), 2);
+    assert_eq!(stack[0].function, "do_select");
+    assert_eq!(stack[0].module.as_deref(), Some("kernel"));
+}
+
+#[test]
+fn hot_frames_count_the_top_of_stack_and_each_frame_once_per_stack() {
+    let stacks = [
+        vec![frame("poll"), frame("run"), frame("run"), frame("main")],
+        vec![frame("poll"), frame("run"), frame("main")],
+        vec![frame("hash"), frame("main")],
+    ];
+    let frames = summarize_stacks(&stacks);
+
+    assert_eq!(frames[0].frame.function, "poll");
+    assert_eq!(frames[0].self_samples, 2);
+    assert_eq!(frames[1].frame.function, "hash");
+    let run = frames
+        .iter()
+        .find(|hot| hot.frame.function == "run")
+        .unwrap();
+    assert_eq!((run.self_samples, run.total_samples), (0, Some(2)));
+    let main = frames
+        .iter()
+        .find(|hot| hot.frame.function == "main")
+        .unwrap();
+    assert_eq!(main.total_samples, Some(3));
+}
+
+#[test]
+fn sample_reports_yield_their_top_of_stack_section() {
+    let frames = parse_sample_report(
+        "Call graph:\n\
+         \x20   2934 Thread_1  DispatchQueue_1: com.apple.main-thread  (serial)\n\
+         \n\
+         Total number in stack (recursive counted multiple, when >=5):\n\
+         \x20       10       _pthread_start  (in libsystem_pthread.dylib) + 224  [0x18d0e6034]\n\
+         \n\
+         Sort by top of stack, same collapsed (when >= 5):\n\
+         \x20       __psynch_cvwait  (in libsystem_kernel.dylib)        2934\n\
+         \x20       mach_msg2_trap  (in libsystem_kernel.dylib)        1467\n\
+         \n\
+         Binary Images:\n",
+    );
+
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].frame.function, "__psynch_cvwait");
+    assert_eq!(
+        frames[0].frame.module.as_deref(),
+        Some("libsystem_kernel.dylib")
+    );
+    assert_eq!(frames[0].self_samples, 2934);
+    assert_eq!(frames[1].total_samples, None);
+}
@@ -0,0 +1,106 @@
+//! The `cpu>50 mem>1gb name~node port:3000` filter syntax.
+
+use pswtf_core::query::parse_query;
+use pswtf_core::{PortExposure, PortInfo, PortOrigin, ProcessInfo};
+
+fn process(pid: i32, name: &str, cmd: &str, cpu: f32, memory_bytes: u64) -> ProcessInfo {
+    ProcessInfo {
+        pid,
+        name: name.to_string(),
+        cmd: cmd.to_string(),
+        user: Some("dev".to_string()),
+        status: "Run".to_string(),
+        cpu_percent: cpu,
+        memory_bytes,
+        ..ProcessInfo::default()
+    }
+}
+
+fn matching(expr: &str, processes: &[ProcessInfo], ports: &[PortInfo]) -> Vec<i32> {
+    let query = parse_query(expr).unwrap();
+    processes
+        .iter()
+        .filter(|process| query.matches(process, ports))
+        .map(|process| process.pid)
+        .collect()
+}
+
+#[test]
+fn compound_terms_all_have_to_match() {
+    let gb = 1024 * 1024 * 1024;
+    let processes = [
+        process(10, "node", "node server.js", 80.0, 2 * gb),
+        process(11, "node", "node build.js", 20.0, 2 * gb),
+        process(12, "postgres", "postgres -D /var/lib/pg", 90.0, 3 * gb),
+        process(13, "node", "npm run dev", 75.0, gb / 2),
+    ];
+
+    assert_eq!(matching("cpu>50 mem>1gb name~node", &processes, &[]), [10]);
+    assert_eq!(matching("mem>=1.5GB -name:node", &processes, &[]), [12]);
+    assert_eq!(matching("cmd~\"run dev\" user:DEV", &processes, &[]), [13]);
+    // Bare words search the name and command line like the search box.
+    assert_eq!(matching("build", &processes, &[]), [11]);
+}
+
+#[test]
+fn cpu_time_terms_split_user_and_system_time() {
+    let processes = [
+        ProcessInfo {
+            user_cpu_seconds: Some(600.0),
+            system_cpu_seconds: Some(5.0),
+            ..process(10, "ffmpeg", "ffmpeg -i in.mov out.mp4", 99.0, 0)
+        },
+        ProcessInfo {
+            user_cpu_seconds: Some(2.0),
+            system_cpu_seconds: Some(300.0),
+            ..process(11, "poller", "poller --interval 0", 99.0, 0)
+        },
+        process(12, "kworker", "", 1.0, 0),
+    ];
+
+    assert_eq!(matching("utime>5m", &processes, &[]), [10]);
+    assert_eq!(matching("stime>=1m cpu>50", &processes, &[]), [11]);
+    // Processes whose times aren't known never match a comparison.
+    assert_eq!(matching("-stime>1m", &processes, &[]), [10, 12]);
+}
+
+#[test]
+fn port_terms_match_the_listening_process() {
+    let processes = [
+        process(10, "node", "node server.js", 1.0, 0),
+        process(11, "vite", "vite", 1.0, 0),
+    ];
+    let ports = [PortInfo {
+        protocol: "tcp".to_string(),
+        local_address: "127.0.0.1".to_string(),
+        exposure: PortExposure::Loopback,
+        port: 3000,
+        service: None,
+        state: Some("LISTEN".to_string()),
+        pid: Some(11),
+        process_name: Some("vite".to_string()),
+        docker: None,
+        forward: None,
+        origin: PortOrigin::Local,
+    }];
+
+    let query = parse_query("port:3000").unwrap();
+    assert!(query.uses_ports());
+    assert_eq!(matching("port:3000", &processes, &ports), [11]);
+    assert_eq!(matching("!port:3000", &processes, &ports), [10]);
+}
+
+#[test]
+fn malformed_queries_are_rejected() {
+    for expr in [
+        "cpu>",
+        "cpu>lots",
+        "mem~1gb",
+        "name>node",
+        "colour:red",
+        "port>80",
+        "cmd~\"unterminated",
+    ] {
+        assert!(parse_query(expr).is_err(), "{expr} should not parse");
+    }
+}
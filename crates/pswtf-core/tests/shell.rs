@@ -0,0 +1,34 @@
+//! Rebuilding command lines for a POSIX shell.
+
+use pswtf_core::shell::{shell_command, shell_quote};
+
+#[test]
+fn quotes_only_what_the_shell_would_mangle() {
+    assert_eq!(shell_quote("--port=3000"), "--port=3000");
+    // Would be read as an assignment rather than the command.
+    assert_eq!(shell_quote("FOO=bar"), "'FOO=bar'");
+    assert_eq!(shell_quote("/usr/bin/node"), "/usr/bin/node");
+    assert_eq!(shell_quote(""), "''");
+    assert_eq!(shell_quote("hello world"), "'hello world'");
+    assert_eq!(shell_quote("$HOME"), "'$HOME'");
+    assert_eq!(shell_quote("it's"), r"'it'\''s'");
+}
+
+#[test]
+fn snippet_changes_directory_and_exports_env_first() {
+    let args = ["node", "server.js", "--name", "my app"].map(String::from);
+    let env = [
+        ("NODE_ENV".to_string(), "production".to_string()),
+        ("BASH_FUNC_x%%".to_string(), "() { :; }".to_string()),
+        ("GREETING".to_string(), "hi there".to_string()),
+    ];
+
+    assert_eq!(
+        shell_command(&args, Some("/srv/my app"), &env),
+        "cd '/srv/my app' && NODE_ENV=production GREETING='hi there' node server.js --name 'my app'"
+    );
+    assert_eq!(
+        shell_command(&args, None, &[]),
+        "node server.js --name 'my app'"
+    );
+}
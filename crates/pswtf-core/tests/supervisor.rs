@@ -0,0 +1,43 @@
+use pswtf_core::supervisor::find_supervisor;
+use pswtf_core::ProcessInfo;
+
+fn process(pid: i32, parent_pid: i32, cmd: &str) -> ProcessInfo {
+    ProcessInfo {
+        pid,
+        parent_pid: Some(parent_pid),
+        name: cmd
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_string(),
+        cmd: cmd.to_string(),
+        ..ProcessInfo::default()
+    }
+}
+
+#[test]
+fn nodemon_is_recognized_by_its_script() {
+    let processes = [
+        process(
+            999_901,
+            1,
+            "node /usr/lib/node_modules/nodemon/bin/nodemon.js server.js",
+        ),
+        process(999_902, 999_901, "sh -c node server.js"),
+        process(999_903, 999_902, "node server.js"),
+    ];
+    let supervised = find_supervisor(&processes, 999_903).unwrap();
+    assert_eq!(supervised.manager, "nodemon");
+    assert_eq!(supervised.manager_pid, 999_901);
+    assert_eq!(supervised.stop_command, None);
+}
+
+#[test]
+fn nodemon_in_a_path_is_not_nodemon() {
+    let processes = [
+        process(999_911, 1, "bash"),
+        process(999_912, 999_911, "node /home/dev/nodemon-demo/server.js"),
+        process(999_913, 999_912, "node /home/dev/nodemon-demo/worker.js"),
+    ];
+    assert!(find_supervisor(&processes, 999_913).is_none());
+}
@@ -0,0 +1,57 @@
+//! Parsing of the listings pulled from the other side of WSL: Windows'
+//! `netstat -ano` plus `tasklist`, and the distro's `ss -Htulnp`.
+
+use pswtf_core::ports::{ports_from_netstat, ports_from_ss};
+use pswtf_core::PortInfo;
+
+fn summary(port: &PortInfo) -> (u16, &str, &str, Option<i32>, Option<&str>) {
+    (
+        port.port,
+        port.protocol.as_str(),
+        port.local_address.as_str(),
+        port.pid,
+        port.process_name.as_deref(),
+    )
+}
+
+#[test]
+fn windows_netstat_named_through_tasklist() {
+    let ports = ports_from_netstat(
+        include_str!("fixtures/netstat_windows.txt"),
+        include_str!("fixtures/tasklist_windows.txt"),
+    );
+
+    // The localized state on port 3000 still counts as listening; the
+    // established connection doesn't.
+    assert_eq!(
+        ports.iter().map(summary).collect::<Vec<_>>(),
+        [
+            (135, "TCP", "0.0.0.0", Some(1100), Some("svchost.exe")),
+            (135, "TCP", "::", Some(1100), Some("svchost.exe")),
+            (3000, "TCP", "0.0.0.0", Some(8812), Some("node.exe")),
+            (5353, "UDP", "0.0.0.0", Some(2212), Some("chrome.exe")),
+            (5432, "TCP", "127.0.0.1", Some(4020), Some("postgres.exe")),
+            (8080, "TCP", "::1", Some(7300), Some("wslrelay.exe")),
+        ]
+    );
+    assert_eq!(ports[0].state.as_deref(), Some("LISTEN"));
+    assert_eq!(ports[3].state, None);
+}
+
+#[test]
+fn distro_ss_listing() {
+    let ports = ports_from_ss(include_str!("fixtures/ss_linux.txt"));
+
+    assert_eq!(
+        ports.iter().map(summary).collect::<Vec<_>>(),
+        [
+            (22, "TCP", "::", None, None),
+            (53, "TCP", "127.0.0.53", Some(611), Some("systemd-resolve")),
+            (53, "UDP", "127.0.0.53", Some(611), Some("systemd-resolve")),
+            (3000, "TCP", "0.0.0.0", Some(8123), Some("node")),
+            (5353, "UDP", "0.0.0.0", Some(702), Some("avahi-daemon")),
+            (5432, "TCP", "127.0.0.1", Some(1450), Some("postgres")),
+            (8080, "TCP", "fe80::1", Some(9001), Some("my server")),
+        ]
+    );
+}
@@ -3,453 +3,5453 @@
     windows_subsystem = "windows"
 )]
 
-use std::collections::{HashMap, HashSet};
-use std::path::Path;
-use std::process::Command;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::time::Duration;
 
-use nix::sys::signal::{kill, Signal};
-use nix::unistd::Pid as UnixPid;
-use serde::Serialize;
-use sysinfo::{Pid, PidExt, Process, ProcessExt, System, SystemExt};
+use base64::Engine;
+use pswtf_core::apps::{group_by_app, GroupBy};
+use pswtf_core::cgroup::collect_cgroup;
+use pswtf_core::container::annotate_docker_ports;
+use pswtf_core::desktop::{open_terminal, reveal_in_file_manager};
+use pswtf_core::dns::resolve_remote_hosts;
+use pswtf_core::forward::annotate_port_forwards;
+use pswtf_core::inspect::{
+    collect_fd_table, collect_loaded_modules, collect_memory_breakdown, collect_open_files,
+    collect_process_counters, collect_resource_limits, collect_thread_waits, collect_threads,
+    count_open_file_handles, process_cwd, process_root,
+};
+use pswtf_core::integrity::executable_integrity;
+use pswtf_core::kill::Signal;
+use pswtf_core::kill::{
+    build_child_map, find_matching_processes, kill_match_set, kill_matching, kill_port,
+    kill_process_tree, known_daemon, perform_kill, resolve_signal, verify_terminated, MatchMode,
+    MatchOptions, MatchSet, ProcessMatcher,
+};
+use pswtf_core::memmap::collect_memory_maps;
+use pswtf_core::ports::{
+    collect_connections, collect_ports, collect_process_sockets, collect_unix_sockets,
+    find_port_holder, port_identity, service_name_overrides, set_service_name_overrides,
+    PortFilter,
+};
+use pswtf_core::priority::get_io_priority;
+use pswtf_core::process::{
+    apply_io_rates, build_snapshot, current_processes, diff_processes, epoch_millis,
+    find_same_process, path_to_string, primed_system, process_ancestry, process_metrics_changed,
+    process_started_at, process_to_info, take_sample, top_processes, ProcessFilter, SortDirection,
+    SortKey, START_TIME_TOLERANCE_MS,
+};
+use pswtf_core::protect::{protected_list, set_protected_list, ProtectedList};
+use pswtf_core::query::parse_query;
+use pswtf_core::service::service_unit;
+use pswtf_core::shell::shell_command;
+use pswtf_core::supervisor::find_supervisor;
+use pswtf_core::system::{
+    collect_disks, collect_energy_impact, collect_network_interfaces, collect_sensors,
+    collect_system_overview, CounterRates,
+};
+use pswtf_core::wsl::merge_wsl_ports;
+use pswtf_core::{
+    ConnectionInfo, CpuCoreInfo, CpuNormalization, DiskInfo, EnergyImpact, FileDescriptor,
+    FileUser, IoPriorityClass, KillReport, LoadedModule, MemoryRegion, NetworkInterfaceInfo,
+    OpenFile, PortExposure, PortInfo, PortProbe, ProcessDetails, ProcessDiff, ProcessInfo,
+    ProcessProfile, ProcessSample, ProcessSnapshot, ReloadReport, SensorReading, SupervisedProcess,
+    SystemOverview, ThreadInfo, TopProcess, UnixSocketInfo, ZombieGroup,
+};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, PidExt, ProcessExt, System, SystemExt};
+use tauri::api::notification::Notification;
+use tauri::{
+    AppHandle, CustomMenuItem, Manager, State, SystemTray, SystemTrayEvent, SystemTrayMenu,
+    SystemTrayMenuItem, WindowEvent,
+};
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct ProcessInfo {
-    pid: i32,
-    parent_pid: Option<i32>,
-    name: String,
-    exe: Option<String>,
-    cmd: String,
-    status: String,
-    cpu_percent: f32,
-    memory_bytes: u64,
-    virtual_memory_bytes: u64,
-    read_bytes: u64,
-    written_bytes: u64,
-    run_time_seconds: u64,
+enum PayloadEncoding {
+    #[default]
+    Json,
+    Msgpack,
+}
+
+/// A snapshot pre-encoded in a compact binary format. Tauri's IPC channel
+/// only carries JSON, so the bytes travel as a single base64 string, which
+/// is far cheaper to serialize than thousands of nested objects.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EncodedPayload {
+    encoding: &'static str,
+    data: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum SnapshotPayload {
+    Json(ProcessSnapshot),
+    Encoded(EncodedPayload),
 }
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
-struct ProcessSnapshot {
+struct SnapshotDelta {
+    token: u64,
+    /// False when `since_token` was unknown and `changed` holds every process.
+    incremental: bool,
     collected_at_epoch_ms: u128,
     process_count: usize,
-    processes: Vec<ProcessInfo>,
+    changed: Vec<ProcessInfo>,
+    added: Vec<i32>,
+    removed: Vec<i32>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
-struct ProcessDetails {
-    process: ProcessInfo,
-    open_file_handles: Option<u32>,
-    cwd: Option<String>,
-    root: Option<String>,
+struct PortEvent {
+    port: PortInfo,
+    process: Option<ProcessInfo>,
 }
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
-struct PortInfo {
-    protocol: String,
-    local_address: String,
+struct PortBinding {
     port: u16,
-    state: Option<String>,
-    pid: Option<i32>,
-    process_name: Option<String>,
+    protocol: String,
+    bound: bool,
+    owner: Option<ProcessInfo>,
 }
 
-#[derive(Debug, Serialize)]
+/// Default cadence for the sampler thread; adjustable at runtime through
+/// `set_refresh_interval`.
+const DEFAULT_SAMPLE_INTERVAL: Duration = Duration::from_millis(1000);
+const MIN_SAMPLE_INTERVAL_MS: u64 = 250;
+const MAX_SAMPLE_INTERVAL_MS: u64 = 60_000;
+
+#[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
-struct KillError {
-    pid: i32,
-    error: String,
+struct MonitoringStatus {
+    interval_ms: u64,
+    paused: bool,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
-struct KillReport {
-    matched: usize,
-    attempted: usize,
-    killed: Vec<i32>,
-    failed: Vec<KillError>,
+struct PidExited {
+    pid: i32,
+    /// When the sampler first noticed the PID missing, not the exact exit time.
+    detected_at_epoch_ms: u128,
+    last_seen: Option<ProcessInfo>,
 }
 
-fn pid_to_i32(pid: Pid) -> i32 {
-    pid.as_u32() as i32
+struct SamplerShared {
+    latest: RwLock<Arc<ProcessSample>>,
+    settings: Mutex<MonitoringStatus>,
+    /// PIDs registered through `watch_pid`; dropped once their exit is reported.
+    watched: Mutex<HashSet<i32>>,
+    /// Signalled whenever `settings` change so the thread re-reads them
+    /// immediately instead of finishing its current sleep.
+    wake: Condvar,
 }
 
-fn path_to_string(path: &Path) -> Option<String> {
-    if path.as_os_str().is_empty() {
-        None
-    } else {
-        Some(path.display().to_string())
-    }
+/// Handle to the background sampler thread's most recent pass.
+struct Sampler {
+    shared: Arc<SamplerShared>,
 }
 
-fn process_to_info(pid: Pid, process: &Process) -> ProcessInfo {
-    let disk_usage = process.disk_usage();
+impl Sampler {
+    fn start(app: AppHandle) -> Self {
+        let mut system = primed_system();
+
+        let shared = Arc::new(SamplerShared {
+            latest: RwLock::new(Arc::new(take_sample(&mut system))),
+            settings: Mutex::new(MonitoringStatus {
+                interval_ms: DEFAULT_SAMPLE_INTERVAL.as_millis() as u64,
+                paused: false,
+            }),
+            watched: Mutex::new(HashSet::new()),
+            wake: Condvar::new(),
+        });
+
+        let thread_shared = Arc::clone(&shared);
+        std::thread::Builder::new()
+            .name("pswtf-sampler".to_string())
+            .spawn(move || run_sampler(&thread_shared, &mut system, &app))
+            .expect("failed to spawn sampler thread");
 
-    ProcessInfo {
-        pid: pid_to_i32(pid),
-        parent_pid: process.parent().map(pid_to_i32),
-        name: process.name().to_string(),
-        exe: path_to_string(process.exe()),
-        cmd: process.cmd().join(" "),
-        status: format!("{:?}", process.status()),
-        cpu_percent: process.cpu_usage(),
-        memory_bytes: process.memory().saturating_mul(1024),
-        virtual_memory_bytes: process.virtual_memory().saturating_mul(1024),
-        read_bytes: disk_usage.total_read_bytes,
-        written_bytes: disk_usage.total_written_bytes,
-        run_time_seconds: process.run_time(),
+        Self { shared }
     }
-}
 
-fn collect_processes() -> Vec<ProcessInfo> {
-    let mut system = System::new_all();
-    system.refresh_all();
+    fn latest(&self) -> Arc<ProcessSample> {
+        Arc::clone(
+            &self
+                .shared
+                .latest
+                .read()
+                .unwrap_or_else(|poisoned| poisoned.into_inner()),
+        )
+    }
 
-    let mut processes = system
-        .processes()
-        .iter()
-        .map(|(pid, process)| process_to_info(*pid, process))
-        .collect::<Vec<_>>();
+    fn status(&self) -> MonitoringStatus {
+        self.shared
+            .settings
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
 
-    processes.sort_by(|a, b| {
-        b.cpu_percent
-            .partial_cmp(&a.cpu_percent)
-            .unwrap_or(std::cmp::Ordering::Equal)
-            .then_with(|| b.memory_bytes.cmp(&a.memory_bytes))
-            .then_with(|| a.pid.cmp(&b.pid))
-    });
+    fn update(&self, apply: impl FnOnce(&mut MonitoringStatus)) -> MonitoringStatus {
+        let mut settings = self
+            .shared
+            .settings
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        apply(&mut settings);
+        self.shared.wake.notify_all();
+        settings.clone()
+    }
 
-    processes
+    fn watch(&self, pid: i32) {
+        self.shared
+            .watched
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(pid);
+    }
 }
 
-fn parse_endpoint(endpoint: &str) -> Option<(String, u16)> {
-    let local = endpoint.split("->").next()?.trim();
+fn run_sampler(shared: &SamplerShared, system: &mut System, app: &AppHandle) {
+    let mut previous = Arc::clone(
+        &shared
+            .latest
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()),
+    );
 
-    let separator = local.rfind(':')?;
-    let (address, port_text) = local.split_at(separator);
-    let port = port_text.trim_start_matches(':').parse::<u16>().ok()?;
+    loop {
+        {
+            let settings = shared
+                .settings
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            let interval = Duration::from_millis(settings.interval_ms);
+            let (settings, _) = shared
+                .wake
+                .wait_timeout(settings, interval)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
 
-    let normalized_address = address.trim_matches(|c| c == '[' || c == ']').to_string();
-    let local_address = if normalized_address.is_empty() {
-        "*".to_string()
-    } else {
-        normalized_address
-    };
+            // A wake-up from a settings change also lands here; while paused
+            // just keep waiting for the next one.
+            if settings.paused {
+                continue;
+            }
+        }
+
+        let mut sample = take_sample(system);
+        apply_io_rates(&previous, &mut sample);
+        let sample = Arc::new(sample);
+        *shared
+            .latest
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Arc::clone(&sample);
 
-    Some((local_address, port))
+        emit_lifecycle_events(app, &previous, &sample);
+        emit_watched_exits(shared, app, &previous, &sample);
+        let _ = app.state::<HistoryRecorder>().record(&sample);
+        app.state::<Notifier>().observe_sample(app, &sample);
+        app.state::<RuleEngine>().evaluate(app, &sample);
+        app.state::<MemoryWatchdog>().observe_sample(app, &sample);
+        app.state::<PinnedProcesses>().observe_sample(app, &sample);
+        previous = sample;
+    }
 }
 
-fn parse_lsof_line(line: &str) -> Option<PortInfo> {
-    if line.trim().is_empty() || line.starts_with("COMMAND") {
-        return None;
+fn emit_lifecycle_events(app: &AppHandle, previous: &ProcessSample, current: &ProcessSample) {
+    let before = previous
+        .processes
+        .iter()
+        .map(|process| process.pid)
+        .collect::<HashSet<_>>();
+    let after = current
+        .processes
+        .iter()
+        .map(|process| process.pid)
+        .collect::<HashSet<_>>();
+
+    for process in &current.processes {
+        if !before.contains(&process.pid) {
+            let _ = app.emit_all("process-started", process);
+        }
     }
 
-    let columns = line.split_whitespace().collect::<Vec<_>>();
-    if columns.len() < 9 {
-        return None;
+    // Exited processes are reported with their last sampled state.
+    for process in &previous.processes {
+        if !after.contains(&process.pid) {
+            let _ = app.emit_all("process-exited", process);
+        }
     }
+}
 
-    let process_name = columns[0].to_string();
-    let pid = columns[1].parse::<i32>().ok();
-    let protocol = columns[7].to_ascii_uppercase();
+fn emit_watched_exits(
+    shared: &SamplerShared,
+    app: &AppHandle,
+    previous: &ProcessSample,
+    current: &ProcessSample,
+) {
+    let mut watched = shared
+        .watched
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if watched.is_empty() {
+        return;
+    }
 
-    let name_segment = columns[8..].join(" ");
-    let (endpoint, state) = if let Some(idx) = name_segment.find(" (") {
-        let (ep, rest) = name_segment.split_at(idx);
-        (
-            ep.trim().to_string(),
-            Some(
-                rest.trim()
-                    .trim_start_matches('(')
-                    .trim_end_matches(')')
-                    .to_string(),
-            ),
-        )
-    } else {
-        (name_segment.trim().to_string(), None)
-    };
+    let alive = current
+        .processes
+        .iter()
+        .map(|process| process.pid)
+        .collect::<HashSet<_>>();
 
-    let (local_address, port) = parse_endpoint(&endpoint)?;
+    watched.retain(|pid| {
+        if alive.contains(pid) {
+            return true;
+        }
 
-    Some(PortInfo {
-        protocol,
-        local_address,
-        port,
-        state,
-        pid,
-        process_name: Some(process_name),
-    })
+        let event = PidExited {
+            pid: *pid,
+            detected_at_epoch_ms: current.collected_at_epoch_ms,
+            last_seen: previous
+                .processes
+                .iter()
+                .find(|process| process.pid == *pid)
+                .cloned(),
+        };
+        let name = event
+            .last_seen
+            .as_ref()
+            .map_or_else(|| "Process".to_string(), |process| process.name.clone());
+        notify(
+            app,
+            &format!("{name} exited"),
+            &format!("PID {pid} is no longer running."),
+        );
+        let _ = app.emit_all("pid-exited", event);
+        false
+    });
 }
 
-fn collect_ports() -> Result<Vec<PortInfo>, String> {
-    let output = Command::new("lsof")
-        .args(["-nP", "-iTCP", "-sTCP:LISTEN", "-iUDP"])
-        .output()
-        .map_err(|error| format!("Failed to run lsof: {error}"))?;
+const NOTIFICATION_SETTINGS_FILE: &str = "notifications.json";
+const DEFAULT_CPU_SUSTAIN_SECS: u64 = 30;
 
-    if !output.status.success() {
-        return Err(format!(
-            "lsof exited with status {:?}",
-            output.status.code()
-        ));
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NotificationSettings {
+    enabled: bool,
+    /// Per-process CPU percentage that triggers an alert; `None` turns the
+    /// CPU alert off while keeping watch notifications.
+    cpu_threshold_percent: Option<f32>,
+    cpu_sustain_seconds: u64,
+    /// Warn when a dev server starts listening on every interface.
+    #[serde(default)]
+    exposure_warnings: bool,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            cpu_threshold_percent: None,
+            cpu_sustain_seconds: DEFAULT_CPU_SUSTAIN_SECS,
+            exposure_warnings: false,
+        }
     }
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut ports = stdout
-        .lines()
-        .filter_map(parse_lsof_line)
-        .collect::<Vec<_>>();
+#[derive(Default)]
+struct CpuAlerts {
+    /// When each PID first went over the threshold in its current streak.
+    above_since: HashMap<i32, u128>,
+    /// PIDs already reported for their current streak.
+    alerted: HashSet<i32>,
+}
 
-    let mut seen = HashSet::new();
-    ports.retain(|entry| {
-        let key = format!(
-            "{}:{}:{}:{}:{:?}",
-            entry.protocol,
-            entry.local_address,
-            entry.port,
-            entry.pid.unwrap_or_default(),
-            entry.state
-        );
-        seen.insert(key)
-    });
+/// Sends native notifications for watch and CPU-threshold triggers; these go
+/// out from the backend so they still arrive while the window is in the tray.
+struct Notifier {
+    dir: Option<PathBuf>,
+    state: Mutex<(NotificationSettings, CpuAlerts)>,
+}
 
-    ports.sort_by(|a, b| {
-        a.port
-            .cmp(&b.port)
-            .then_with(|| a.protocol.cmp(&b.protocol))
-            .then_with(|| a.pid.unwrap_or_default().cmp(&b.pid.unwrap_or_default()))
-    });
+impl Notifier {
+    fn load(dir: Option<PathBuf>) -> Self {
+        let settings =
+            load_settings_file::<NotificationSettings>(dir.as_deref(), NOTIFICATION_SETTINGS_FILE)
+                .unwrap_or_default();
 
-    Ok(ports)
-}
+        Self {
+            dir,
+            state: Mutex::new((settings, CpuAlerts::default())),
+        }
+    }
+
+    fn settings(&self) -> NotificationSettings {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .0
+            .clone()
+    }
+
+    fn enabled(&self) -> bool {
+        self.settings().enabled
+    }
 
-fn count_open_file_handles(pid: i32) -> Option<u32> {
-    let output = Command::new("lsof")
-        .args(["-nP", "-p", &pid.to_string()])
-        .output()
-        .ok()?;
+    fn configure(&self, settings: NotificationSettings) -> Result<NotificationSettings, String> {
+        let dir = self
+            .dir
+            .as_ref()
+            .ok_or_else(|| "No app data directory available for settings".to_string())?;
+        save_settings_file(dir, NOTIFICATION_SETTINGS_FILE, &settings)?;
 
-    if !output.status.success() {
-        return None;
+        let mut state = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        *state = (settings.clone(), CpuAlerts::default());
+        Ok(settings)
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let count = stdout.lines().count().saturating_sub(1);
-    Some(count as u32)
-}
+    fn observe_sample(&self, app: &AppHandle, sample: &ProcessSample) {
+        let mut state = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let (settings, alerts) = &mut *state;
 
-fn build_child_map(processes: &[ProcessInfo]) -> HashMap<i32, Vec<i32>> {
-    let mut child_map = HashMap::<i32, Vec<i32>>::new();
+        let threshold = match settings.cpu_threshold_percent {
+            Some(threshold) if settings.enabled => threshold,
+            _ => {
+                *alerts = CpuAlerts::default();
+                return;
+            }
+        };
+        let sustain_ms = settings.cpu_sustain_seconds as u128 * 1000;
+        let now = sample.collected_at_epoch_ms;
+
+        let mut over = HashSet::new();
+        for process in &sample.processes {
+            if process.cpu_percent < threshold {
+                continue;
+            }
+            over.insert(process.pid);
 
-    for process in processes {
-        if let Some(parent_pid) = process.parent_pid {
-            child_map.entry(parent_pid).or_default().push(process.pid);
+            let since = *alerts.above_since.entry(process.pid).or_insert(now);
+            if now.saturating_sub(since) >= sustain_ms && alerts.alerted.insert(process.pid) {
+                send_notification(
+                    app,
+                    &format!("{} is using {:.0}% CPU", process.name, process.cpu_percent),
+                    &format!(
+                        "PID {} has been above {threshold:.0}% for {}s.",
+                        process.pid, settings.cpu_sustain_seconds
+                    ),
+                );
+            }
         }
+
+        // Dropping below the threshold (or exiting) ends the streak.
+        alerts.above_since.retain(|pid, _| over.contains(pid));
+        alerts.alerted.retain(|pid| over.contains(pid));
     }
+}
+
+/// Longest delay `schedule_kill` accepts.
+const MAX_KILL_DELAY_SECONDS: u64 = 7 * 24 * 60 * 60;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ScheduledKill {
+    id: u64,
+    pid: i32,
+    process_name: String,
+    include_children: bool,
+    force: bool,
+    scheduled_at_epoch_ms: u128,
+    fires_at_epoch_ms: u128,
+    #[serde(skip)]
+    started_at_epoch_ms: u128,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ScheduledKillFired {
+    scheduled: ScheduledKill,
+    report: Option<KillReport>,
+    error: Option<String>,
+}
 
-    child_map
+#[derive(Default)]
+struct ScheduledKills {
+    next_id: u64,
+    pending: Vec<ScheduledKill>,
 }
 
-fn collect_descendants(root_pid: i32, child_map: &HashMap<i32, Vec<i32>>, out: &mut Vec<i32>) {
-    if let Some(children) = child_map.get(&root_pid) {
-        for child_pid in children {
-            collect_descendants(*child_pid, child_map, out);
-            out.push(*child_pid);
+struct KillSchedulerShared {
+    state: Mutex<ScheduledKills>,
+    wake: Condvar,
+}
+
+/// Delayed kills. One thread sleeps until the earliest deadline and is woken
+/// whenever a kill is scheduled or cancelled.
+struct KillScheduler {
+    shared: Arc<KillSchedulerShared>,
+}
+
+impl KillScheduler {
+    fn start(app: AppHandle) -> Self {
+        let shared = Arc::new(KillSchedulerShared {
+            state: Mutex::new(ScheduledKills::default()),
+            wake: Condvar::new(),
+        });
+
+        let thread_shared = Arc::clone(&shared);
+        std::thread::Builder::new()
+            .name("pswtf-kill-scheduler".to_string())
+            .spawn(move || run_kill_scheduler(&thread_shared, &app))
+            .expect("failed to spawn kill scheduler thread");
+
+        Self { shared }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ScheduledKills> {
+        self.shared
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn schedule(
+        &self,
+        process: &ProcessInfo,
+        delay: Duration,
+        include_children: bool,
+        force: bool,
+    ) -> ScheduledKill {
+        let now = epoch_millis();
+        let mut state = self.lock();
+        state.next_id += 1;
+        let scheduled = ScheduledKill {
+            id: state.next_id,
+            pid: process.pid,
+            process_name: process.name.clone(),
+            include_children,
+            force,
+            scheduled_at_epoch_ms: now,
+            fires_at_epoch_ms: now + delay.as_millis(),
+            started_at_epoch_ms: process_started_at(process, now),
+        };
+        state.pending.push(scheduled.clone());
+        drop(state);
+
+        self.shared.wake.notify_all();
+        scheduled
+    }
+
+    fn cancel(&self, id: u64) -> Result<(), String> {
+        let mut state = self.lock();
+        let before = state.pending.len();
+        state.pending.retain(|scheduled| scheduled.id != id);
+        if state.pending.len() == before {
+            return Err(format!("Scheduled kill {id} was not found"));
         }
+        drop(state);
+
+        self.shared.wake.notify_all();
+        Ok(())
+    }
+
+    fn pending(&self) -> Vec<ScheduledKill> {
+        self.lock().pending.clone()
     }
 }
 
-fn dedupe_pids(pids: Vec<i32>) -> Vec<i32> {
-    let mut seen = HashSet::new();
-    let mut deduped = Vec::new();
+fn run_kill_scheduler(shared: &KillSchedulerShared, app: &AppHandle) {
+    let mut state = shared
+        .state
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    loop {
+        let now = epoch_millis();
+        let (due, waiting): (Vec<_>, Vec<_>) = std::mem::take(&mut state.pending)
+            .into_iter()
+            .partition(|scheduled| scheduled.fires_at_epoch_ms <= now);
+        state.pending = waiting;
 
-    for pid in pids {
-        if seen.insert(pid) {
-            deduped.push(pid);
+        if !due.is_empty() {
+            // Killing shells out on some platforms; don't hold the lock and
+            // block schedule/cancel calls meanwhile.
+            drop(state);
+            for scheduled in due {
+                fire_scheduled_kill(app, scheduled);
+            }
+            state = shared
+                .state
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            continue;
         }
-    }
 
-    deduped
+        let next = state
+            .pending
+            .iter()
+            .map(|scheduled| scheduled.fires_at_epoch_ms)
+            .min();
+        state = match next {
+            Some(deadline) => {
+                let timeout = Duration::from_millis((deadline - now) as u64);
+                shared
+                    .wake
+                    .wait_timeout(state, timeout)
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .0
+            }
+            None => shared
+                .wake
+                .wait(state)
+                .unwrap_or_else(|poisoned| poisoned.into_inner()),
+        };
+    }
 }
 
-fn resolve_signal(force: Option<bool>) -> Signal {
-    if force.unwrap_or(false) {
-        Signal::SIGKILL
+fn fire_scheduled_kill(app: &AppHandle, scheduled: ScheduledKill) {
+    let processes = current_processes();
+    let now = epoch_millis();
+
+    // The PID may have exited and been reused in the meantime; only kill it
+    // if it still looks like the process that was scheduled.
+    let still_running = find_same_process(
+        &processes,
+        scheduled.pid,
+        &scheduled.process_name,
+        scheduled.started_at_epoch_ms,
+        now,
+    )
+    .is_some();
+
+    let (report, error) = if still_running {
+        let force = Some(scheduled.force);
+        let result = kill_process_tree(
+            &processes,
+            scheduled.pid,
+            scheduled.include_children,
+            force,
+            false,
+        );
+        audit_kill(
+            app,
+            "schedule",
+            KillTarget::Pid {
+                pid: scheduled.pid,
+                include_children: scheduled.include_children,
+            },
+            force,
+            &processes,
+            &result,
+        );
+        match result {
+            Ok(report) => (Some(report), None),
+            Err(error) => (None, Some(error)),
+        }
     } else {
-        Signal::SIGTERM
+        (
+            None,
+            Some(format!(
+                "Process {} ({}) had already exited",
+                scheduled.pid, scheduled.process_name
+            )),
+        )
+    };
+
+    if let Some(report) = &report {
+        let body = match report.failed.first() {
+            Some(failure) if report.killed.is_empty() => format!(
+                "PID {} is still running; the kill failed: {}.",
+                scheduled.pid, failure.error
+            ),
+            Some(failure) => format!(
+                "Killed {} of {} processes; PID {} failed: {}.",
+                report.killed.len(),
+                report.attempted,
+                failure.pid,
+                failure.error
+            ),
+            None => format!(
+                "PID {} was still running and has been terminated.",
+                scheduled.pid
+            ),
+        };
+        notify(
+            app,
+            &format!("Scheduled kill: {}", scheduled.process_name),
+            &body,
+        );
     }
+
+    let _ = app.emit_all(
+        "scheduled-kill-fired",
+        &ScheduledKillFired {
+            scheduled,
+            report,
+            error,
+        },
+    );
 }
 
-fn perform_kill(targets: Vec<i32>, matched: usize, signal: Signal) -> KillReport {
-    let self_pid = std::process::id() as i32;
+const RULES_FILE: &str = "rules.json";
+/// How many rule firings `list_rule_activity` keeps around.
+const RULE_ACTIVITY_LEN: usize = 200;
 
-    let mut attempted = 0usize;
-    let mut killed = Vec::<i32>::new();
-    let mut failed = Vec::<KillError>::new();
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "metric", rename_all = "camelCase")]
+enum RuleCondition {
+    #[serde(rename_all = "camelCase")]
+    Cpu {
+        above_percent: f32,
+        for_seconds: u64,
+    },
+    #[serde(rename_all = "camelCase")]
+    Memory { above_bytes: u64, for_seconds: u64 },
+}
 
-    for pid in targets {
-        if pid <= 0 || pid == self_pid {
-            continue;
+impl RuleCondition {
+    fn breached(&self, process: &ProcessInfo) -> bool {
+        match self {
+            Self::Cpu { above_percent, .. } => process.cpu_percent > *above_percent,
+            Self::Memory { above_bytes, .. } => process.memory_bytes > *above_bytes,
         }
+    }
 
-        attempted += 1;
-        match kill(UnixPid::from_raw(pid), signal) {
-            Ok(_) => killed.push(pid),
-            Err(error) => failed.push(KillError {
-                pid,
-                error: error.to_string(),
-            }),
+    fn for_seconds(&self) -> u64 {
+        match self {
+            Self::Cpu { for_seconds, .. } | Self::Memory { for_seconds, .. } => *for_seconds,
         }
     }
 
-    KillReport {
-        matched,
-        attempted,
-        killed,
-        failed,
+    fn describe(&self) -> String {
+        match self {
+            Self::Cpu {
+                above_percent,
+                for_seconds,
+            } => format!("CPU above {above_percent:.0}% for {for_seconds}s"),
+            Self::Memory {
+                above_bytes,
+                for_seconds,
+            } => format!(
+                "memory above {} MB for {for_seconds}s",
+                above_bytes / (1024 * 1024)
+            ),
+        }
     }
 }
 
-#[tauri::command]
-fn get_process_snapshot() -> Result<ProcessSnapshot, String> {
-    let processes = collect_processes();
-
-    let collected_at_epoch_ms = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map_err(|error| format!("Clock error: {error}"))?
-        .as_millis();
-
-    Ok(ProcessSnapshot {
-        collected_at_epoch_ms,
-        process_count: processes.len(),
-        processes,
-    })
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum RuleAction {
+    Notify,
+    Kill,
+    ForceKill,
 }
 
-#[tauri::command]
-fn get_process_details(pid: i32) -> Result<ProcessDetails, String> {
-    if pid <= 0 {
-        return Err("PID must be a positive integer".to_string());
-    }
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Rule {
+    id: u64,
+    name: String,
+    query: String,
+    match_mode: MatchMode,
+    condition: RuleCondition,
+    action: RuleAction,
+}
 
-    let mut system = System::new_all();
-    system.refresh_all();
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RulesFile {
+    next_id: u64,
+    rules: Vec<Rule>,
+}
 
-    let target_pid = Pid::from_u32(pid as u32);
-    let process = system
-        .process(target_pid)
-        .ok_or_else(|| format!("Process {pid} was not found"))?;
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RuleFiring {
+    rule_id: u64,
+    rule_name: String,
+    action: RuleAction,
+    process: ProcessInfo,
+    fired_at_epoch_ms: u128,
+    /// Set for kill actions; `None` for notifications.
+    kill_report: Option<KillReport>,
+}
 
-    Ok(ProcessDetails {
-        process: process_to_info(target_pid, process),
-        open_file_handles: count_open_file_handles(pid),
-        cwd: path_to_string(process.cwd()),
-        root: path_to_string(process.root()),
-    })
+#[derive(Default)]
+struct RuleEngineState {
+    file: RulesFile,
+    matchers: HashMap<u64, ProcessMatcher>,
+    /// When each (rule, PID) pair started its current breach.
+    breached_since: HashMap<(u64, i32), u128>,
+    /// Pairs that already fired for their current breach.
+    fired: HashSet<(u64, i32)>,
+    activity: VecDeque<RuleFiring>,
 }
 
-#[tauri::command]
-fn list_open_ports() -> Result<Vec<PortInfo>, String> {
-    collect_ports()
+/// Threshold rules evaluated against every sampler pass. Each rule fires at
+/// most once per continuous breach so a kill that fails isn't retried every
+/// second.
+struct RuleEngine {
+    dir: Option<PathBuf>,
+    state: Mutex<RuleEngineState>,
 }
 
-#[tauri::command]
-fn kill_process(
-    pid: i32,
-    include_children: Option<bool>,
-    force: Option<bool>,
-) -> Result<KillReport, String> {
-    if pid <= 0 {
-        return Err("PID must be a positive integer".to_string());
+impl RuleEngine {
+    fn load(dir: Option<PathBuf>) -> Self {
+        let file = load_settings_file::<RulesFile>(dir.as_deref(), RULES_FILE).unwrap_or_default();
+        let matchers = file
+            .rules
+            .iter()
+            .filter_map(|rule| {
+                ProcessMatcher::new(&rule.query, rule.match_mode)
+                    .ok()
+                    .map(|matcher| (rule.id, matcher))
+            })
+            .collect();
+
+        Self {
+            dir,
+            state: Mutex::new(RuleEngineState {
+                file,
+                matchers,
+                ..RuleEngineState::default()
+            }),
+        }
     }
 
-    let processes = collect_processes();
-    if !processes.iter().any(|process| process.pid == pid) {
-        return Err(format!("Process {pid} was not found"));
+    fn lock(&self) -> std::sync::MutexGuard<'_, RuleEngineState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
     }
 
-    let child_map = build_child_map(&processes);
+    fn save(&self, file: &RulesFile) -> Result<(), String> {
+        let dir = self
+            .dir
+            .as_ref()
+            .ok_or_else(|| "No app data directory available for rules".to_string())?;
+        save_settings_file(dir, RULES_FILE, file)
+    }
 
-    let mut targets = Vec::<i32>::new();
-    if include_children.unwrap_or(true) {
-        collect_descendants(pid, &child_map, &mut targets);
+    fn rules(&self) -> Vec<Rule> {
+        self.lock().file.rules.clone()
     }
-    targets.push(pid);
 
-    let deduped = dedupe_pids(targets);
+    fn activity(&self) -> Vec<RuleFiring> {
+        self.lock().activity.iter().cloned().collect()
+    }
 
-    Ok(perform_kill(deduped, 1, resolve_signal(force)))
-}
+    fn add(
+        &self,
+        name: String,
+        query: String,
+        match_mode: MatchMode,
+        condition: RuleCondition,
+        action: RuleAction,
+    ) -> Result<Rule, String> {
+        let matcher = ProcessMatcher::new(&query, match_mode)?;
 
-#[tauri::command]
-fn kill_matching_processes(
-    query: String,
-    include_children: Option<bool>,
-    force: Option<bool>,
-) -> Result<KillReport, String> {
-    let normalized_query = query.trim().to_ascii_lowercase();
-    if normalized_query.is_empty() {
-        return Err("Query cannot be empty".to_string());
+        let mut state = self.lock();
+        state.file.next_id += 1;
+        let rule = Rule {
+            id: state.file.next_id,
+            name,
+            query,
+            match_mode,
+            condition,
+            action,
+        };
+        state.file.rules.push(rule.clone());
+
+        if let Err(error) = self.save(&state.file) {
+            state.file.rules.pop();
+            return Err(error);
+        }
+        state.matchers.insert(rule.id, matcher);
+        Ok(rule)
+    }
+
+    fn remove(&self, id: u64) -> Result<(), String> {
+        let mut state = self.lock();
+        let before = state.file.rules.len();
+        state.file.rules.retain(|rule| rule.id != id);
+        if state.file.rules.len() == before {
+            return Err(format!("Rule {id} was not found"));
+        }
+
+        self.save(&state.file)?;
+        state.matchers.remove(&id);
+        state
+            .breached_since
+            .retain(|(rule_id, _), _| *rule_id != id);
+        state.fired.retain(|(rule_id, _)| *rule_id != id);
+        Ok(())
     }
 
-    let processes = collect_processes();
-    let child_map = build_child_map(&processes);
+    fn evaluate(&self, app: &AppHandle, sample: &ProcessSample) {
+        let mut guard = self.lock();
+        if guard.file.rules.is_empty() {
+            return;
+        }
+
+        let state = &mut *guard;
+        let now = sample.collected_at_epoch_ms;
+        let mut breaching = HashSet::new();
 
-    let matched_roots = processes
-        .iter()
-        .filter_map(|process| {
-            let name_match = process
-                .name
-                .to_ascii_lowercase()
-                .contains(&normalized_query);
-            let cmd_match = process.cmd.to_ascii_lowercase().contains(&normalized_query);
-            if name_match || cmd_match {
-                Some(process.pid)
-            } else {
-                None
+        for rule in &state.file.rules {
+            let Some(matcher) = state.matchers.get(&rule.id) else {
+                continue;
+            };
+
+            for process in &sample.processes {
+                if !matcher.matches(process) || !rule.condition.breached(process) {
+                    continue;
+                }
+
+                let key = (rule.id, process.pid);
+                breaching.insert(key);
+                let since = *state.breached_since.entry(key).or_insert(now);
+                let sustained =
+                    now.saturating_sub(since) >= rule.condition.for_seconds() as u128 * 1000;
+                if !sustained || !state.fired.insert(key) {
+                    continue;
+                }
+
+                let firing = fire_rule(app, rule, process, now);
+                let _ = app.emit_all("rule-fired", &firing);
+                if state.activity.len() == RULE_ACTIVITY_LEN {
+                    state.activity.pop_front();
+                }
+                state.activity.push_back(firing);
             }
-        })
-        .collect::<Vec<_>>();
+        }
 
-    if matched_roots.is_empty() {
-        return Ok(KillReport {
-            matched: 0,
-            attempted: 0,
-            killed: Vec::new(),
-            failed: Vec::new(),
-        });
+        state
+            .breached_since
+            .retain(|key, _| breaching.contains(key));
+        state.fired.retain(|key| breaching.contains(key));
+    }
+}
+
+fn fire_rule(app: &AppHandle, rule: &Rule, process: &ProcessInfo, now: u128) -> RuleFiring {
+    let kill_report = match rule.action {
+        RuleAction::Notify => None,
+        RuleAction::Kill | RuleAction::ForceKill => {
+            let force = Some(rule.action == RuleAction::ForceKill);
+            let report = perform_kill(
+                vec![process.pid],
+                &[process.pid],
+                resolve_signal(force),
+                false,
+            );
+            audit_kill(
+                app,
+                format!("rule: {}", rule.name),
+                KillTarget::Pid {
+                    pid: process.pid,
+                    include_children: false,
+                },
+                force,
+                std::slice::from_ref(process),
+                &Ok(report.clone()),
+            );
+            Some(report)
+        }
+    };
+
+    let verb = match (&kill_report, rule.action) {
+        (None, _) => "matched",
+        (Some(report), _) if !report.failed.is_empty() => "failed to kill",
+        (Some(_), RuleAction::ForceKill) => "force-killed",
+        (Some(_), _) => "killed",
+    };
+    notify(
+        app,
+        &format!("Rule \"{}\" {verb} {}", rule.name, process.name),
+        &format!("PID {}: {}.", process.pid, rule.condition.describe()),
+    );
+
+    RuleFiring {
+        rule_id: rule.id,
+        rule_name: rule.name.clone(),
+        action: rule.action,
+        process: process.clone(),
+        fired_at_epoch_ms: now,
+        kill_report,
     }
+}
+
+/// Memory watches look at one RSS reading per interval rather than every
+/// sampler pass, so allocator jitter doesn't break an otherwise steady climb.
+const LEAK_SAMPLE_INTERVAL_MS: u128 = 10_000;
+const DEFAULT_LEAK_WINDOW_SECS: u64 = 10 * 60;
+const MIN_LEAK_WINDOW_SECS: u64 = 60;
+const MAX_LEAK_WINDOW_SECS: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MemoryWatch {
+    pid: i32,
+    process_name: String,
+    /// With the name, tells the watched process from a later one that was
+    /// handed the same PID.
+    started_at_epoch_ms: u128,
+    /// Fires when RSS rose at every reading across the window and the
+    /// overall rate is at least this much.
+    min_growth_bytes_per_minute: Option<u64>,
+    /// Fires as soon as RSS crosses this, trend or not.
+    ceiling_bytes: Option<u64>,
+    window_seconds: u64,
+    action: RuleAction,
+    growth_bytes_per_minute: Option<f64>,
+    #[serde(skip)]
+    readings: VecDeque<(u128, u64)>,
+    #[serde(skip)]
+    alerted: bool,
+}
+
+impl MemoryWatch {
+    fn observe(&mut self, at: u128, resident_bytes: u64) -> Option<&'static str> {
+        let due = self.readings.back().map_or(true, |(last, _)| {
+            at.saturating_sub(*last) >= LEAK_SAMPLE_INTERVAL_MS
+        });
+        if due {
+            self.readings.push_back((at, resident_bytes));
+            let window_ms = self.window_seconds as u128 * 1000;
+            while self
+                .readings
+                .front()
+                .is_some_and(|(first, _)| at.saturating_sub(*first) > window_ms)
+            {
+                self.readings.pop_front();
+            }
+            self.growth_bytes_per_minute = self.growth_rate(window_ms);
+        }
 
-    let mut targets = Vec::<i32>::new();
+        let over_ceiling = self
+            .ceiling_bytes
+            .is_some_and(|ceiling| resident_bytes > ceiling);
+        let leaking = match (
+            self.min_growth_bytes_per_minute,
+            self.growth_bytes_per_minute,
+        ) {
+            (Some(threshold), Some(rate)) => rate >= threshold as f64,
+            _ => false,
+        };
 
-    for root_pid in &matched_roots {
-        if include_children.unwrap_or(true) {
-            collect_descendants(*root_pid, &child_map, &mut targets);
+        if !over_ceiling && !leaking {
+            self.alerted = false;
+            return None;
+        }
+        if std::mem::replace(&mut self.alerted, true) {
+            return None;
         }
-        targets.push(*root_pid);
+
+        Some(if over_ceiling { "ceiling" } else { "growth" })
     }
 
-    let deduped = dedupe_pids(targets);
+    /// Growth per minute over the window, or `None` until the readings span
+    /// most of the window or if RSS dipped at any point in it.
+    fn growth_rate(&self, window_ms: u128) -> Option<f64> {
+        let (first_at, first_bytes) = *self.readings.front()?;
+        let (last_at, last_bytes) = *self.readings.back()?;
+        let span = last_at.saturating_sub(first_at);
+        if span + LEAK_SAMPLE_INTERVAL_MS < window_ms {
+            return None;
+        }
+
+        let monotonic = self
+            .readings
+            .iter()
+            .zip(self.readings.iter().skip(1))
+            .all(|((_, before), (_, after))| after >= before);
+        if !monotonic || last_bytes <= first_bytes {
+            return None;
+        }
 
-    Ok(perform_kill(
-        deduped,
-        matched_roots.len(),
-        resolve_signal(force),
-    ))
+        Some((last_bytes - first_bytes) as f64 * 60_000.0 / span as f64)
+    }
 }
 
-fn main() {
-    tauri::Builder::default()
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MemoryAlert {
+    watch: MemoryWatch,
+    reason: &'static str,
+    resident_bytes: u64,
+    fired_at_epoch_ms: u128,
+    kill_report: Option<KillReport>,
+}
+
+#[derive(Default)]
+struct MemoryWatchdog(Mutex<HashMap<i32, MemoryWatch>>);
+
+impl MemoryWatchdog {
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<i32, MemoryWatch>> {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn observe_sample(&self, app: &AppHandle, sample: &ProcessSample) {
+        let mut watches = self.lock();
+        if watches.is_empty() {
+            return;
+        }
+
+        let now = sample.collected_at_epoch_ms;
+        let by_pid = sample
+            .processes
+            .iter()
+            .map(|process| (process.pid, process))
+            .collect::<HashMap<_, _>>();
+
+        // A watch ends with its process, including when the PID has been
+        // reused by something else; `pid-exited` covers telling the UI.
+        watches.retain(|pid, watch| {
+            find_same_process(
+                &sample.processes,
+                *pid,
+                &watch.process_name,
+                watch.started_at_epoch_ms,
+                now,
+            )
+            .is_some()
+        });
+
+        let mut killed = Vec::new();
+        for watch in watches.values_mut() {
+            let process = by_pid[&watch.pid];
+            let Some(reason) = watch.observe(now, process.memory_bytes) else {
+                continue;
+            };
+
+            let kill_report = match watch.action {
+                RuleAction::Notify => None,
+                RuleAction::Kill | RuleAction::ForceKill => {
+                    killed.push(watch.pid);
+                    let force = Some(watch.action == RuleAction::ForceKill);
+                    let report =
+                        perform_kill(vec![watch.pid], &[watch.pid], resolve_signal(force), false);
+                    audit_kill(
+                        app,
+                        "memory-watch",
+                        KillTarget::Pid {
+                            pid: watch.pid,
+                            include_children: false,
+                        },
+                        force,
+                        std::slice::from_ref(process),
+                        &Ok(report.clone()),
+                    );
+                    Some(report)
+                }
+            };
+
+            let detail = match reason {
+                "ceiling" => format!("RSS reached {} MB.", process.memory_bytes / (1024 * 1024)),
+                _ => format!(
+                    "RSS has grown steadily for {} minutes, now {} MB.",
+                    watch.window_seconds / 60,
+                    process.memory_bytes / (1024 * 1024)
+                ),
+            };
+            notify(
+                app,
+                &format!("{} may be leaking memory", process.name),
+                &format!("PID {}: {detail}", process.pid),
+            );
+
+            let _ = app.emit_all(
+                "memory-alert",
+                &MemoryAlert {
+                    watch: watch.clone(),
+                    reason,
+                    resident_bytes: process.memory_bytes,
+                    fired_at_epoch_ms: now,
+                    kill_report,
+                },
+            );
+        }
+
+        for pid in killed {
+            watches.remove(&pid);
+        }
+    }
+}
+
+const PINS_FILE: &str = "pins.json";
+
+/// A pinned pattern and whether anything matching it is running.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PinnedProcess {
+    pattern: String,
+    running: bool,
+    pids: Vec<i32>,
+    /// False for pins restored at launch until the first sampler pass, so
+    /// whatever is already running then isn't announced as just started.
+    #[serde(skip)]
+    observed: bool,
+}
+
+/// Favourite processes, kept by name or executable rather than PID so a dev
+/// server that restarts is still the same pin. Emits `pinned-process-up`
+/// and `pinned-process-down` when a pin's first instance appears or its
+/// last one exits.
+struct PinnedProcesses {
+    dir: Option<PathBuf>,
+    pins: Mutex<Vec<PinnedProcess>>,
+}
+
+impl PinnedProcesses {
+    fn load(dir: Option<PathBuf>) -> Self {
+        let pins = load_settings_file::<Vec<String>>(dir.as_deref(), PINS_FILE)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|pattern| PinnedProcess {
+                pattern,
+                running: false,
+                pids: Vec::new(),
+                observed: false,
+            })
+            .collect();
+
+        Self {
+            dir,
+            pins: Mutex::new(pins),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<PinnedProcess>> {
+        self.pins
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn save(&self, pins: &[PinnedProcess]) -> Result<(), String> {
+        let dir = self
+            .dir
+            .as_ref()
+            .ok_or_else(|| "No app data directory available for settings".to_string())?;
+        let patterns = pins
+            .iter()
+            .map(|pin| pin.pattern.clone())
+            .collect::<Vec<_>>();
+        save_settings_file(dir, PINS_FILE, &patterns)
+    }
+
+    /// Starts with the current state, so pinning something that is already
+    /// up doesn't announce it as just started.
+    fn pin(&self, pattern: String, sample: &ProcessSample) -> Result<PinnedProcess, String> {
+        let mut pins = self.lock();
+        if let Some(pin) = pins.iter().find(|pin| pin.pattern == pattern) {
+            return Ok(pin.clone());
+        }
+
+        let pids = pinned_pids(&pattern, sample);
+        pins.push(PinnedProcess {
+            pattern,
+            running: !pids.is_empty(),
+            pids,
+            observed: true,
+        });
+
+        if let Err(error) = self.save(&pins) {
+            pins.pop();
+            return Err(error);
+        }
+        Ok(pins[pins.len() - 1].clone())
+    }
+
+    fn unpin(&self, pattern: &str) -> Result<(), String> {
+        let mut pins = self.lock();
+        let remaining = pins
+            .iter()
+            .filter(|pin| pin.pattern != pattern)
+            .cloned()
+            .collect::<Vec<_>>();
+        if remaining.len() == pins.len() {
+            return Err(format!("{pattern} is not pinned"));
+        }
+
+        self.save(&remaining)?;
+        *pins = remaining;
+        Ok(())
+    }
+
+    fn observe_sample(&self, app: &AppHandle, sample: &ProcessSample) {
+        for pin in self.lock().iter_mut() {
+            let pids = pinned_pids(&pin.pattern, sample);
+            let running = !pids.is_empty();
+            let changed = pin.observed && running != pin.running;
+            pin.running = running;
+            pin.pids = pids;
+            pin.observed = true;
+            if !changed {
+                continue;
+            }
+
+            let event = if running {
+                notify(
+                    app,
+                    &format!("{} is running", pin.pattern),
+                    &format!("PID {}.", pin.pids[0]),
+                );
+                "pinned-process-up"
+            } else {
+                notify(
+                    app,
+                    &format!("{} stopped", pin.pattern),
+                    "No matching process is running any more.",
+                );
+                "pinned-process-down"
+            };
+            let _ = app.emit_all(event, &*pin);
+        }
+    }
+}
+
+/// A pattern with a path separator is compared with the full executable
+/// path; anything else with the process name or the executable's file
+/// name, ignoring case.
+fn pinned_pids(pattern: &str, sample: &ProcessSample) -> Vec<i32> {
+    let by_path = pattern.contains(['/', '\\']);
+    let mut pids = sample
+        .processes
+        .iter()
+        .filter(|process| {
+            let exe = process.exe.as_deref();
+            if by_path {
+                return exe == Some(pattern);
+            }
+            let file_name = exe.and_then(|exe| Path::new(exe).file_name()?.to_str());
+            process.name.eq_ignore_ascii_case(pattern)
+                || file_name.is_some_and(|name| name.eq_ignore_ascii_case(pattern))
+        })
+        .map(|process| process.pid)
+        .collect::<Vec<_>>();
+    pids.sort_unstable();
+    pids
+}
+
+/// Exited launches kept for `list_launched_processes` before the oldest are
+/// dropped; running ones are always kept.
+const MAX_LAUNCHED_HISTORY: usize = 50;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LaunchedProcess {
+    pid: i32,
+    cmd: String,
+    args: Vec<String>,
+    cwd: Option<String>,
+    started_at_epoch_ms: u128,
+    /// `None` while the process is still running.
+    exited_at_epoch_ms: Option<u128>,
+    exit_code: Option<i32>,
+    /// The signal that ended it, on Unix.
+    exit_signal: Option<i32>,
+}
+
+/// Processes started with `spawn_process`. Each has a thread waiting on it,
+/// so it is reaped instead of lingering as a zombie under pswtf, and its exit
+/// is reported as `launched-process-exited`.
+#[derive(Default)]
+struct LaunchedProcesses(Arc<Mutex<Vec<LaunchedProcess>>>);
+
+impl LaunchedProcesses {
+    fn list(&self) -> Vec<LaunchedProcess> {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    fn spawn(
+        &self,
+        app: &AppHandle,
+        cmd: String,
+        args: Vec<String>,
+        cwd: Option<String>,
+        env: HashMap<String, String>,
+    ) -> Result<LaunchedProcess, String> {
+        let mut command = std::process::Command::new(&cmd);
+        command
+            .args(&args)
+            .envs(&env)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null());
+        if let Some(cwd) = &cwd {
+            command.current_dir(cwd);
+        }
+        let mut child = command
+            .spawn()
+            .map_err(|error| format!("Failed to start {cmd}: {error}"))?;
+
+        let launched = LaunchedProcess {
+            pid: child.id() as i32,
+            cmd,
+            args,
+            cwd,
+            started_at_epoch_ms: epoch_millis(),
+            exited_at_epoch_ms: None,
+            exit_code: None,
+            exit_signal: None,
+        };
+        {
+            let mut list = self
+                .0
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            list.push(launched.clone());
+            while list.len() > MAX_LAUNCHED_HISTORY {
+                let Some(oldest) = list
+                    .iter()
+                    .position(|entry| entry.exited_at_epoch_ms.is_some())
+                else {
+                    break;
+                };
+                list.remove(oldest);
+            }
+        }
+
+        let list = Arc::clone(&self.0);
+        let app = app.clone();
+        let pid = launched.pid;
+        let started_at = launched.started_at_epoch_ms;
+        std::thread::Builder::new()
+            .name(format!("pswtf-launch-{pid}"))
+            .spawn(move || {
+                let status = child.wait().ok();
+                let mut list = list.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                // An earlier launch may have had the same PID, so go by the
+                // launch itself rather than the first entry with that PID.
+                let Some(entry) = list.iter_mut().find(|entry| {
+                    entry.pid == pid
+                        && entry.started_at_epoch_ms == started_at
+                        && entry.exited_at_epoch_ms.is_none()
+                }) else {
+                    return;
+                };
+                entry.exited_at_epoch_ms = Some(epoch_millis());
+                entry.exit_code = status.and_then(|status| status.code());
+                entry.exit_signal = status.and_then(exit_signal);
+                let _ = app.emit_all("launched-process-exited", &*entry);
+            })
+            .map_err(|error| format!("Failed to watch PID {pid}: {error}"))?;
+
+        Ok(launched)
+    }
+}
+
+#[cfg(unix)]
+fn exit_signal(status: std::process::ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal()
+}
+
+#[cfg(not(unix))]
+fn exit_signal(_status: std::process::ExitStatus) -> Option<i32> {
+    None
+}
+
+fn send_notification(app: &AppHandle, title: &str, body: &str) {
+    let _ = Notification::new(&app.config().tauri.bundle.identifier)
+        .title(title)
+        .body(body)
+        .show();
+}
+
+/// Sends a notification unless the user has switched them off.
+fn notify(app: &AppHandle, title: &str, body: &str) {
+    if app.state::<Notifier>().enabled() {
+        send_notification(app, title, body);
+    }
+}
+
+const KILL_AUDIT_FILE: &str = "kill-audit.jsonl";
+const DEFAULT_KILL_HISTORY_LIMIT: usize = 500;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+enum KillTarget {
+    #[serde(rename_all = "camelCase")]
+    Pid {
+        pid: i32,
+        include_children: bool,
+    },
+    #[serde(rename_all = "camelCase")]
+    Port {
+        port: u16,
+        include_children: bool,
+    },
+    ProcessGroup {
+        pgid: i32,
+    },
+    #[serde(rename_all = "camelCase")]
+    Multiplexer {
+        session: String,
+        window_index: Option<u32>,
+    },
+    Tty {
+        tty: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    Query {
+        query: String,
+        match_mode: MatchMode,
+        include_children: bool,
+        /// Missing from entries logged before the options existed.
+        #[serde(default)]
+        case_sensitive: bool,
+        #[serde(default)]
+        exclude: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AuditedProcess {
+    pid: i32,
+    name: String,
+    cmd: String,
+    user: Option<String>,
+}
+
+/// One line of the kill audit log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KillAuditEntry {
+    at_epoch_ms: u128,
+    /// What asked for the kill: the UI, the tray, a rule, the local API...
+    source: String,
+    target: KillTarget,
+    signal: String,
+    /// Every process the kill touched, named as it was just before.
+    processes: Vec<AuditedProcess>,
+    report: Option<KillReport>,
+    error: Option<String>,
+}
+
+/// An append-only JSON-lines file in the app data directory, e.g. the kill
+/// audit log. `what` names it in error messages.
+struct JsonLinesLog {
+    path: Option<PathBuf>,
+    what: &'static str,
+    lock: Mutex<()>,
+}
+
+impl JsonLinesLog {
+    fn new(dir: Option<PathBuf>, file: &str, what: &'static str) -> Self {
+        Self {
+            path: dir.map(|dir| dir.join(file)),
+            what,
+            lock: Mutex::new(()),
+        }
+    }
+
+    fn append<T: Serialize>(&self, entry: &T) -> Result<(), String> {
+        let path = self
+            .path
+            .as_ref()
+            .ok_or_else(|| format!("No app data directory available for the {}", self.what))?;
+        let mut line = serde_json::to_vec(entry)
+            .map_err(|error| format!("Failed to encode {} entry: {error}", self.what))?;
+        line.push(b'\n');
+
+        let _guard = self
+            .lock
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|error| format!("Failed to create {}: {error}", dir.display()))?;
+        }
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(&line))
+            .map_err(|error| format!("Failed to write {}: {error}", path.display()))
+    }
+
+    /// The most recent `limit` entries `keep` accepts, oldest first. Lines
+    /// that fail to parse (e.g. a write cut short by a crash) are skipped.
+    fn read<T: serde::de::DeserializeOwned>(
+        &self,
+        limit: usize,
+        keep: impl Fn(&T) -> bool,
+    ) -> Result<Vec<T>, String> {
+        let Some(path) = self.path.as_ref().filter(|path| path.exists()) else {
+            return Ok(Vec::new());
+        };
+
+        let _guard = self
+            .lock
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let file = std::fs::File::open(path)
+            .map_err(|error| format!("Failed to open {}: {error}", path.display()))?;
+
+        let mut entries = VecDeque::new();
+        for line in BufReader::new(file).lines() {
+            let line =
+                line.map_err(|error| format!("Failed to read {}: {error}", path.display()))?;
+            if let Ok(entry) = serde_json::from_str::<T>(&line) {
+                if !keep(&entry) {
+                    continue;
+                }
+                if entries.len() == limit {
+                    entries.pop_front();
+                }
+                entries.push_back(entry);
+            }
+        }
+
+        Ok(entries.into())
+    }
+}
+
+/// Every kill pswtf performs, wherever it was triggered from.
+struct KillAudit(JsonLinesLog);
+
+impl KillAudit {
+    fn new(dir: Option<PathBuf>) -> Self {
+        Self(JsonLinesLog::new(dir, KILL_AUDIT_FILE, "kill log"))
+    }
+
+    fn append(&self, entry: &KillAuditEntry) -> Result<(), String> {
+        self.0.append(entry)
+    }
+
+    fn history(&self, limit: usize) -> Result<Vec<KillAuditEntry>, String> {
+        self.0.read(limit, |_| true)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum ExportFormat {
+    Csv,
+    Json,
+}
+
+const KILL_HISTORY_CSV_HEADER: &str =
+    "time,source,target,query,signal,matched,processes,killed,failed,stillRunning,error";
+
+/// One row per kill; list columns are `;`-separated so the file stays one
+/// row per entry in a spreadsheet.
+fn kill_history_csv(entries: &[KillAuditEntry]) -> String {
+    let mut csv = format!("{KILL_HISTORY_CSV_HEADER}\n");
+    for entry in entries {
+        let query = match &entry.target {
+            KillTarget::Query { query, .. } => query.as_str(),
+            _ => "",
+        };
+        let processes = entry
+            .processes
+            .iter()
+            .map(|process| format!("{} {}", process.pid, process.name))
+            .collect::<Vec<_>>()
+            .join("; ");
+        let report = entry.report.as_ref();
+        let killed = report.map_or_else(String::new, |report| {
+            report
+                .killed
+                .iter()
+                .map(|killed| format!("{} {} {}", killed.pid, killed.name, killed.signal))
+                .collect::<Vec<_>>()
+                .join("; ")
+        });
+        let failed = report.map_or_else(String::new, |report| {
+            report
+                .failed
+                .iter()
+                .map(|failure| format!("{}: {}", failure.pid, failure.error))
+                .collect::<Vec<_>>()
+                .join("; ")
+        });
+        let still_running = report.map_or_else(String::new, |report| {
+            report
+                .still_running
+                .iter()
+                .map(i32::to_string)
+                .collect::<Vec<_>>()
+                .join("; ")
+        });
+        let matched = report.map_or_else(String::new, |report| report.matched.to_string());
+
+        let fields = [
+            rfc3339_utc(entry.at_epoch_ms),
+            entry.source.clone(),
+            describe_kill_target(&entry.target),
+            query.to_string(),
+            entry.signal.clone(),
+            matched,
+            processes,
+            killed,
+            failed,
+            still_running,
+            entry.error.clone().unwrap_or_default(),
+        ];
+        let row = fields
+            .iter()
+            .map(|field| csv_field(field))
+            .collect::<Vec<_>>()
+            .join(",");
+        csv.push_str(&row);
+        csv.push('\n');
+    }
+    csv
+}
+
+fn describe_kill_target(target: &KillTarget) -> String {
+    let tree = |include_children: bool| {
+        if include_children {
+            " and children"
+        } else {
+            ""
+        }
+    };
+    match target {
+        KillTarget::Pid {
+            pid,
+            include_children,
+        } => format!("pid {pid}{}", tree(*include_children)),
+        KillTarget::Port {
+            port,
+            include_children,
+        } => format!("port {port}{}", tree(*include_children)),
+        KillTarget::ProcessGroup { pgid } => format!("process group {pgid}"),
+        KillTarget::Multiplexer {
+            session,
+            window_index: Some(window),
+        } => format!("session {session}:{window}"),
+        KillTarget::Multiplexer { session, .. } => format!("session {session}"),
+        KillTarget::Tty { tty } => format!("tty {tty}"),
+        KillTarget::Query {
+            match_mode,
+            include_children,
+            case_sensitive,
+            exclude,
+            ..
+        } => {
+            let excluding = if exclude.is_empty() {
+                String::new()
+            } else {
+                format!(" excluding {}", exclude.join(", "))
+            };
+            format!(
+                "{}{} match{}{excluding}",
+                if *case_sensitive {
+                    "case-sensitive "
+                } else {
+                    ""
+                },
+                format!("{match_mode:?}").to_ascii_lowercase(),
+                tree(*include_children)
+            )
+        }
+    }
+}
+
+/// Quotes `value` when it holds a comma, quote or line break (RFC 4180).
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// `2024-03-09T14:02:11.482Z` for milliseconds since the Unix epoch.
+fn rfc3339_utc(epoch_ms: u128) -> String {
+    let seconds = (epoch_ms / 1000) as i64;
+    let (days, time) = (seconds.div_euclid(86_400), seconds.rem_euclid(86_400));
+
+    // Howard Hinnant's days-to-civil conversion.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        time / 3600,
+        time % 3600 / 60,
+        time % 60,
+        epoch_ms % 1000
+    )
+}
+
+/// Records a finished kill in the audit log. `processes` is the process list
+/// the kill was resolved against, used to name the PIDs in the report.
+fn audit_kill(
+    app: &AppHandle,
+    source: impl Into<String>,
+    target: KillTarget,
+    force: Option<bool>,
+    processes: &[ProcessInfo],
+    result: &Result<KillReport, String>,
+) {
+    audit_signal(
+        app,
+        source,
+        target,
+        resolve_signal(force),
+        processes,
+        result,
+    );
+}
+
+/// `audit_kill` for signals other than the TERM/KILL pair, e.g. reloads.
+fn audit_signal(
+    app: &AppHandle,
+    source: impl Into<String>,
+    target: KillTarget,
+    signal: Signal,
+    processes: &[ProcessInfo],
+    result: &Result<KillReport, String>,
+) {
+    let by_pid = processes
+        .iter()
+        .map(|process| (process.pid, process))
+        .collect::<HashMap<_, _>>();
+    let touched = match result {
+        Ok(report) => report
+            .killed
+            .iter()
+            .map(|entry| entry.pid)
+            .chain(report.failed.iter().map(|failure| failure.pid))
+            .collect::<Vec<_>>(),
+        Err(_) => Vec::new(),
+    };
+
+    let entry = KillAuditEntry {
+        at_epoch_ms: epoch_millis(),
+        source: source.into(),
+        target,
+        signal: signal.as_str().to_string(),
+        processes: touched
+            .into_iter()
+            .filter_map(|pid| by_pid.get(&pid))
+            .map(|process| AuditedProcess {
+                pid: process.pid,
+                name: process.name.clone(),
+                cmd: process.cmd.clone(),
+                user: process.user.clone(),
+            })
+            .collect(),
+        report: result.as_ref().ok().cloned(),
+        error: result.as_ref().err().cloned(),
+    };
+
+    // A kill that already happened shouldn't be reported as failed just
+    // because the log couldn't be written.
+    let _ = app.state::<KillAudit>().append(&entry);
+}
+
+const PROTECTED_FILE: &str = "protected.json";
+
+/// Owns the persisted copy of the protected list; the list `perform_kill`
+/// actually consults lives in pswtf-core.
+struct ProtectedProcesses {
+    dir: Option<PathBuf>,
+}
+
+impl ProtectedProcesses {
+    fn load(dir: Option<PathBuf>) -> Self {
+        if let Some(list) = load_settings_file::<ProtectedList>(dir.as_deref(), PROTECTED_FILE) {
+            set_protected_list(list);
+        }
+
+        Self { dir }
+    }
+
+    fn configure(&self, list: ProtectedList) -> Result<ProtectedList, String> {
+        let dir = self
+            .dir
+            .as_ref()
+            .ok_or_else(|| "No app data directory available for settings".to_string())?;
+        save_settings_file(dir, PROTECTED_FILE, &list)?;
+
+        set_protected_list(list.clone());
+        Ok(list)
+    }
+}
+
+const PORT_NAMES_FILE: &str = "port-names.json";
+const MAX_PORT_NAME_CHARS: usize = 64;
+
+/// User labels for ports, layered over the built-in service-name table.
+struct PortNames {
+    dir: Option<PathBuf>,
+}
+
+impl PortNames {
+    fn load(dir: Option<PathBuf>) -> Self {
+        if let Some(names) =
+            load_settings_file::<HashMap<u16, String>>(dir.as_deref(), PORT_NAMES_FILE)
+        {
+            set_service_name_overrides(names);
+        }
+
+        Self { dir }
+    }
+
+    fn configure(&self, names: HashMap<u16, String>) -> Result<HashMap<u16, String>, String> {
+        let dir = self
+            .dir
+            .as_ref()
+            .ok_or_else(|| "No app data directory available for settings".to_string())?;
+        save_settings_file(dir, PORT_NAMES_FILE, &names)?;
+
+        set_service_name_overrides(names.clone());
+        Ok(names)
+    }
+}
+
+const SETTINGS_FILE: &str = "settings.json";
+
+/// A named `query_processes` expression.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SavedFilter {
+    name: String,
+    query: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WatchedPort {
+    port: u16,
+    protocol: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct StoredSettings {
+    refresh_interval_ms: u64,
+    saved_filters: Vec<SavedFilter>,
+    /// Re-registered with the port watcher on launch.
+    watched_ports: Vec<WatchedPort>,
+}
+
+impl Default for StoredSettings {
+    fn default() -> Self {
+        Self {
+            refresh_interval_ms: DEFAULT_SAMPLE_INTERVAL.as_millis() as u64,
+            saved_filters: Vec::new(),
+            watched_ports: Vec::new(),
+        }
+    }
+}
+
+/// Everything `get_settings` returns. The protected list keeps its own file
+/// so `ProtectedProcesses` stays the one place that writes it.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AppSettings {
+    #[serde(flatten)]
+    stored: StoredSettings,
+    protected: ProtectedList,
+}
+
+/// General preferences that should outlive the window. The refresh interval
+/// here is the one restored on launch; `set_refresh_interval` stays
+/// transient so the hidden-window slowdown isn't remembered.
+struct SettingsStore {
+    dir: Option<PathBuf>,
+    settings: Mutex<StoredSettings>,
+}
+
+impl SettingsStore {
+    fn load(dir: Option<PathBuf>) -> Self {
+        let settings =
+            load_settings_file::<StoredSettings>(dir.as_deref(), SETTINGS_FILE).unwrap_or_default();
+
+        Self {
+            dir,
+            settings: Mutex::new(settings),
+        }
+    }
+
+    fn stored(&self) -> StoredSettings {
+        self.settings
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    fn configure(&self, settings: StoredSettings) -> Result<StoredSettings, String> {
+        let dir = self
+            .dir
+            .as_ref()
+            .ok_or_else(|| "No app data directory available for settings".to_string())?;
+        save_settings_file(dir, SETTINGS_FILE, &settings)?;
+
+        *self
+            .settings
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = settings.clone();
+        Ok(settings)
+    }
+}
+
+const HISTORY_DB_FILE: &str = "history.sqlite3";
+const HISTORY_SETTINGS_FILE: &str = "history.json";
+const DEFAULT_HISTORY_RETENTION_SECS: u64 = 30 * 60;
+const MIN_HISTORY_RETENTION_SECS: u64 = 60;
+const MAX_HISTORY_RETENTION_SECS: u64 = 7 * 24 * 60 * 60;
+const DEFAULT_HISTORY_WINDOW_SECS: u64 = 5 * 60;
+const DEFAULT_HISTORY_RESOLUTION: u32 = 60;
+const MAX_HISTORY_RESOLUTION: u32 = 1000;
+/// Expired rows are swept at most this often rather than on every sample.
+const HISTORY_PRUNE_INTERVAL_MS: u128 = 60_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HistorySettings {
+    enabled: bool,
+    retention_seconds: u64,
+}
+
+impl Default for HistorySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            retention_seconds: DEFAULT_HISTORY_RETENTION_SECS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HistoryPoint {
+    /// Timestamp of the newest raw sample folded into this bucket.
+    epoch_ms: u128,
+    cpu_percent: f64,
+    memory_bytes: u64,
+    /// Cumulative I/O totals as of the end of the bucket.
+    read_bytes: u64,
+    written_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProcessHistory {
+    pid: i32,
+    window_seconds: u64,
+    resolution: u32,
+    points: Vec<HistoryPoint>,
+}
+
+struct HistoryStore {
+    settings: HistorySettings,
+    connection: Option<Connection>,
+    last_pruned_at_ms: u128,
+}
+
+/// Opt-in recorder that appends every sampler pass to a SQLite database in
+/// the app data directory, so recent per-process activity survives the
+/// window being closed.
+struct HistoryRecorder {
+    dir: Option<PathBuf>,
+    store: Mutex<HistoryStore>,
+}
+
+impl HistoryRecorder {
+    fn load(dir: Option<PathBuf>) -> Self {
+        let settings = load_settings_file::<HistorySettings>(dir.as_deref(), HISTORY_SETTINGS_FILE)
+            .unwrap_or_default();
+
+        let connection = match (&dir, settings.enabled) {
+            (Some(dir), true) => open_history_db(dir).ok(),
+            _ => None,
+        };
+
+        Self {
+            dir,
+            store: Mutex::new(HistoryStore {
+                settings,
+                connection,
+                last_pruned_at_ms: 0,
+            }),
+        }
+    }
+
+    fn settings(&self) -> HistorySettings {
+        self.store
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .settings
+            .clone()
+    }
+
+    fn configure(&self, settings: HistorySettings) -> Result<HistorySettings, String> {
+        let dir = self
+            .dir
+            .as_ref()
+            .ok_or_else(|| "No app data directory available for history".to_string())?;
+        let mut store = self
+            .store
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if settings.enabled && store.connection.is_none() {
+            store.connection = Some(open_history_db(dir)?);
+        } else if !settings.enabled {
+            // Recorded rows are left on disk; they're pruned once recording
+            // is switched back on.
+            store.connection = None;
+        }
+
+        save_settings_file(dir, HISTORY_SETTINGS_FILE, &settings)?;
+        store.settings = settings.clone();
+        store.last_pruned_at_ms = 0;
+        Ok(settings)
+    }
+
+    fn record(&self, sample: &ProcessSample) -> rusqlite::Result<()> {
+        let mut store = self
+            .store
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let HistoryStore {
+            settings,
+            connection,
+            last_pruned_at_ms,
+        } = &mut *store;
+        let Some(connection) = connection.as_mut() else {
+            return Ok(());
+        };
+
+        let collected_at = sample.collected_at_epoch_ms;
+        let transaction = connection.transaction()?;
+        {
+            let mut insert = transaction.prepare_cached(
+                "INSERT INTO process_samples
+                    (collected_at_ms, pid, started_at_ms, name, cpu_percent, memory_bytes,
+                     read_bytes, written_bytes)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )?;
+            for process in &sample.processes {
+                insert.execute(params![
+                    collected_at as i64,
+                    process.pid,
+                    process_started_at(process, collected_at) as i64,
+                    process.name,
+                    process.cpu_percent,
+                    process.memory_bytes as i64,
+                    process.read_bytes as i64,
+                    process.written_bytes as i64,
+                ])?;
+            }
+        }
+
+        if collected_at.saturating_sub(*last_pruned_at_ms) >= HISTORY_PRUNE_INTERVAL_MS {
+            let cutoff = collected_at.saturating_sub(settings.retention_seconds as u128 * 1000);
+            transaction.execute(
+                "DELETE FROM process_samples WHERE collected_at_ms < ?1",
+                params![cutoff as i64],
+            )?;
+            *last_pruned_at_ms = collected_at;
+        }
+
+        transaction.commit()
+    }
+
+    /// Averages the samples of one process over the trailing window into at
+    /// most `resolution` evenly sized buckets; empty buckets are simply
+    /// absent. A series is a PID plus a start time, so a reused PID starts a
+    /// new one instead of continuing a dead process's. Without a start time
+    /// (the process has exited) the newest series for `pid` is used.
+    fn process_history(
+        &self,
+        pid: i32,
+        started_at_ms: Option<u128>,
+        window_seconds: u64,
+        resolution: u32,
+    ) -> Result<Vec<HistoryPoint>, String> {
+        let store = self
+            .store
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let connection = store
+            .connection
+            .as_ref()
+            .ok_or_else(|| "Process history recording is disabled".to_string())?;
+
+        let started_at_ms = match started_at_ms {
+            Some(started_at_ms) => started_at_ms as i64,
+            None => match connection
+                .query_row(
+                    "SELECT started_at_ms FROM process_samples
+                     WHERE pid = ?1 AND started_at_ms IS NOT NULL
+                     ORDER BY collected_at_ms DESC LIMIT 1",
+                    params![pid],
+                    |row| row.get::<_, i64>(0),
+                )
+                .optional()
+                .map_err(|error| format!("Failed to query process history: {error}"))?
+            {
+                Some(started_at_ms) => started_at_ms,
+                None => return Ok(Vec::new()),
+            },
+        };
+
+        let window_ms = window_seconds as i64 * 1000;
+        let start = epoch_millis() as i64 - window_ms;
+
+        // Start times come from whole-second run times, so they wobble a
+        // little from sample to sample.
+        let mut query = connection
+            .prepare_cached(
+                "SELECT MAX(collected_at_ms), AVG(cpu_percent), AVG(memory_bytes),
+                        MAX(read_bytes), MAX(written_bytes)
+                 FROM process_samples
+                 WHERE pid = ?1 AND collected_at_ms >= ?2
+                   AND ABS(started_at_ms - ?5) <= ?6
+                 GROUP BY (collected_at_ms - ?2) * ?3 / ?4
+                 ORDER BY 1",
+            )
+            .map_err(|error| format!("Failed to query process history: {error}"))?;
+
+        let rows = query
+            .query_map(
+                params![
+                    pid,
+                    start,
+                    resolution,
+                    window_ms,
+                    started_at_ms,
+                    START_TIME_TOLERANCE_MS as i64
+                ],
+                |row| {
+                    Ok(HistoryPoint {
+                        epoch_ms: row.get::<_, i64>(0)? as u128,
+                        cpu_percent: row.get(1)?,
+                        memory_bytes: row.get::<_, f64>(2)? as u64,
+                        read_bytes: row.get::<_, i64>(3)? as u64,
+                        written_bytes: row.get::<_, i64>(4)? as u64,
+                    })
+                },
+            )
+            .map_err(|error| format!("Failed to query process history: {error}"))?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|error| format!("Failed to read process history: {error}"))
+    }
+}
+
+/// Reads a small JSON settings file from the app data directory; `None` when
+/// it is missing or unreadable.
+fn load_settings_file<T: for<'de> Deserialize<'de>>(dir: Option<&Path>, name: &str) -> Option<T> {
+    dir.and_then(|dir| std::fs::read(dir.join(name)).ok())
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+}
+
+fn save_settings_file<T: Serialize>(dir: &Path, name: &str, settings: &T) -> Result<(), String> {
+    std::fs::create_dir_all(dir)
+        .map_err(|error| format!("Failed to create {}: {error}", dir.display()))?;
+    let json = serde_json::to_vec_pretty(settings)
+        .map_err(|error| format!("Failed to encode {name}: {error}"))?;
+    std::fs::write(dir.join(name), json).map_err(|error| format!("Failed to save {name}: {error}"))
+}
+
+fn open_history_db(dir: &Path) -> Result<Connection, String> {
+    std::fs::create_dir_all(dir)
+        .map_err(|error| format!("Failed to create {}: {error}", dir.display()))?;
+
+    let path = dir.join(HISTORY_DB_FILE);
+    let connection = Connection::open(&path)
+        .map_err(|error| format!("Failed to open {}: {error}", path.display()))?;
+
+    // WAL + NORMAL keeps the once-per-second insert from fsyncing every time.
+    connection
+        .execute_batch(
+            "PRAGMA journal_mode = WAL;
+             PRAGMA synchronous = NORMAL;
+             CREATE TABLE IF NOT EXISTS process_samples (
+                 collected_at_ms INTEGER NOT NULL,
+                 pid INTEGER NOT NULL,
+                 started_at_ms INTEGER,
+                 name TEXT NOT NULL,
+                 cpu_percent REAL NOT NULL,
+                 memory_bytes INTEGER NOT NULL,
+                 read_bytes INTEGER NOT NULL,
+                 written_bytes INTEGER NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS process_samples_pid_time
+                 ON process_samples (pid, collected_at_ms);
+             CREATE INDEX IF NOT EXISTS process_samples_time
+                 ON process_samples (collected_at_ms);",
+        )
+        .map_err(|error| format!("Failed to initialise history database: {error}"))?;
+
+    // Databases from before series were keyed by start time. Their rows get
+    // no start time and so belong to no series; retention clears them out.
+    let has_started_at = connection
+        .prepare("SELECT 1 FROM pragma_table_info('process_samples') WHERE name = 'started_at_ms'")
+        .and_then(|mut statement| statement.exists([]))
+        .map_err(|error| format!("Failed to initialise history database: {error}"))?;
+    if !has_started_at {
+        connection
+            .execute_batch("ALTER TABLE process_samples ADD COLUMN started_at_ms INTEGER;")
+            .map_err(|error| format!("Failed to initialise history database: {error}"))?;
+    }
+
+    Ok(connection)
+}
+
+const METRICS_SETTINGS_FILE: &str = "metrics.json";
+const DEFAULT_METRICS_PORT: u16 = 9465;
+const LOCAL_API_SETTINGS_FILE: &str = "local-api.json";
+const DEFAULT_LOCAL_API_PORT: u16 = 9466;
+/// How many of the busiest processes get their own per-PID gauges.
+const METRICS_TOP_N: usize = 10;
+/// How long the non-blocking accept loops sleep between checks of their stop flag.
+const HTTP_ACCEPT_POLL: Duration = Duration::from_millis(200);
+const HTTP_READ_TIMEOUT: Duration = Duration::from_secs(2);
+const HTTP_MAX_REQUEST_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ListenerSettings {
+    enabled: bool,
+    port: u16,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ListenerStatus {
+    enabled: bool,
+    port: u16,
+    running: bool,
+}
+
+/// A loopback HTTP listener running on its own thread until `stop` is called.
+struct HttpServer {
+    stop: Arc<AtomicBool>,
+    thread: std::thread::JoinHandle<()>,
+}
+
+impl HttpServer {
+    fn spawn(name: &str, port: u16, app: AppHandle, handle: HttpHandler) -> Result<Self, String> {
+        let listener = TcpListener::bind(("127.0.0.1", port))
+            .map_err(|error| format!("Failed to listen on 127.0.0.1:{port}: {error}"))?;
+        listener
+            .set_nonblocking(true)
+            .map_err(|error| format!("Failed to configure listener: {error}"))?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+        let thread = std::thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || {
+                while !thread_stop.load(Ordering::Relaxed) {
+                    match listener.accept() {
+                        Ok((stream, _)) => serve_http_connection(&app, stream, handle),
+                        Err(_) => std::thread::sleep(HTTP_ACCEPT_POLL),
+                    }
+                }
+            })
+            .map_err(|error| format!("Failed to spawn {name} thread: {error}"))?;
+
+        Ok(Self { stop, thread })
+    }
+
+    /// Blocks until the listener thread exits so the port is free to rebind.
+    fn stop(self) {
+        self.stop.store(true, Ordering::Relaxed);
+        let _ = self.thread.join();
+    }
+}
+
+struct HttpRequest {
+    method: String,
+    path: String,
+    query: HashMap<String, String>,
+    /// Header names are lower-cased.
+    headers: HashMap<String, String>,
+}
+
+struct HttpResponse {
+    status: u16,
+    content_type: &'static str,
+    body: String,
+}
+
+impl HttpResponse {
+    fn text(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: body.into(),
+        }
+    }
+
+    fn json(status: u16, value: &impl Serialize) -> Self {
+        match serde_json::to_string(value) {
+            Ok(body) => Self {
+                status,
+                content_type: "application/json",
+                body,
+            },
+            Err(error) => Self::json_error(500, &format!("Failed to encode response: {error}")),
+        }
+    }
+
+    fn json_error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: serde_json::json!({ "error": message }).to_string(),
+        }
+    }
+}
+
+fn serve_http_connection(app: &AppHandle, mut stream: TcpStream, handle: HttpHandler) {
+    // BSD-derived platforms hand back accepted sockets with the listener's
+    // non-blocking flag still set.
+    let _ = stream.set_nonblocking(false);
+    let _ = stream.set_read_timeout(Some(HTTP_READ_TIMEOUT));
+
+    let response = match read_http_request(&mut stream) {
+        Some(request) => handle(app, &request),
+        None => HttpResponse::text(400, "Bad Request\n"),
+    };
+
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    };
+    let head = format!(
+        "HTTP/1.1 {} {reason}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        response.content_type,
+        response.body.len()
+    );
+    let _ = stream.write_all(head.as_bytes());
+    let _ = stream.write_all(response.body.as_bytes());
+}
+
+fn read_http_request(stream: &mut TcpStream) -> Option<HttpRequest> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+    while !buffer.windows(4).any(|window| window == b"\r\n\r\n") {
+        let read = stream.read(&mut chunk).ok()?;
+        if read == 0 || buffer.len() + read > HTTP_MAX_REQUEST_BYTES {
+            return None;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+
+    let head = String::from_utf8_lossy(&buffer);
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let target = request_line.next()?;
+
+    let headers = lines
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let query = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key), percent_decode(value))
+        })
+        .collect();
+
+    Some(HttpRequest {
+        method,
+        path: path.to_string(),
+        query,
+        headers,
+    })
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let hex = |byte: u8| (byte as char).to_digit(16).map(|digit| digit as u8);
+
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = match bytes[index..] {
+            [b'%', high, low, ..] => hex(high).zip(hex(low)).map(|(high, low)| high << 4 | low),
+            _ => None,
+        };
+        match (escaped, bytes[index]) {
+            (Some(byte), _) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            (None, b'+') => {
+                decoded.push(b' ');
+                index += 1;
+            }
+            (None, byte) => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+type HttpHandler = fn(&AppHandle, &HttpRequest) -> HttpResponse;
+
+/// An opt-in loopback HTTP service whose enabled flag and port persist in a
+/// settings file, restarted in place whenever they change.
+struct HttpService {
+    name: &'static str,
+    settings_file: &'static str,
+    handler: HttpHandler,
+    dir: Option<PathBuf>,
+    state: Mutex<(ListenerSettings, Option<HttpServer>)>,
+}
+
+impl HttpService {
+    fn load(
+        name: &'static str,
+        settings_file: &'static str,
+        default_port: u16,
+        handler: HttpHandler,
+        dir: Option<PathBuf>,
+        app: AppHandle,
+    ) -> Self {
+        let settings = load_settings_file::<ListenerSettings>(dir.as_deref(), settings_file)
+            .unwrap_or(ListenerSettings {
+                enabled: false,
+                port: default_port,
+            });
+        let server = if settings.enabled {
+            HttpServer::spawn(name, settings.port, app, handler).ok()
+        } else {
+            None
+        };
+
+        Self {
+            name,
+            settings_file,
+            handler,
+            dir,
+            state: Mutex::new((settings, server)),
+        }
+    }
+
+    fn status(&self) -> ListenerStatus {
+        let state = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        ListenerStatus {
+            enabled: state.0.enabled,
+            port: state.0.port,
+            running: state.1.is_some(),
+        }
+    }
+
+    fn configure(
+        &self,
+        app: AppHandle,
+        settings: ListenerSettings,
+    ) -> Result<ListenerStatus, String> {
+        if settings.port == 0 {
+            return Err("Port must be between 1 and 65535".to_string());
+        }
+        let dir = self
+            .dir
+            .as_ref()
+            .ok_or_else(|| "No app data directory available for settings".to_string())?;
+
+        let mut state = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(server) = state.1.take() {
+            server.stop();
+        }
+        if settings.enabled {
+            state.1 = Some(HttpServer::spawn(
+                self.name,
+                settings.port,
+                app,
+                self.handler,
+            )?);
+        }
+
+        save_settings_file(dir, self.settings_file, &settings)?;
+        state.0 = settings;
+
+        Ok(ListenerStatus {
+            enabled: state.0.enabled,
+            port: state.0.port,
+            running: state.1.is_some(),
+        })
+    }
+}
+
+/// Prometheus text-format endpoint at `http://127.0.0.1:<port>/metrics`.
+struct MetricsExporter(HttpService);
+
+/// JSON mirror of the snapshot/ports/kill commands for local scripts.
+struct LocalApi(HttpService);
+
+fn handle_metrics_request(app: &AppHandle, request: &HttpRequest) -> HttpResponse {
+    if request.path != "/metrics" {
+        return HttpResponse::text(404, "Not Found\n");
+    }
+    if request.method != "GET" {
+        return HttpResponse::text(405, "Method Not Allowed\n");
+    }
+
+    let sample = app.state::<Sampler>().latest();
+    let ports = collect_ports().ok();
+    HttpResponse {
+        status: 200,
+        content_type: "text/plain; version=0.0.4; charset=utf-8",
+        body: render_prometheus_metrics(&sample, ports.as_deref()),
+    }
+}
+
+fn prometheus_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn render_prometheus_metrics(sample: &ProcessSample, ports: Option<&[PortInfo]>) -> String {
+    let mut out = String::new();
+
+    let _ = writeln!(
+        out,
+        "# HELP pswtf_process_count Number of running processes."
+    );
+    let _ = writeln!(out, "# TYPE pswtf_process_count gauge");
+    let _ = writeln!(out, "pswtf_process_count {}", sample.processes.len());
+
+    let _ = writeln!(
+        out,
+        "# HELP pswtf_system_cpu_percent Global CPU usage across all cores."
+    );
+    let _ = writeln!(out, "# TYPE pswtf_system_cpu_percent gauge");
+    let _ = writeln!(out, "pswtf_system_cpu_percent {}", sample.cpu_percent);
+
+    let mut by_cpu = sample.processes.iter().collect::<Vec<_>>();
+    by_cpu.sort_by(|a, b| b.cpu_percent.total_cmp(&a.cpu_percent));
+    let _ = writeln!(
+        out,
+        "# HELP pswtf_process_cpu_percent CPU usage of the top {METRICS_TOP_N} processes by CPU."
+    );
+    let _ = writeln!(out, "# TYPE pswtf_process_cpu_percent gauge");
+    for process in by_cpu.into_iter().take(METRICS_TOP_N) {
+        let _ = writeln!(
+            out,
+            "pswtf_process_cpu_percent{{pid=\"{}\",name=\"{}\"}} {}",
+            process.pid,
+            prometheus_label(&process.name),
+            process.cpu_percent
+        );
+    }
+
+    let mut by_memory = sample.processes.iter().collect::<Vec<_>>();
+    by_memory.sort_by(|a, b| b.memory_bytes.cmp(&a.memory_bytes));
+    let _ = writeln!(
+        out,
+        "# HELP pswtf_process_memory_bytes Resident memory of the top {METRICS_TOP_N} processes by memory."
+    );
+    let _ = writeln!(out, "# TYPE pswtf_process_memory_bytes gauge");
+    for process in by_memory.into_iter().take(METRICS_TOP_N) {
+        let _ = writeln!(
+            out,
+            "pswtf_process_memory_bytes{{pid=\"{}\",name=\"{}\"}} {}",
+            process.pid,
+            prometheus_label(&process.name),
+            process.memory_bytes
+        );
+    }
+
+    // Leave the series out entirely when lsof fails rather than reporting 0.
+    if let Some(ports) = ports {
+        let mut by_protocol = HashMap::<&str, usize>::new();
+        for port in ports {
+            *by_protocol.entry(port.protocol.as_str()).or_default() += 1;
+        }
+        let mut by_protocol = by_protocol.into_iter().collect::<Vec<_>>();
+        by_protocol.sort();
+
+        let _ = writeln!(
+            out,
+            "# HELP pswtf_listening_ports Listening TCP and bound UDP sockets."
+        );
+        let _ = writeln!(out, "# TYPE pswtf_listening_ports gauge");
+        for (protocol, count) in by_protocol {
+            let _ = writeln!(
+                out,
+                "pswtf_listening_ports{{protocol=\"{}\"}} {count}",
+                prometheus_label(protocol)
+            );
+        }
+    }
+
+    out
+}
+
+fn query_param<T: std::str::FromStr>(
+    request: &HttpRequest,
+    key: &str,
+) -> Result<Option<T>, String> {
+    request
+        .query
+        .get(key)
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|_| format!("Invalid value for {key}: {value}"))
+        })
+        .transpose()
+}
+
+/// Parses a camelCase enum query value the same way the Tauri IPC layer would.
+fn query_enum<T: for<'de> Deserialize<'de>>(
+    request: &HttpRequest,
+    key: &str,
+) -> Result<Option<T>, String> {
+    request
+        .query
+        .get(key)
+        .map(|value| {
+            serde_json::from_value(serde_json::Value::String(value.clone()))
+                .map_err(|_| format!("Invalid value for {key}: {value}"))
+        })
+        .transpose()
+}
+
+/// Routes `GET /snapshot`, `GET /ports` and `POST /kill` to the same code
+/// paths as the matching Tauri commands.
+fn handle_local_api_request(app: &AppHandle, request: &HttpRequest) -> HttpResponse {
+    // Loopback alone doesn't stop a web page from POSTing here, so refuse
+    // anything a browser sent cross-origin or via a rebound DNS name.
+    let host = request
+        .headers
+        .get("host")
+        .map(String::as_str)
+        .unwrap_or("");
+    let host = host.rsplit_once(':').map_or(host, |(name, _)| name);
+    if request.headers.contains_key("origin") || !matches!(host, "127.0.0.1" | "localhost") {
+        return HttpResponse::json_error(403, "Requests must come from a local client");
+    }
+
+    let result = match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/snapshot") => local_api_snapshot(app, request),
+        ("GET", "/ports") => collect_ports().map(|mut ports| {
+            annotate_docker_ports(&mut ports);
+            annotate_port_forwards(&mut ports);
+            merge_wsl_ports(&mut ports);
+            HttpResponse::json(200, &ports)
+        }),
+        ("GET", "/top") => local_api_top(app, request),
+        ("POST", "/kill") => local_api_kill(app, request),
+        (_, "/snapshot" | "/top" | "/ports" | "/kill") => {
+            return HttpResponse::json_error(405, "Method not allowed")
+        }
+        _ => return HttpResponse::json_error(404, "Not found"),
+    };
+
+    result.unwrap_or_else(|error| HttpResponse::json_error(400, &error))
+}
+
+fn local_api_snapshot(app: &AppHandle, request: &HttpRequest) -> Result<HttpResponse, String> {
+    let filter = ProcessFilter {
+        name_filter: request.query.get("name").cloned(),
+        min_cpu: query_param(request, "minCpu")?,
+        min_memory: query_param(request, "minMemory")?,
+        user: request.query.get("user").cloned(),
+        exe_outdated: query_param(request, "exeOutdated")?,
+    };
+    let mut snapshot = build_snapshot(
+        &app.state::<Sampler>().latest(),
+        &filter,
+        query_enum(request, "sortBy")?,
+        query_enum(request, "sortDir")?,
+        query_param(request, "limit")?,
+        query_param(request, "offset")?,
+        query_enum(request, "cpuNormalization")?,
+    );
+    if query_enum(request, "groupBy")? == Some(GroupBy::App) {
+        snapshot.groups = Some(group_by_app(&snapshot.processes));
+    }
+
+    Ok(HttpResponse::json(200, &snapshot))
+}
+
+fn local_api_top(app: &AppHandle, request: &HttpRequest) -> Result<HttpResponse, String> {
+    let top = top_processes(
+        &app.state::<Sampler>().latest().processes,
+        query_param(request, "n")?.unwrap_or(DEFAULT_TOP_COUNT),
+        query_enum(request, "metric")?.unwrap_or_default(),
+    );
+    Ok(HttpResponse::json(200, &top))
+}
+
+fn local_api_kill(app: &AppHandle, request: &HttpRequest) -> Result<HttpResponse, String> {
+    let pid = query_param::<i32>(request, "pid")?.ok_or_else(|| "Missing pid".to_string())?;
+    let include_children = query_param(request, "includeChildren")?.unwrap_or(true);
+    let force = query_param(request, "force")?;
+
+    let latest = app.state::<Sampler>().latest();
+    // A script can't answer an admin prompt, so the API never elevates.
+    let result = kill_process_tree(&latest.processes, pid, include_children, force, false);
+    audit_kill(
+        app,
+        "local-api",
+        KillTarget::Pid {
+            pid,
+            include_children,
+        },
+        force,
+        &latest.processes,
+        &result,
+    );
+
+    Ok(HttpResponse::json(200, &result?))
+}
+
+/// How often the port watcher re-runs lsof looking for new/closed listeners.
+const PORT_WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Runtimes whose listeners are almost always local dev servers, matched as
+/// prefixes since lsof truncates command names.
+const DEV_RUNTIMES: &[&str] = &[
+    "node", "deno", "bun", "python", "ruby", "rails", "php", "java", "dotnet", "cargo", "go",
+    "vite", "uvicorn", "gunicorn", "flask", "hugo", "jekyll",
+];
+
+fn is_dev_runtime(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    DEV_RUNTIMES.iter().any(|runtime| name.starts_with(runtime))
+}
+
+/// Ports registered through `watch_port`, keyed by (port, protocol), with the
+/// entry that held each one on the port watcher's last pass.
+#[derive(Default)]
+struct PortWatchState(Mutex<HashMap<(u16, String), Option<PortInfo>>>);
+
+const PORT_HISTORY_FILE: &str = "port-history.jsonl";
+const DEFAULT_PORT_HISTORY_LIMIT: usize = 200;
+
+/// A watched port changing hands. `bound` is false once nothing holds it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PortOwnershipEntry {
+    at_epoch_ms: u128,
+    port: u16,
+    protocol: String,
+    bound: bool,
+    pid: Option<i32>,
+    process_name: Option<String>,
+    cmd: Option<String>,
+    user: Option<String>,
+    /// When the owner was started, which tells a leftover from an earlier
+    /// session apart from something started since.
+    started_at_epoch_ms: Option<u128>,
+    /// The Docker container publishing the port, or the one the owner runs in.
+    container: Option<String>,
+}
+
+/// Whether a port is bound and by which PID, when that's known.
+type PortOwner = (bool, Option<i32>);
+
+/// Who held each watched port over time, as seen by the port watcher and
+/// kept across restarts.
+struct PortHistory {
+    log: JsonLinesLog,
+    /// The last owner written per port, seeded from the log so a restart
+    /// doesn't record the same owner again.
+    last: Mutex<HashMap<(u16, String), PortOwner>>,
+}
+
+impl PortHistory {
+    fn load(dir: Option<PathBuf>) -> Self {
+        let log = JsonLinesLog::new(dir, PORT_HISTORY_FILE, "port history");
+        let last = log
+            .read::<PortOwnershipEntry>(usize::MAX, |_| true)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|entry| ((entry.port, entry.protocol), (entry.bound, entry.pid)))
+            .collect();
+        Self {
+            log,
+            last: Mutex::new(last),
+        }
+    }
+
+    /// Logs `holder` as the owner of `port` unless it already was. A port
+    /// that has never been seen bound isn't worth an entry.
+    fn record(&self, port: u16, protocol: &str, holder: Option<&PortInfo>, sample: &ProcessSample) {
+        let key = (port, protocol.to_string());
+        let owner = (holder.is_some(), holder.and_then(|entry| entry.pid));
+        let mut last = self
+            .last
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if last.get(&key).copied().unwrap_or((false, None)) == owner {
+            return;
+        }
+
+        let now = epoch_millis();
+        let process = owner
+            .1
+            .and_then(|pid| sample.processes.iter().find(|process| process.pid == pid));
+        let entry = PortOwnershipEntry {
+            at_epoch_ms: now,
+            port,
+            protocol: protocol.to_string(),
+            bound: owner.0,
+            pid: owner.1,
+            process_name: process
+                .map(|process| process.name.clone())
+                .or_else(|| holder.and_then(|entry| entry.process_name.clone())),
+            cmd: process.map(|process| process.cmd.clone()),
+            user: process.and_then(|process| process.user.clone()),
+            started_at_epoch_ms: process.map(|process| process_started_at(process, now)),
+            container: holder
+                .and_then(|entry| entry.docker.as_ref())
+                .map(|docker| docker.container_name.clone())
+                .or_else(|| {
+                    process
+                        .and_then(|process| process.container.as_ref())
+                        .and_then(|container| container.name.clone())
+                }),
+        };
+        // Left unrecorded on failure so the next pass tries again.
+        if self.log.append(&entry).is_ok() {
+            last.insert(key, owner);
+        }
+    }
+
+    /// The most recent `limit` owners of `port`, oldest first, for either
+    /// protocol unless one is given.
+    fn history(
+        &self,
+        port: u16,
+        protocol: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<PortOwnershipEntry>, String> {
+        self.log.read(limit, |entry: &PortOwnershipEntry| {
+            entry.port == port && protocol.map_or(true, |protocol| entry.protocol == protocol)
+        })
+    }
+}
+
+/// How many past snapshots `get_snapshot_delta` can diff against; enough for
+/// a couple of independent pollers without holding the table forever.
+const SNAPSHOT_HISTORY_LEN: usize = 4;
+
+#[derive(Default)]
+struct SnapshotHistory {
+    next_token: u64,
+    snapshots: Vec<(u64, HashMap<i32, ProcessInfo>)>,
+}
+
+#[derive(Default)]
+struct SnapshotDeltaState(Mutex<SnapshotHistory>);
+
+/// How many `save_snapshot` results `diff_snapshots` can refer back to.
+const MAX_SAVED_SNAPSHOTS: usize = 16;
+/// Changes smaller than these are noise for "what changed after I clicked
+/// build"; both can be overridden per call.
+const DEFAULT_DIFF_CPU_DELTA: f32 = 10.0;
+const DEFAULT_DIFF_MEMORY_DELTA: u64 = 50 * 1024 * 1024;
+
+/// Process lists kept for `diff_snapshots`, separate from the delta
+/// history so polling `get_snapshot_delta` can't evict them.
+#[derive(Default)]
+struct SavedSnapshots(Mutex<SavedSnapshotStore>);
+
+#[derive(Default)]
+struct SavedSnapshotStore {
+    next_id: u64,
+    snapshots: VecDeque<(u64, Arc<ProcessSample>)>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SavedSnapshot {
+    id: u64,
+    collected_at_epoch_ms: u128,
+    process_count: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SnapshotDiff {
+    before_id: u64,
+    /// `None` when the diff was taken against the live sample.
+    after_id: Option<u64>,
+    elapsed_ms: u128,
+    #[serde(flatten)]
+    diff: ProcessDiff,
+}
+
+impl SavedSnapshots {
+    fn save(&self, sample: Arc<ProcessSample>) -> SavedSnapshot {
+        let mut store = self
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        store.next_id += 1;
+        let saved = SavedSnapshot {
+            id: store.next_id,
+            collected_at_epoch_ms: sample.collected_at_epoch_ms,
+            process_count: sample.processes.len(),
+        };
+        store.snapshots.push_back((saved.id, sample));
+        if store.snapshots.len() > MAX_SAVED_SNAPSHOTS {
+            store.snapshots.pop_front();
+        }
+        saved
+    }
+
+    fn get(&self, id: u64) -> Result<Arc<ProcessSample>, String> {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .snapshots
+            .iter()
+            .find(|(stored, _)| *stored == id)
+            .map(|(_, sample)| Arc::clone(sample))
+            .ok_or_else(|| format!("Snapshot {id} is unknown or has been discarded"))
+    }
+}
+
+#[derive(Default)]
+struct NetworkRateState(Mutex<CounterRates>);
+
+#[derive(Default)]
+struct DiskRateState(Mutex<CounterRates>);
+
+fn compute_snapshot_delta(
+    state: &SnapshotDeltaState,
+    since_token: Option<u64>,
+    processes: Vec<ProcessInfo>,
+    collected_at_epoch_ms: u128,
+) -> SnapshotDelta {
+    let mut history = state
+        .0
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let previous = since_token.and_then(|token| {
+        history
+            .snapshots
+            .iter()
+            .find(|(stored, _)| *stored == token)
+            .map(|(_, snapshot)| snapshot)
+    });
+
+    let process_count = processes.len();
+    let (changed, added, removed) = match previous {
+        Some(previous) => {
+            let current_pids = processes
+                .iter()
+                .map(|process| process.pid)
+                .collect::<HashSet<_>>();
+
+            let mut added = Vec::new();
+            let changed = processes
+                .iter()
+                .filter(|process| match previous.get(&process.pid) {
+                    Some(before) => process_metrics_changed(before, process),
+                    None => {
+                        added.push(process.pid);
+                        true
+                    }
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+
+            let mut removed = previous
+                .keys()
+                .filter(|pid| !current_pids.contains(pid))
+                .copied()
+                .collect::<Vec<_>>();
+            removed.sort_unstable();
+
+            (changed, added, removed)
+        }
+        None => (processes.clone(), Vec::new(), Vec::new()),
+    };
+    let incremental = previous.is_some();
+
+    history.next_token += 1;
+    let token = history.next_token;
+    let snapshot = processes
+        .into_iter()
+        .map(|process| (process.pid, process))
+        .collect();
+    history.snapshots.push((token, snapshot));
+    if history.snapshots.len() > SNAPSHOT_HISTORY_LEN {
+        history.snapshots.remove(0);
+    }
+
+    SnapshotDelta {
+        token,
+        incremental,
+        collected_at_epoch_ms,
+        process_count,
+        changed,
+        added,
+        removed,
+    }
+}
+
+fn encode_snapshot(
+    snapshot: ProcessSnapshot,
+    encoding: PayloadEncoding,
+) -> Result<SnapshotPayload, String> {
+    match encoding {
+        PayloadEncoding::Json => Ok(SnapshotPayload::Json(snapshot)),
+        PayloadEncoding::Msgpack => {
+            // Named fields keep the decoded objects shaped like the JSON ones.
+            let bytes = rmp_serde::to_vec_named(&snapshot)
+                .map_err(|error| format!("Failed to encode snapshot: {error}"))?;
+            Ok(SnapshotPayload::Encoded(EncodedPayload {
+                encoding: "msgpack",
+                data: base64::engine::general_purpose::STANDARD.encode(bytes),
+            }))
+        }
+    }
+}
+
+fn port_binding(
+    sample: &ProcessSample,
+    port: u16,
+    protocol: &str,
+    holder: Option<&PortInfo>,
+) -> PortBinding {
+    PortBinding {
+        port,
+        protocol: protocol.to_string(),
+        bound: holder.is_some(),
+        owner: holder.and_then(|entry| {
+            sample
+                .processes
+                .iter()
+                .find(|process| Some(process.pid) == entry.pid)
+                .cloned()
+        }),
+    }
+}
+
+fn emit_port_watch_transitions(
+    app: &AppHandle,
+    ports: &HashMap<String, PortInfo>,
+    sample: &ProcessSample,
+) {
+    let watches = app.state::<PortWatchState>();
+    let mut watches = watches
+        .0
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let history = app.state::<PortHistory>();
+    for ((port, protocol), last) in watches.iter_mut() {
+        let holder = find_port_holder(ports.values(), *port, protocol);
+        history.record(*port, protocol, holder, sample);
+
+        // A different PID taking over counts as a fresh bind so callers
+        // always see the current owner.
+        let changed = match (last.as_ref(), holder) {
+            (None, None) => false,
+            (Some(before), Some(now)) => before.pid != now.pid,
+            _ => true,
+        };
+        if !changed {
+            continue;
+        }
+
+        let binding = port_binding(sample, *port, protocol, holder);
+        let event = if binding.bound {
+            let owner = holder
+                .and_then(|entry| entry.process_name.clone())
+                .unwrap_or_else(|| "unknown".to_string());
+            let pid = holder
+                .and_then(|entry| entry.pid)
+                .map_or_else(|| "-".to_string(), |pid| pid.to_string());
+            notify(
+                app,
+                &format!("Port {port}/{protocol} is in use"),
+                &format!("Now held by {owner} (PID {pid})."),
+            );
+            "port-bound"
+        } else {
+            notify(
+                app,
+                &format!("Port {port}/{protocol} is free"),
+                "Nothing is listening on it any more.",
+            );
+            "port-freed"
+        };
+        let _ = app.emit_all(event, binding);
+        *last = holder.cloned();
+    }
+}
+
+/// The tray menu is rebuilt on its own cadence; replacing it every sampler
+/// pass would close it under the user's cursor.
+const TRAY_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+const TRAY_TOP_N: usize = 5;
+
+fn build_tray_menu(sample: &ProcessSample, watches: &PortWatchState) -> SystemTrayMenu {
+    let mut menu =
+        SystemTrayMenu::new().add_item(CustomMenuItem::new("header-cpu", "Top CPU").disabled());
+
+    let mut by_cpu = sample.processes.iter().collect::<Vec<_>>();
+    by_cpu.sort_by(|a, b| b.cpu_percent.total_cmp(&a.cpu_percent));
+    for process in by_cpu.into_iter().take(TRAY_TOP_N) {
+        menu = menu.add_item(CustomMenuItem::new(
+            tray_kill_id(sample, process),
+            format!(
+                "Kill {} ({}) \u{2014} {:.1}%",
+                process.name, process.pid, process.cpu_percent
+            ),
+        ));
+    }
+
+    let watches = watches
+        .0
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if !watches.is_empty() {
+        menu = menu
+            .add_native_item(SystemTrayMenuItem::Separator)
+            .add_item(CustomMenuItem::new("header-ports", "Watched ports").disabled());
+
+        let mut watched = watches.iter().collect::<Vec<_>>();
+        watched.sort_by(|a, b| a.0.cmp(b.0));
+        for ((port, protocol), holder) in watched {
+            let id = format!("port:{port}:{protocol}");
+            menu = menu.add_item(
+                match holder.as_ref().and_then(|entry| {
+                    let pid = entry.pid?;
+                    let process = sample.processes.iter().find(|process| process.pid == pid)?;
+                    Some((entry, pid, process))
+                }) {
+                    Some((entry, pid, process)) => CustomMenuItem::new(
+                        tray_kill_id(sample, process),
+                        format!(
+                            "Kill {} ({pid}) on {port}/{protocol}",
+                            entry.process_name.as_deref().unwrap_or("unknown")
+                        ),
+                    ),
+                    None => {
+                        CustomMenuItem::new(id, format!("{port}/{protocol} is free")).disabled()
+                    }
+                },
+            );
+        }
+    }
+
+    menu.add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(CustomMenuItem::new("show", "Show pswtf"))
+        .add_item(CustomMenuItem::new("quit", "Quit"))
+}
+
+/// `kill:<pid>:<started at>:<name>`. The menu can be several seconds old
+/// when it's clicked, so the item carries enough to tell whether the PID
+/// still belongs to the same process. The name goes last since it can hold
+/// colons.
+fn tray_kill_id(sample: &ProcessSample, process: &ProcessInfo) -> String {
+    format!(
+        "kill:{}:{}:{}",
+        process.pid,
+        process_started_at(process, sample.collected_at_epoch_ms),
+        process.name
+    )
+}
+
+fn parse_tray_kill_id(id: &str) -> Option<(i32, u128, &str)> {
+    let mut parts = id.strip_prefix("kill:")?.splitn(3, ':');
+    let pid = parts.next()?.parse().ok()?;
+    let started_at = parts.next()?.parse().ok()?;
+    Some((pid, started_at, parts.next()?))
+}
+
+fn tray_kill(app: &AppHandle, pid: i32, started_at_epoch_ms: u128, name: &str) {
+    let latest = app.state::<Sampler>().latest();
+    let same_process = find_same_process(
+        &latest.processes,
+        pid,
+        name,
+        started_at_epoch_ms,
+        latest.collected_at_epoch_ms,
+    );
+    if same_process.is_none() {
+        notify(
+            app,
+            &format!("Not killing {name}"),
+            &format!(
+                "PID {pid} is no longer {name}; it exited or was reused since the menu was built."
+            ),
+        );
+        refresh_tray_menu(app);
+        return;
+    }
+
+    let report = perform_kill(vec![pid], &[pid], resolve_signal(None), false);
+    audit_kill(
+        app,
+        "tray",
+        KillTarget::Pid {
+            pid,
+            include_children: false,
+        },
+        None,
+        &latest.processes,
+        &Ok(report.clone()),
+    );
+    let _ = app.emit_all("tray-kill", &report);
+    refresh_tray_menu(app);
+}
+
+fn refresh_tray_menu(app: &AppHandle) {
+    let menu = build_tray_menu(
+        &app.state::<Sampler>().latest(),
+        &app.state::<PortWatchState>(),
+    );
+    let _ = app.tray_handle().set_menu(menu);
+}
+
+fn start_tray_refresher(app: AppHandle) {
+    std::thread::Builder::new()
+        .name("pswtf-tray".to_string())
+        .spawn(move || loop {
+            refresh_tray_menu(&app);
+            std::thread::sleep(TRAY_REFRESH_INTERVAL);
+        })
+        .expect("failed to spawn tray refresher thread");
+}
+
+fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+fn handle_tray_event(app: &AppHandle, event: SystemTrayEvent) {
+    match event {
+        SystemTrayEvent::LeftClick { .. } if !cfg!(target_os = "macos") => show_main_window(app),
+        SystemTrayEvent::MenuItemClick { id, .. } => match id.as_str() {
+            "show" => show_main_window(app),
+            "quit" => app.exit(0),
+            _ => {
+                if let Some((pid, started_at, name)) = parse_tray_kill_id(&id) {
+                    tray_kill(app, pid, started_at, name);
+                }
+            }
+        },
+        _ => {}
+    }
+}
+
+const DEEP_LINK_SCHEME: &str = "pswtf";
+
+/// A parsed `pswtf://` URL. The UI owns the confirmation step, so these only
+/// describe what was asked for.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "action", rename_all = "camelCase")]
+enum DeepLink {
+    #[serde(rename_all = "camelCase")]
+    KillPort { port: u16 },
+    #[serde(rename_all = "camelCase")]
+    Inspect { pid: i32 },
+}
+
+/// The most recent deep link the UI hasn't picked up yet; links that launch
+/// the app arrive before the webview is listening.
+#[derive(Default)]
+struct DeepLinkState(Mutex<Option<DeepLink>>);
+
+fn parse_deep_link(url: &str) -> Result<DeepLink, String> {
+    let rest = url
+        .strip_prefix(DEEP_LINK_SCHEME)
+        .and_then(|rest| rest.strip_prefix("://"))
+        .ok_or_else(|| format!("Not a {DEEP_LINK_SCHEME}:// link: {url}"))?;
+    let rest = rest.split(['?', '#']).next().unwrap_or_default();
+
+    match rest.trim_end_matches('/').split_once('/') {
+        Some(("kill-port", port)) => port
+            .parse::<u16>()
+            .ok()
+            .filter(|port| *port > 0)
+            .map(|port| DeepLink::KillPort { port })
+            .ok_or_else(|| format!("Invalid port in deep link: {port}")),
+        Some(("inspect", pid)) => pid
+            .parse::<i32>()
+            .ok()
+            .filter(|pid| *pid > 0)
+            .map(|pid| DeepLink::Inspect { pid })
+            .ok_or_else(|| format!("Invalid PID in deep link: {pid}")),
+        _ => Err(format!("Unsupported deep link: {url}")),
+    }
+}
+
+fn dispatch_deep_link(app: &AppHandle, url: &str) {
+    // macOS debug builds also deliver an empty event for the secondary instance.
+    if url.trim().is_empty() {
+        return;
+    }
+
+    match parse_deep_link(url.trim()) {
+        Ok(link) => {
+            *app.state::<DeepLinkState>()
+                .0
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(link);
+            show_main_window(app);
+            let _ = app.emit_all("deep-link", ());
+        }
+        Err(error) => {
+            let _ = app.emit_all("deep-link-error", error);
+        }
+    }
+}
+
+/// Flags a dev server that just bound the wildcard address, since that puts
+/// it on whatever network the machine is on.
+fn warn_if_exposed(app: &AppHandle, event: &PortEvent) {
+    if event.port.exposure != PortExposure::All {
+        return;
+    }
+    let name = event
+        .process
+        .as_ref()
+        .map(|process| process.name.as_str())
+        .or(event.port.process_name.as_deref())
+        .unwrap_or_default();
+    if !is_dev_runtime(name) {
+        return;
+    }
+
+    let _ = app.emit_all("port-exposed", event);
+    notify(
+        app,
+        &format!("{name} is listening on all interfaces"),
+        &format!(
+            "Port {} ({}) is reachable from other machines on the network.",
+            event.port.port, event.port.protocol
+        ),
+    );
+}
+
+fn start_port_watcher(app: AppHandle) {
+    std::thread::Builder::new()
+        .name("pswtf-port-watcher".to_string())
+        .spawn(move || {
+            let mut previous: Option<HashMap<String, PortInfo>> = None;
+
+            loop {
+                std::thread::sleep(PORT_WATCH_INTERVAL);
+
+                let sampler = app.state::<Sampler>();
+                if sampler.status().paused {
+                    continue;
+                }
+
+                let Ok(ports) = collect_ports() else {
+                    continue;
+                };
+                let current = ports
+                    .into_iter()
+                    .map(|port| (port_identity(&port), port))
+                    .collect::<HashMap<_, _>>();
+
+                let sample = sampler.latest();
+                emit_port_watch_transitions(&app, &current, &sample);
+
+                // The first pass only establishes a baseline; everything
+                // already listening at launch isn't "newly opened".
+                if let Some(previous) = &previous {
+                    let owner = |port: &PortInfo| {
+                        sample
+                            .processes
+                            .iter()
+                            .find(|process| Some(process.pid) == port.pid)
+                            .cloned()
+                    };
+
+                    let exposure_warnings = app.state::<Notifier>().settings().exposure_warnings;
+                    for (key, port) in &current {
+                        if !previous.contains_key(key) {
+                            let event = PortEvent {
+                                process: owner(port),
+                                port: port.clone(),
+                            };
+                            if exposure_warnings {
+                                warn_if_exposed(&app, &event);
+                            }
+                            let _ = app.emit_all("port-opened", event);
+                        }
+                    }
+
+                    for (key, port) in previous {
+                        if !current.contains_key(key) {
+                            let event = PortEvent {
+                                process: owner(port),
+                                port: port.clone(),
+                            };
+                            let _ = app.emit_all("port-closed", event);
+                        }
+                    }
+                }
+
+                previous = Some(current);
+            }
+        })
+        .expect("failed to spawn port watcher thread");
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+fn get_process_snapshot(
+    name_filter: Option<String>,
+    min_cpu: Option<f32>,
+    min_memory: Option<u64>,
+    user: Option<String>,
+    exe_outdated: Option<bool>,
+    sort_by: Option<SortKey>,
+    sort_dir: Option<SortDirection>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+    cpu_normalization: Option<CpuNormalization>,
+    group_by: Option<GroupBy>,
+    encoding: Option<PayloadEncoding>,
+    sampler: State<'_, Sampler>,
+) -> Result<SnapshotPayload, String> {
+    let filter = ProcessFilter {
+        name_filter,
+        min_cpu,
+        min_memory,
+        user,
+        exe_outdated,
+    };
+
+    let mut snapshot = build_snapshot(
+        &sampler.latest(),
+        &filter,
+        sort_by,
+        sort_dir,
+        limit,
+        offset,
+        cpu_normalization,
+    );
+    if group_by == Some(GroupBy::App) {
+        snapshot.groups = Some(group_by_app(&snapshot.processes));
+    }
+    encode_snapshot(snapshot, encoding.unwrap_or_default())
+}
+
+#[tauri::command]
+fn get_snapshot_delta(
+    since_token: Option<u64>,
+    sampler: State<'_, Sampler>,
+    state: State<'_, SnapshotDeltaState>,
+) -> SnapshotDelta {
+    let sample = sampler.latest();
+
+    compute_snapshot_delta(
+        &state,
+        since_token,
+        sample.processes.clone(),
+        sample.collected_at_epoch_ms,
+    )
+}
+
+/// Keeps the current sample for a later `diff_snapshots`.
+#[tauri::command]
+fn save_snapshot(sampler: State<'_, Sampler>, saved: State<'_, SavedSnapshots>) -> SavedSnapshot {
+    saved.save(sampler.latest())
+}
+
+/// What appeared, went away or moved noticeably between two saved
+/// snapshots, or between one and now when `after` is left out.
+#[tauri::command]
+fn diff_snapshots(
+    before: u64,
+    after: Option<u64>,
+    min_cpu_delta: Option<f32>,
+    min_memory_delta_bytes: Option<u64>,
+    sampler: State<'_, Sampler>,
+    saved: State<'_, SavedSnapshots>,
+) -> Result<SnapshotDiff, String> {
+    let earlier = saved.get(before)?;
+    let later = match after {
+        Some(id) => saved.get(id)?,
+        None => sampler.latest(),
+    };
+
+    Ok(SnapshotDiff {
+        before_id: before,
+        after_id: after,
+        elapsed_ms: later
+            .collected_at_epoch_ms
+            .saturating_sub(earlier.collected_at_epoch_ms),
+        diff: diff_processes(
+            &earlier.processes,
+            &later.processes,
+            min_cpu_delta.unwrap_or(DEFAULT_DIFF_CPU_DELTA),
+            min_memory_delta_bytes.unwrap_or(DEFAULT_DIFF_MEMORY_DELTA),
+        ),
+    })
+}
+
+#[tauri::command]
+fn get_monitoring_status(sampler: State<'_, Sampler>) -> MonitoringStatus {
+    sampler.status()
+}
+
+#[tauri::command]
+fn set_refresh_interval(ms: u64, sampler: State<'_, Sampler>) -> Result<MonitoringStatus, String> {
+    check_refresh_interval(ms)?;
+    Ok(sampler.update(|settings| settings.interval_ms = ms))
+}
+
+fn check_refresh_interval(ms: u64) -> Result<(), String> {
+    if !(MIN_SAMPLE_INTERVAL_MS..=MAX_SAMPLE_INTERVAL_MS).contains(&ms) {
+        return Err(format!(
+            "Refresh interval must be between {MIN_SAMPLE_INTERVAL_MS} and {MAX_SAMPLE_INTERVAL_MS} ms"
+        ));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn pause_monitoring(sampler: State<'_, Sampler>) -> MonitoringStatus {
+    sampler.update(|settings| settings.paused = true)
+}
+
+#[tauri::command]
+fn resume_monitoring(sampler: State<'_, Sampler>) -> MonitoringStatus {
+    sampler.update(|settings| settings.paused = false)
+}
+
+#[tauri::command]
+fn get_history_settings(history: State<'_, HistoryRecorder>) -> HistorySettings {
+    history.settings()
+}
+
+#[tauri::command]
+fn set_history_settings(
+    enabled: bool,
+    retention_seconds: Option<u64>,
+    history: State<'_, HistoryRecorder>,
+) -> Result<HistorySettings, String> {
+    let retention_seconds = retention_seconds.unwrap_or(DEFAULT_HISTORY_RETENTION_SECS);
+    if !(MIN_HISTORY_RETENTION_SECS..=MAX_HISTORY_RETENTION_SECS).contains(&retention_seconds) {
+        return Err(format!(
+            "History retention must be between {MIN_HISTORY_RETENTION_SECS} and {MAX_HISTORY_RETENTION_SECS} seconds"
+        ));
+    }
+
+    history.configure(HistorySettings {
+        enabled,
+        retention_seconds,
+    })
+}
+
+#[tauri::command(async)]
+fn get_process_history(
+    pid: i32,
+    window_seconds: Option<u64>,
+    resolution: Option<u32>,
+    history: State<'_, HistoryRecorder>,
+    sampler: State<'_, Sampler>,
+) -> Result<ProcessHistory, String> {
+    if pid <= 0 {
+        return Err("PID must be a positive integer".to_string());
+    }
+
+    let window_seconds = window_seconds.unwrap_or(DEFAULT_HISTORY_WINDOW_SECS);
+    if !(1..=MAX_HISTORY_RETENTION_SECS).contains(&window_seconds) {
+        return Err(format!(
+            "History window must be between 1 and {MAX_HISTORY_RETENTION_SECS} seconds"
+        ));
+    }
+
+    let resolution = resolution.unwrap_or(DEFAULT_HISTORY_RESOLUTION);
+    if !(1..=MAX_HISTORY_RESOLUTION).contains(&resolution) {
+        return Err(format!(
+            "History resolution must be between 1 and {MAX_HISTORY_RESOLUTION} points"
+        ));
+    }
+
+    let latest = sampler.latest();
+    let started_at_ms = latest
+        .processes
+        .iter()
+        .find(|process| process.pid == pid)
+        .map(|process| process_started_at(process, latest.collected_at_epoch_ms));
+
+    Ok(ProcessHistory {
+        pid,
+        window_seconds,
+        resolution,
+        points: history.process_history(pid, started_at_ms, window_seconds, resolution)?,
+    })
+}
+
+#[tauri::command]
+fn get_notification_settings(notifier: State<'_, Notifier>) -> NotificationSettings {
+    notifier.settings()
+}
+
+#[tauri::command]
+fn set_notification_settings(
+    enabled: bool,
+    cpu_threshold_percent: Option<f32>,
+    cpu_sustain_seconds: Option<u64>,
+    exposure_warnings: Option<bool>,
+    notifier: State<'_, Notifier>,
+) -> Result<NotificationSettings, String> {
+    if cpu_threshold_percent.is_some_and(|threshold| threshold.is_nan() || threshold <= 0.0) {
+        return Err("CPU threshold must be greater than 0".to_string());
+    }
+
+    notifier.configure(NotificationSettings {
+        enabled,
+        cpu_threshold_percent,
+        cpu_sustain_seconds: cpu_sustain_seconds.unwrap_or(DEFAULT_CPU_SUSTAIN_SECS),
+        // Callers from before this setting existed leave it out.
+        exposure_warnings: exposure_warnings
+            .unwrap_or_else(|| notifier.settings().exposure_warnings),
+    })
+}
+
+#[tauri::command]
+fn get_protected_processes() -> ProtectedList {
+    protected_list()
+}
+
+/// Replaces the protected list. pswtf's own ancestry stays protected
+/// regardless of what's configured here.
+#[tauri::command]
+fn set_protected_processes(
+    names: Vec<String>,
+    pids: Vec<i32>,
+    protected: State<'_, ProtectedProcesses>,
+) -> Result<ProtectedList, String> {
+    protected.configure(checked_protected_list(names, pids)?)
+}
+
+fn checked_protected_list(names: Vec<String>, pids: Vec<i32>) -> Result<ProtectedList, String> {
+    if pids.iter().any(|pid| *pid <= 0) {
+        return Err("PID must be a positive integer".to_string());
+    }
+
+    let mut names = names
+        .into_iter()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect::<Vec<_>>();
+    names.dedup();
+
+    Ok(ProtectedList { names, pids })
+}
+
+#[tauri::command]
+fn get_settings(store: State<'_, SettingsStore>) -> AppSettings {
+    AppSettings {
+        stored: store.stored(),
+        protected: protected_list(),
+    }
+}
+
+/// Updates whichever settings are given and leaves the rest alone. Nothing
+/// is saved unless every given value is valid.
+#[tauri::command(async)]
+#[allow(clippy::too_many_arguments)]
+fn set_settings(
+    refresh_interval_ms: Option<u64>,
+    saved_filters: Option<Vec<SavedFilter>>,
+    protected: Option<ProtectedList>,
+    watched_ports: Option<Vec<WatchedPort>>,
+    store: State<'_, SettingsStore>,
+    protected_processes: State<'_, ProtectedProcesses>,
+    sampler: State<'_, Sampler>,
+    watches: State<'_, PortWatchState>,
+) -> Result<AppSettings, String> {
+    let previous = store.stored();
+    let mut settings = previous.clone();
+
+    if let Some(ms) = refresh_interval_ms {
+        check_refresh_interval(ms)?;
+        settings.refresh_interval_ms = ms;
+    }
+    if let Some(filters) = saved_filters {
+        for filter in &filters {
+            if filter.name.trim().is_empty() {
+                return Err("Saved filters need a name".to_string());
+            }
+            parse_query(&filter.query)
+                .map_err(|error| format!("Saved filter \"{}\": {error}", filter.name))?;
+        }
+        settings.saved_filters = filters;
+    }
+    if let Some(ports) = watched_ports {
+        let mut checked = Vec::<WatchedPort>::new();
+        for watch in ports {
+            let watch = WatchedPort {
+                port: checked_watch_port(watch.port)?,
+                protocol: checked_watch_protocol(Some(watch.protocol))?,
+            };
+            if !checked.contains(&watch) {
+                checked.push(watch);
+            }
+        }
+        settings.watched_ports = checked;
+    }
+    let protected = protected
+        .map(|list| checked_protected_list(list.names, list.pids))
+        .transpose()?;
+
+    let settings = store.configure(settings)?;
+    if let Some(list) = protected {
+        protected_processes.configure(list)?;
+    }
+    if refresh_interval_ms.is_some() {
+        sampler.update(|status| status.interval_ms = settings.refresh_interval_ms);
+    }
+    sync_port_watches(&watches, &previous.watched_ports, &settings.watched_ports);
+
+    Ok(AppSettings {
+        stored: settings,
+        protected: protected_list(),
+    })
+}
+
+/// Drops watches that were removed from the saved list and registers new
+/// ones with their current holder, so the first pass doesn't report a port
+/// that was already in use as freshly bound. Watches added with
+/// `watch_port` and never saved are left alone.
+fn sync_port_watches(watches: &PortWatchState, previous: &[WatchedPort], current: &[WatchedPort]) {
+    let ports = if current.iter().any(|watch| !previous.contains(watch)) {
+        collect_ports().unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+
+    let mut watches = watches
+        .0
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    for watch in previous.iter().filter(|watch| !current.contains(watch)) {
+        watches.remove(&(watch.port, watch.protocol.clone()));
+    }
+    for watch in current {
+        watches
+            .entry((watch.port, watch.protocol.clone()))
+            .or_insert_with(|| find_port_holder(&ports, watch.port, &watch.protocol).cloned());
+    }
+}
+
+#[tauri::command]
+fn get_port_names() -> HashMap<u16, String> {
+    service_name_overrides()
+}
+
+/// Replaces the user's port labels; an empty label removes that override.
+#[tauri::command]
+fn set_port_names(
+    names: HashMap<u16, String>,
+    port_names: State<'_, PortNames>,
+) -> Result<HashMap<u16, String>, String> {
+    if names.contains_key(&0) {
+        return Err("Port must be between 1 and 65535".to_string());
+    }
+
+    let names = names
+        .into_iter()
+        .map(|(port, name)| (port, name.trim().to_string()))
+        .filter(|(_, name)| !name.is_empty())
+        .collect::<HashMap<_, _>>();
+    if let Some((port, _)) = names
+        .iter()
+        .find(|(_, name)| name.chars().count() > MAX_PORT_NAME_CHARS)
+    {
+        return Err(format!(
+            "Name for port {port} must be at most {MAX_PORT_NAME_CHARS} characters"
+        ));
+    }
+
+    port_names.configure(names)
+}
+
+#[tauri::command]
+fn add_rule(
+    name: String,
+    query: String,
+    match_mode: Option<MatchMode>,
+    condition: RuleCondition,
+    action: RuleAction,
+    rules: State<'_, RuleEngine>,
+) -> Result<Rule, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Rule name cannot be empty".to_string());
+    }
+    match &condition {
+        RuleCondition::Cpu { above_percent, .. }
+            if above_percent.is_nan() || *above_percent <= 0.0 =>
+        {
+            return Err("CPU threshold must be greater than 0".to_string());
+        }
+        RuleCondition::Memory { above_bytes: 0, .. } => {
+            return Err("Memory threshold must be greater than 0".to_string());
+        }
+        _ => {}
+    }
+
+    rules.add(
+        name,
+        query,
+        match_mode.unwrap_or_default(),
+        condition,
+        action,
+    )
+}
+
+#[tauri::command]
+fn list_rules(rules: State<'_, RuleEngine>) -> Vec<Rule> {
+    rules.rules()
+}
+
+#[tauri::command]
+fn remove_rule(id: u64, rules: State<'_, RuleEngine>) -> Result<(), String> {
+    rules.remove(id)
+}
+
+#[tauri::command]
+fn list_rule_activity(rules: State<'_, RuleEngine>) -> Vec<RuleFiring> {
+    rules.activity()
+}
+
+#[tauri::command]
+fn get_metrics_exporter(exporter: State<'_, MetricsExporter>) -> ListenerStatus {
+    exporter.0.status()
+}
+
+#[tauri::command]
+fn set_metrics_exporter(
+    enabled: bool,
+    port: Option<u16>,
+    app: AppHandle,
+    exporter: State<'_, MetricsExporter>,
+) -> Result<ListenerStatus, String> {
+    let port = port.unwrap_or(DEFAULT_METRICS_PORT);
+    exporter
+        .0
+        .configure(app, ListenerSettings { enabled, port })
+}
+
+#[tauri::command]
+fn get_local_api(api: State<'_, LocalApi>) -> ListenerStatus {
+    api.0.status()
+}
+
+#[tauri::command]
+fn set_local_api(
+    enabled: bool,
+    port: Option<u16>,
+    app: AppHandle,
+    api: State<'_, LocalApi>,
+) -> Result<ListenerStatus, String> {
+    let port = port.unwrap_or(DEFAULT_LOCAL_API_PORT);
+    api.0.configure(app, ListenerSettings { enabled, port })
+}
+
+/// Registers `pid` so the sampler emits `pid-exited` once it disappears. A PID
+/// that is already gone is reported on the next sampler pass.
+#[tauri::command]
+fn watch_pid(pid: i32, sampler: State<'_, Sampler>) -> Result<(), String> {
+    if pid <= 0 {
+        return Err("PID must be a positive integer".to_string());
+    }
+
+    sampler.watch(pid);
+    Ok(())
+}
+
+/// Starts tracking `pid`'s resident memory and alerts (or kills, per
+/// `action`) once it grows steadily past `min_growth_bytes_per_minute` over
+/// `window_seconds`, or crosses `ceiling_bytes`.
+#[tauri::command]
+fn watch_memory(
+    pid: i32,
+    min_growth_bytes_per_minute: Option<u64>,
+    ceiling_bytes: Option<u64>,
+    window_seconds: Option<u64>,
+    action: Option<RuleAction>,
+    sampler: State<'_, Sampler>,
+    watchdog: State<'_, MemoryWatchdog>,
+) -> Result<MemoryWatch, String> {
+    if pid <= 0 {
+        return Err("PID must be a positive integer".to_string());
+    }
+    if min_growth_bytes_per_minute.is_none() && ceiling_bytes.is_none() {
+        return Err("Set a growth rate, a memory ceiling, or both".to_string());
+    }
+    if min_growth_bytes_per_minute == Some(0) || ceiling_bytes == Some(0) {
+        return Err("Memory thresholds must be greater than 0".to_string());
+    }
+
+    let window_seconds = window_seconds.unwrap_or(DEFAULT_LEAK_WINDOW_SECS);
+    if !(MIN_LEAK_WINDOW_SECS..=MAX_LEAK_WINDOW_SECS).contains(&window_seconds) {
+        return Err(format!(
+            "Window must be between {MIN_LEAK_WINDOW_SECS} and {MAX_LEAK_WINDOW_SECS} seconds"
+        ));
+    }
+
+    let latest = sampler.latest();
+    let process = latest
+        .processes
+        .iter()
+        .find(|process| process.pid == pid)
+        .ok_or_else(|| format!("Process {pid} was not found"))?;
+
+    let watch = MemoryWatch {
+        pid,
+        process_name: process.name.clone(),
+        started_at_epoch_ms: process_started_at(process, latest.collected_at_epoch_ms),
+        min_growth_bytes_per_minute,
+        ceiling_bytes,
+        window_seconds,
+        action: action.unwrap_or(RuleAction::Notify),
+        growth_bytes_per_minute: None,
+        readings: VecDeque::new(),
+        alerted: false,
+    };
+    watchdog.lock().insert(pid, watch.clone());
+    Ok(watch)
+}
+
+#[tauri::command]
+fn unwatch_memory(pid: i32, watchdog: State<'_, MemoryWatchdog>) -> Result<(), String> {
+    watchdog
+        .lock()
+        .remove(&pid)
+        .map(|_| ())
+        .ok_or_else(|| format!("Process {pid} is not being watched"))
+}
+
+#[tauri::command]
+fn list_memory_watches(watchdog: State<'_, MemoryWatchdog>) -> Vec<MemoryWatch> {
+    let mut watches = watchdog.lock().values().cloned().collect::<Vec<_>>();
+    watches.sort_by_key(|watch| watch.pid);
+    watches
+}
+
+#[tauri::command]
+fn pin_process(
+    pattern: String,
+    sampler: State<'_, Sampler>,
+    pins: State<'_, PinnedProcesses>,
+) -> Result<PinnedProcess, String> {
+    let pattern = pattern.trim();
+    if pattern.is_empty() {
+        return Err("Pin pattern must not be empty".to_string());
+    }
+    pins.pin(pattern.to_string(), &sampler.latest())
+}
+
+#[tauri::command]
+fn unpin_process(pattern: String, pins: State<'_, PinnedProcesses>) -> Result<(), String> {
+    pins.unpin(pattern.trim())
+}
+
+#[tauri::command]
+fn list_pinned_processes(pins: State<'_, PinnedProcesses>) -> Vec<PinnedProcess> {
+    pins.lock().clone()
+}
+
+#[tauri::command(async)]
+fn get_system_overview(sampler: State<'_, Sampler>) -> SystemOverview {
+    collect_system_overview(&sampler.latest())
+}
+
+#[tauri::command(async)]
+fn get_energy_impact() -> Result<Vec<EnergyImpact>, String> {
+    collect_energy_impact()
+}
+
+#[tauri::command]
+fn get_cpu_cores(sampler: State<'_, Sampler>) -> Vec<CpuCoreInfo> {
+    sampler.latest().cpu_cores.clone()
+}
+
+#[tauri::command]
+fn get_network_interfaces(rate_state: State<'_, NetworkRateState>) -> Vec<NetworkInterfaceInfo> {
+    collect_network_interfaces(
+        &mut rate_state
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()),
+    )
+}
+
+#[tauri::command]
+fn get_disks(rate_state: State<'_, DiskRateState>) -> Vec<DiskInfo> {
+    collect_disks(
+        &mut rate_state
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()),
+    )
+}
+
+#[tauri::command]
+fn get_sensors() -> Vec<SensorReading> {
+    collect_sensors()
+}
+
+/// Filters the latest sample with the `cpu>50 name~node port:3000` syntax.
+/// Off the main thread since `port:` terms have to list sockets.
+#[tauri::command(async)]
+fn query_processes(expr: String, sampler: State<'_, Sampler>) -> Result<Vec<ProcessInfo>, String> {
+    let query = parse_query(&expr)?;
+    let ports = if query.uses_ports() {
+        collect_ports()?
+    } else {
+        Vec::new()
+    };
+
+    Ok(sampler
+        .latest()
+        .processes
+        .iter()
+        .filter(|process| query.matches(process, &ports))
+        .cloned()
+        .collect())
+}
+
+/// How many rows `get_top_processes` returns when the caller doesn't say.
+const DEFAULT_TOP_COUNT: usize = 10;
+
+/// A few rows off the latest sample, for a mini-view or menubar widget that
+/// polls far more often than the full table refreshes.
+#[tauri::command]
+fn get_top_processes(
+    n: Option<usize>,
+    metric: Option<SortKey>,
+    sampler: State<'_, Sampler>,
+) -> Vec<TopProcess> {
+    top_processes(
+        &sampler.latest().processes,
+        n.unwrap_or(DEFAULT_TOP_COUNT),
+        metric.unwrap_or_default(),
+    )
+}
+
+/// The working directory `get_process_details` would report for `pid`.
+fn resolve_process_cwd(pid: i32) -> Result<String, String> {
+    if pid <= 0 {
+        return Err("PID must be a positive integer".to_string());
+    }
+
+    let mut system = System::new();
+    let target_pid = Pid::from_u32(pid as u32);
+    if !system.refresh_process(target_pid) {
+        return Err(format!("Process {pid} was not found"));
+    }
+    system
+        .process(target_pid)
+        .and_then(|process| path_to_string(process.cwd()))
+        .or_else(|| process_cwd(pid))
+        .ok_or_else(|| format!("Can't read the working directory of process {pid}"))
+}
+
+#[tauri::command]
+fn open_terminal_at(pid: i32) -> Result<String, String> {
+    let cwd = resolve_process_cwd(pid)?;
+    open_terminal(Path::new(&cwd))?;
+    Ok(cwd)
+}
+
+/// `pid`'s command line as a pasteable `sh` snippet. `include_env` exports
+/// only the variables that differ from pswtf's own environment, which is
+/// usually what was set specially for that process.
+#[tauri::command]
+fn get_shell_command(
+    pid: i32,
+    include_cwd: Option<bool>,
+    include_env: Option<bool>,
+) -> Result<String, String> {
+    if pid <= 0 {
+        return Err("PID must be a positive integer".to_string());
+    }
+
+    let mut system = System::new();
+    let target_pid = Pid::from_u32(pid as u32);
+    if !system.refresh_process(target_pid) {
+        return Err(format!("Process {pid} was not found"));
+    }
+    let process = system
+        .process(target_pid)
+        .ok_or_else(|| format!("Process {pid} was not found"))?;
+    if process.cmd().is_empty() {
+        return Err(format!("Process {pid} has no command line to show"));
+    }
+
+    let cwd = if include_cwd.unwrap_or(false) {
+        Some(resolve_process_cwd(pid)?)
+    } else {
+        None
+    };
+    let mut env = Vec::new();
+    if include_env.unwrap_or(false) {
+        env = process
+            .environ()
+            .iter()
+            .filter_map(|entry| entry.split_once('='))
+            .filter(|(key, value)| std::env::var(key).ok().as_deref() != Some(*value))
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect::<Vec<_>>();
+        env.sort();
+    }
+
+    Ok(shell_command(process.cmd(), cwd.as_deref(), &env))
+}
+
+/// Off the main thread: this reads every process's fd table, or runs lsof.
+#[tauri::command(async)]
+fn find_file_users(path: String) -> Result<Vec<FileUser>, String> {
+    let path = path.trim();
+    if path.is_empty() {
+        return Err("Path must not be empty".to_string());
+    }
+    pswtf_core::fuser::find_file_users(Path::new(path))
+}
+
+/// What is stopping `mount_point` from being unmounted or ejected.
+#[tauri::command(async)]
+fn find_mount_blockers(mount_point: String) -> Result<Vec<FileUser>, String> {
+    let mount_point = mount_point.trim();
+    if mount_point.is_empty() {
+        return Err("Mount point must not be empty".to_string());
+    }
+    pswtf_core::fuser::find_mount_blockers(Path::new(mount_point))
+}
+
+#[tauri::command]
+fn reveal_executable(pid: i32, sampler: State<'_, Sampler>) -> Result<String, String> {
+    if pid <= 0 {
+        return Err("PID must be a positive integer".to_string());
+    }
+
+    let latest = sampler.latest();
+    let process = latest
+        .processes
+        .iter()
+        .find(|process| process.pid == pid)
+        .ok_or_else(|| format!("Process {pid} was not found"))?;
+    let exe = process
+        .exe
+        .clone()
+        .ok_or_else(|| format!("The executable of process {pid} is not known"))?;
+    reveal_in_file_manager(Path::new(&exe))?;
+    Ok(exe)
+}
+
+/// The parent chain of `pid`, outermost first and ending with `pid` itself.
+#[tauri::command]
+fn get_process_ancestry(pid: i32, sampler: State<'_, Sampler>) -> Result<Vec<ProcessInfo>, String> {
+    process_ancestry(&sampler.latest().processes, pid)
+}
+
+/// Off the main thread: hashing the executable can take a moment the first
+/// time a large binary is inspected.
+#[tauri::command(async)]
+fn get_process_details(pid: i32, sampler: State<'_, Sampler>) -> Result<ProcessDetails, String> {
+    if pid <= 0 {
+        return Err("PID must be a positive integer".to_string());
+    }
+
+    // cwd/root aren't part of the sample, so look this one process up directly.
+    let mut system = System::new();
+    let target_pid = Pid::from_u32(pid as u32);
+    if !system.refresh_process(target_pid) {
+        return Err(format!("Process {pid} was not found"));
+    }
+    let process = system
+        .process(target_pid)
+        .ok_or_else(|| format!("Process {pid} was not found"))?;
+
+    let sample = sampler.latest();
+    let child_map = build_child_map(&sample.processes);
+    let child_pids = child_map
+        .get(&pid)
+        .map(|pids| pids.iter().copied().collect::<HashSet<_>>())
+        .unwrap_or_default();
+    let children = sample
+        .processes
+        .iter()
+        .filter(|candidate| child_pids.contains(&candidate.pid))
+        .cloned()
+        .collect();
+
+    // Prefer the sampled entry for its CPU figure; a process younger than
+    // one sample interval falls back to the direct lookup.
+    let info = sample
+        .processes
+        .iter()
+        .find(|candidate| candidate.pid == pid)
+        .cloned()
+        .unwrap_or_else(|| process_to_info(&system, target_pid, process));
+
+    let reload = known_daemon(&info.name);
+    let integrity = info.exe.as_deref().map(executable_integrity);
+    Ok(ProcessDetails {
+        process: info,
+        open_file_handles: count_open_file_handles(pid),
+        cwd: path_to_string(process.cwd()).or_else(|| process_cwd(pid)),
+        root: path_to_string(process.root()).or_else(|| process_root(pid)),
+        open_sockets: collect_process_sockets(pid),
+        memory: collect_memory_breakdown(pid),
+        counters: collect_process_counters(pid),
+        limits: collect_resource_limits(pid),
+        waits: collect_thread_waits(pid),
+        io_priority: get_io_priority(pid),
+        cgroup: collect_cgroup(pid),
+        integrity,
+        children,
+        reload,
+        service: service_unit(pid),
+        supervisor: find_supervisor(&sample.processes, pid),
+    })
+}
+
+#[tauri::command]
+fn list_loaded_modules(pid: i32) -> Result<Vec<LoadedModule>, String> {
+    if pid <= 0 {
+        return Err("PID must be a positive integer".to_string());
+    }
+
+    collect_loaded_modules(pid)
+}
+
+/// Every open file descriptor of `pid`, read natively (no lsof) on Linux
+/// and macOS.
+#[tauri::command]
+fn list_open_files(pid: i32) -> Result<Vec<OpenFile>, String> {
+    if pid <= 0 {
+        return Err("PID must be a positive integer".to_string());
+    }
+
+    collect_open_files(pid)
+}
+
+const DEFAULT_PROFILE_MS: u64 = 2_000;
+const MAX_PROFILE_MS: u64 = 30_000;
+
+/// Samples `pid`'s stacks for `duration_ms` and returns the hottest frames.
+#[tauri::command(async)]
+fn sample_process(pid: i32, duration_ms: Option<u64>) -> Result<ProcessProfile, String> {
+    if pid <= 0 {
+        return Err("PID must be a positive integer".to_string());
+    }
+    let duration_ms = duration_ms.unwrap_or(DEFAULT_PROFILE_MS);
+    if !(1..=MAX_PROFILE_MS).contains(&duration_ms) {
+        return Err(format!(
+            "duration_ms must be between 1 and {MAX_PROFILE_MS}"
+        ));
+    }
+
+    // Same name as the command, hence the full path.
+    pswtf_core::profile::sample_process(pid, Duration::from_millis(duration_ms))
+}
+
+/// Every region of `pid`'s address space with its resident size, to tell
+/// anonymous growth from mapped files when memory balloons. Async because
+/// smaps takes a while to read for big processes and vmmap is slow.
+#[tauri::command(async)]
+fn get_memory_maps(pid: i32) -> Result<Vec<MemoryRegion>, String> {
+    if pid <= 0 {
+        return Err("PID must be a positive integer".to_string());
+    }
+
+    collect_memory_maps(pid)
+}
+
+/// Like `list_open_files` with each fd's flags, offset and inotify/epoll
+/// watch count; Linux only.
+#[tauri::command]
+fn list_fds(pid: i32) -> Result<Vec<FileDescriptor>, String> {
+    if pid <= 0 {
+        return Err("PID must be a positive integer".to_string());
+    }
+
+    collect_fd_table(pid)
+}
+
+/// Runs off the main thread because the Linux path sleeps between samples.
+#[tauri::command(async)]
+fn list_threads(pid: i32) -> Result<Vec<ThreadInfo>, String> {
+    if pid <= 0 {
+        return Err("PID must be a positive integer".to_string());
+    }
+
+    collect_threads(pid)
+}
+
+/// Listening ports, optionally narrowed server-side, e.g. TCP listeners with
+/// `port_range: [3000, 9000]`.
+#[tauri::command]
+fn list_open_ports(
+    protocol: Option<String>,
+    port_range: Option<(u16, u16)>,
+    pid: Option<i32>,
+    state: Option<String>,
+) -> Result<Vec<PortInfo>, String> {
+    if port_range.is_some_and(|(start, end)| start > end) {
+        return Err("Port range start must not be greater than its end".to_string());
+    }
+    if pid.is_some_and(|pid| pid <= 0) {
+        return Err("PID must be a positive integer".to_string());
+    }
+
+    let filter = PortFilter {
+        protocol,
+        port_range,
+        pid,
+        state,
+    };
+    let mut ports = collect_ports()?;
+    annotate_docker_ports(&mut ports);
+    annotate_port_forwards(&mut ports);
+    // Merged last: the annotations look PIDs up in the local process table.
+    merge_wsl_ports(&mut ports);
+    ports.retain(|port| filter.matches(port));
+    Ok(ports)
+}
+
+/// Connects to `port` on loopback to see what is actually answering.
+#[tauri::command(async)]
+fn probe_port(port: u16) -> PortProbe {
+    pswtf_core::probe::probe_port(port)
+}
+
+/// Unix domain sockets (docker.sock, postgres, php-fpm, ...) with their
+/// owners and peers, optionally only those `pid` holds or talks to.
+#[tauri::command(async)]
+fn list_unix_sockets(pid: Option<i32>) -> Result<Vec<UnixSocketInfo>, String> {
+    let mut sockets = collect_unix_sockets()?;
+    if let Some(pid) = pid {
+        sockets.retain(|entry| {
+            entry.pid == Some(pid) || entry.peers.iter().any(|peer| peer.pid == pid)
+        });
+    }
+    Ok(sockets)
+}
+
+/// How long `list_connections` waits on uncached reverse-DNS lookups.
+const DNS_LOOKUP_TIMEOUT: Duration = Duration::from_millis(750);
+
+/// Established connections with their remote end, optionally only those
+/// held by `pid`. `resolve_hosts` adds reverse-DNS names where a lookup
+/// finishes within `DNS_LOOKUP_TIMEOUT`.
+#[tauri::command(async)]
+fn list_connections(
+    pid: Option<i32>,
+    resolve_hosts: Option<bool>,
+) -> Result<Vec<ConnectionInfo>, String> {
+    let mut connections = collect_connections()?;
+    if let Some(pid) = pid {
+        connections.retain(|entry| entry.pid == Some(pid));
+    }
+    if resolve_hosts.unwrap_or(false) {
+        resolve_remote_hosts(&mut connections, DNS_LOOKUP_TIMEOUT);
+    }
+    Ok(connections)
+}
+
+/// Starts watching `port` (TCP by default) and returns whether it's bound
+/// right now. Later transitions arrive as `port-bound` / `port-freed` events.
+#[tauri::command(async)]
+fn watch_port(
+    port: u16,
+    protocol: Option<String>,
+    sampler: State<'_, Sampler>,
+    watches: State<'_, PortWatchState>,
+) -> Result<PortBinding, String> {
+    let port = checked_watch_port(port)?;
+    let protocol = checked_watch_protocol(protocol)?;
+
+    let ports = collect_ports()?;
+    let holder = find_port_holder(&ports, port, &protocol);
+    let binding = port_binding(&sampler.latest(), port, &protocol, holder);
+
+    watches
+        .0
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .insert((port, protocol), holder.cloned());
+
+    Ok(binding)
+}
+
+fn checked_watch_port(port: u16) -> Result<u16, String> {
+    if port == 0 {
+        return Err("Port must be between 1 and 65535".to_string());
+    }
+    Ok(port)
+}
+
+fn checked_watch_protocol(protocol: Option<String>) -> Result<String, String> {
+    let protocol = protocol
+        .map(|value| value.trim().to_ascii_uppercase())
+        .unwrap_or_else(|| "TCP".to_string());
+    if protocol != "TCP" && protocol != "UDP" {
+        return Err(format!("Unsupported protocol: {protocol}"));
+    }
+    Ok(protocol)
+}
+
+/// Longest `verify_ms` a kill command will block for.
+const MAX_KILL_VERIFY_MS: u64 = 10_000;
+
+fn kill_verify_timeout(verify_ms: Option<u64>) -> Result<Option<Duration>, String> {
+    match verify_ms {
+        Some(ms) if ms > MAX_KILL_VERIFY_MS => {
+            Err(format!("verify_ms must be at most {MAX_KILL_VERIFY_MS}"))
+        }
+        Some(ms) => Ok(Some(Duration::from_millis(ms))),
+        None => Ok(None),
+    }
+}
+
+/// Applies `verify_ms` to a finished kill so the audit log and the caller
+/// both see which targets survived.
+fn verify_kill(result: &mut Result<KillReport, String>, timeout: Option<Duration>) {
+    if let (Ok(report), Some(timeout)) = (result, timeout) {
+        verify_terminated(report, timeout);
+    }
+}
+
+#[tauri::command(async)]
+#[allow(clippy::too_many_arguments)]
+fn kill_port_owners(
+    port: u16,
+    include_children: Option<bool>,
+    force: Option<bool>,
+    allow_elevation: Option<bool>,
+    verify_ms: Option<u64>,
+    sampler: State<'_, Sampler>,
+    app: AppHandle,
+) -> Result<KillReport, String> {
+    let verify = kill_verify_timeout(verify_ms)?;
+    let include_children = include_children.unwrap_or(true);
+    let latest = sampler.latest();
+    let mut result = collect_ports().and_then(|ports| {
+        kill_port(
+            &latest.processes,
+            &ports,
+            port,
+            include_children,
+            force,
+            allow_elevation.unwrap_or(false),
+        )
+    });
+    verify_kill(&mut result, verify);
+    audit_kill(
+        &app,
+        "ui",
+        KillTarget::Port {
+            port,
+            include_children,
+        },
+        force,
+        &latest.processes,
+        &result,
+    );
+    result
+}
+
+#[tauri::command]
+fn take_pending_deep_link(state: State<'_, DeepLinkState>) -> Option<DeepLink> {
+    state
+        .0
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .take()
+}
+
+/// With `verify_ms`, waits up to that long for the targets to exit and
+/// reports survivors in `stillRunning`; async so the wait never blocks the UI.
+#[tauri::command(async)]
+#[allow(clippy::too_many_arguments)]
+fn kill_process(
+    pid: i32,
+    include_children: Option<bool>,
+    force: Option<bool>,
+    allow_elevation: Option<bool>,
+    verify_ms: Option<u64>,
+    sampler: State<'_, Sampler>,
+    app: AppHandle,
+) -> Result<KillReport, String> {
+    let verify = kill_verify_timeout(verify_ms)?;
+    let include_children = include_children.unwrap_or(true);
+    let latest = sampler.latest();
+    let mut result = kill_process_tree(
+        &latest.processes,
+        pid,
+        include_children,
+        force,
+        allow_elevation.unwrap_or(false),
+    );
+    verify_kill(&mut result, verify);
+    audit_kill(
+        &app,
+        "ui",
+        KillTarget::Pid {
+            pid,
+            include_children,
+        },
+        force,
+        &latest.processes,
+        &result,
+    );
+    result
+}
+
+#[tauri::command]
+fn kill_process_group(
+    pgid: i32,
+    force: Option<bool>,
+    sampler: State<'_, Sampler>,
+    app: AppHandle,
+) -> Result<KillReport, String> {
+    let latest = sampler.latest();
+    // Same name as the command, hence the full path.
+    let result = pswtf_core::kill::kill_process_group(&latest.processes, pgid, force);
+    audit_kill(
+        &app,
+        "ui",
+        KillTarget::ProcessGroup { pgid },
+        force,
+        &latest.processes,
+        &result,
+    );
+    result
+}
+
+/// Kills everything running in a tmux/screen session (or one tmux window),
+/// leaving the pane shells open.
+#[tauri::command(async)]
+fn kill_multiplexer_session(
+    session: String,
+    window_index: Option<u32>,
+    force: Option<bool>,
+    allow_elevation: Option<bool>,
+    verify_ms: Option<u64>,
+    sampler: State<'_, Sampler>,
+    app: AppHandle,
+) -> Result<KillReport, String> {
+    let verify = kill_verify_timeout(verify_ms)?;
+    let latest = sampler.latest();
+    let mut result = pswtf_core::kill::kill_multiplexer_session(
+        &latest.processes,
+        &session,
+        window_index,
+        force,
+        allow_elevation.unwrap_or(false),
+    );
+    verify_kill(&mut result, verify);
+    audit_kill(
+        &app,
+        "ui",
+        KillTarget::Multiplexer {
+            session,
+            window_index,
+        },
+        force,
+        &latest.processes,
+        &result,
+    );
+    result
+}
+
+/// Kills everything running on a terminal, its shell included.
+#[tauri::command(async)]
+fn kill_by_tty(
+    tty: String,
+    force: Option<bool>,
+    allow_elevation: Option<bool>,
+    verify_ms: Option<u64>,
+    sampler: State<'_, Sampler>,
+    app: AppHandle,
+) -> Result<KillReport, String> {
+    let verify = kill_verify_timeout(verify_ms)?;
+    let latest = sampler.latest();
+    let mut result = pswtf_core::kill::kill_by_tty(
+        &latest.processes,
+        &tty,
+        force,
+        allow_elevation.unwrap_or(false),
+    );
+    verify_kill(&mut result, verify);
+    audit_kill(
+        &app,
+        "ui",
+        KillTarget::Tty { tty },
+        force,
+        &latest.processes,
+        &result,
+    );
+    result
+}
+
+/// Stops the container `id` belongs to with `docker stop` (or `podman stop`),
+/// which is what you want instead of killing a containerd-shim child.
+#[tauri::command(async)]
+fn stop_container(id: String, sampler: State<'_, Sampler>) -> Result<(), String> {
+    let runtime = sampler
+        .latest()
+        .processes
+        .iter()
+        .filter_map(|process| process.container.as_ref())
+        .find(|container| container.id.as_deref() == Some(id.as_str()))
+        .map_or("docker", |container| container.runtime);
+
+    pswtf_core::container::stop_container(runtime, &id)
+}
+
+#[tauri::command(async)]
+fn stop_unit(unit: String, user: Option<bool>) -> Result<(), String> {
+    pswtf_core::service::stop_unit(&unit, user.unwrap_or(false))
+}
+
+#[tauri::command(async)]
+fn restart_unit(unit: String, user: Option<bool>) -> Result<(), String> {
+    pswtf_core::service::restart_unit(&unit, user.unwrap_or(false))
+}
+
+/// Stops a pm2/supervisord/forever child through its manager, which
+/// would otherwise respawn it after a kill.
+#[tauri::command(async)]
+fn stop_supervised(pid: i32, sampler: State<'_, Sampler>) -> Result<SupervisedProcess, String> {
+    pswtf_core::supervisor::stop_supervised(&sampler.latest().processes, pid)
+}
+
+/// Renices `pid`, so a runaway build can be slowed down instead of killed.
+#[tauri::command]
+fn set_priority(pid: i32, nice: i32) -> Result<(), String> {
+    pswtf_core::priority::set_priority(pid, nice)
+}
+
+/// `ionice` for a process, e.g. the idle class for a backup agent that is
+/// thrashing the disk.
+#[tauri::command]
+fn set_io_priority(pid: i32, class: IoPriorityClass, level: Option<u8>) -> Result<(), String> {
+    pswtf_core::priority::set_io_priority(pid, class, level)
+}
+
+#[tauri::command]
+fn get_affinity(pid: i32) -> Result<Vec<usize>, String> {
+    pswtf_core::priority::get_affinity(pid)
+}
+
+/// Pins `pid` to `cpus`, e.g. to keep a background build off the cores
+/// the foreground work is using.
+#[tauri::command]
+fn set_affinity(pid: i32, cpus: Vec<usize>) -> Result<(), String> {
+    pswtf_core::priority::set_affinity(pid, &cpus)
+}
+
+#[tauri::command(async)]
+fn bootout_job(label: String, user: Option<bool>) -> Result<(), String> {
+    pswtf_core::service::bootout_job(&label, user.unwrap_or(true))
+}
+
+#[tauri::command(async)]
+fn disable_job(label: String, user: Option<bool>) -> Result<(), String> {
+    pswtf_core::service::disable_job(&label, user.unwrap_or(true))
+}
+
+/// Sends SIGHUP, the usual "re-read your config" signal, instead of killing.
+#[tauri::command]
+fn reload_process(
+    pid: i32,
+    sampler: State<'_, Sampler>,
+    app: AppHandle,
+) -> Result<ReloadReport, String> {
+    let latest = sampler.latest();
+    let result = pswtf_core::kill::reload_process(&latest.processes, pid);
+    audit_signal(
+        &app,
+        "ui",
+        KillTarget::Pid {
+            pid,
+            include_children: false,
+        },
+        Signal::SIGHUP,
+        &latest.processes,
+        &result
+            .as_ref()
+            .map(|reload| reload.report.clone())
+            .map_err(Clone::clone),
+    );
+    result
+}
+
+#[tauri::command]
+fn list_zombies(sampler: State<'_, Sampler>) -> Vec<ZombieGroup> {
+    pswtf_core::kill::list_zombies(&sampler.latest().processes)
+}
+
+/// Nudges a parent into reaping its zombies with SIGCHLD. Killing the
+/// zombies themselves is pointless; they've already exited.
+#[tauri::command]
+fn signal_zombie_parent(
+    pid: i32,
+    sampler: State<'_, Sampler>,
+    app: AppHandle,
+) -> Result<KillReport, String> {
+    let latest = sampler.latest();
+    let result = pswtf_core::kill::signal_zombie_parent(&latest.processes, pid);
+    #[cfg(unix)]
+    audit_signal(
+        &app,
+        "ui",
+        KillTarget::Pid {
+            pid,
+            include_children: false,
+        },
+        Signal::SIGCHLD,
+        &latest.processes,
+        &result,
+    );
+    #[cfg(not(unix))]
+    let _ = app;
+    result
+}
+
+#[tauri::command]
+fn suspend_process(
+    pid: i32,
+    sampler: State<'_, Sampler>,
+    app: AppHandle,
+) -> Result<KillReport, String> {
+    let latest = sampler.latest();
+    let result = pswtf_core::kill::suspend_process(&latest.processes, pid);
+    #[cfg(unix)]
+    audit_signal(
+        &app,
+        "ui",
+        KillTarget::Pid {
+            pid,
+            include_children: false,
+        },
+        Signal::SIGSTOP,
+        &latest.processes,
+        &result,
+    );
+    #[cfg(not(unix))]
+    let _ = app;
+    result
+}
+
+#[tauri::command]
+fn resume_process(
+    pid: i32,
+    sampler: State<'_, Sampler>,
+    app: AppHandle,
+) -> Result<KillReport, String> {
+    let latest = sampler.latest();
+    let result = pswtf_core::kill::resume_process(&latest.processes, pid);
+    #[cfg(unix)]
+    audit_signal(
+        &app,
+        "ui",
+        KillTarget::Pid {
+            pid,
+            include_children: false,
+        },
+        Signal::SIGCONT,
+        &latest.processes,
+        &result,
+    );
+    #[cfg(not(unix))]
+    let _ = app;
+    result
+}
+
+#[tauri::command]
+fn schedule_kill(
+    pid: i32,
+    delay_seconds: u64,
+    include_children: Option<bool>,
+    force: Option<bool>,
+    sampler: State<'_, Sampler>,
+    scheduler: State<'_, KillScheduler>,
+) -> Result<ScheduledKill, String> {
+    if pid <= 0 {
+        return Err("PID must be a positive integer".to_string());
+    }
+    if delay_seconds == 0 || delay_seconds > MAX_KILL_DELAY_SECONDS {
+        return Err(format!(
+            "Delay must be between 1 and {MAX_KILL_DELAY_SECONDS} seconds"
+        ));
+    }
+
+    let latest = sampler.latest();
+    let process = latest
+        .processes
+        .iter()
+        .find(|process| process.pid == pid)
+        .ok_or_else(|| format!("Process {pid} was not found"))?;
+
+    Ok(scheduler.schedule(
+        process,
+        Duration::from_secs(delay_seconds),
+        include_children.unwrap_or(true),
+        force.unwrap_or(false),
+    ))
+}
+
+#[tauri::command]
+fn cancel_scheduled_kill(id: u64, scheduler: State<'_, KillScheduler>) -> Result<(), String> {
+    scheduler.cancel(id)
+}
+
+#[tauri::command]
+fn list_scheduled_kills(scheduler: State<'_, KillScheduler>) -> Vec<ScheduledKill> {
+    scheduler.pending()
+}
+
+/// Starts `cmd` directly, without a shell, as a child of pswtf. `env` is
+/// added to pswtf's own environment rather than replacing it.
+#[tauri::command]
+fn spawn_process(
+    cmd: String,
+    args: Option<Vec<String>>,
+    cwd: Option<String>,
+    env: Option<HashMap<String, String>>,
+    app: AppHandle,
+    launched: State<'_, LaunchedProcesses>,
+) -> Result<LaunchedProcess, String> {
+    let cmd = cmd.trim().to_string();
+    if cmd.is_empty() {
+        return Err("Command must not be empty".to_string());
+    }
+    if let Some(cwd) = cwd.as_deref().filter(|cwd| !Path::new(cwd).is_dir()) {
+        return Err(format!("Working directory {cwd} does not exist"));
+    }
+
+    launched.spawn(
+        &app,
+        cmd,
+        args.unwrap_or_default(),
+        cwd,
+        env.unwrap_or_default(),
+    )
+}
+
+#[tauri::command]
+fn list_launched_processes(launched: State<'_, LaunchedProcesses>) -> Vec<LaunchedProcess> {
+    launched.list()
+}
+
+/// How long a `prepare_matching_kill` token can be redeemed for.
+const KILL_CONFIRMATION_TTL: Duration = Duration::from_secs(30);
+
+/// A previewed matching kill waiting for its token to come back.
+struct PendingMatchKill {
+    query: String,
+    options: MatchOptions,
+    /// Name of every previewed target by PID, so a PID reused by something
+    /// else since the preview counts as drift.
+    targets: HashMap<i32, String>,
+    expires_at_epoch_ms: u128,
+}
+
+/// Tokens handed out by `prepare_matching_kill`. Each can be redeemed once,
+/// so a double-clicked confirm button can't kill twice.
+#[derive(Default)]
+struct KillConfirmations(Mutex<HashMap<String, PendingMatchKill>>);
+
+impl KillConfirmations {
+    fn issue(&self, pending: PendingMatchKill) -> String {
+        let token = confirmation_token();
+        let now = epoch_millis();
+        let mut tokens = self
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        tokens.retain(|_, pending| pending.expires_at_epoch_ms > now);
+        tokens.insert(token.clone(), pending);
+        token
+    }
+
+    fn redeem(&self, token: &str) -> Result<PendingMatchKill, String> {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(token)
+            .filter(|pending| pending.expires_at_epoch_ms > epoch_millis())
+            .ok_or_else(|| {
+                "Confirmation token is unknown, expired or already used; preview again".to_string()
+            })
+    }
+}
+
+/// Not a secret: tokens only guard against stale or repeated calls, so the
+/// randomly keyed std hasher is enough.
+fn confirmation_token() -> String {
+    use std::hash::{BuildHasher, Hasher};
+
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_u128(epoch_millis());
+    format!("{:016x}", hasher.finish())
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct KillConfirmation {
+    token: String,
+    expires_at_epoch_ms: u128,
+    preview: MatchSet,
+}
+
+/// Kills what `pending` previewed, refusing if anything matches now that
+/// wasn't in the preview. Targets that exited in between are just skipped.
+fn kill_confirmed_match(
+    processes: &[ProcessInfo],
+    pending: &PendingMatchKill,
+    force: Option<bool>,
+    allow_elevation: bool,
+) -> Result<KillReport, String> {
+    let match_set = find_matching_processes(processes, &pending.query, &pending.options)?;
+    let drifted = match_set
+        .roots
+        .iter()
+        .chain(&match_set.descendants)
+        .filter(|process| pending.targets.get(&process.pid) != Some(&process.name))
+        .count();
+    if drifted > 0 {
+        return Err(format!(
+            "{drifted} process(es) match now that weren't in the preview; preview again"
+        ));
+    }
+    Ok(kill_match_set(match_set, force, allow_elevation))
+}
+
+/// The first half of a two-phase matching kill: the preview, plus a token
+/// `kill_matching_processes` takes instead of re-resolving the query.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+fn prepare_matching_kill(
+    query: String,
+    match_mode: Option<MatchMode>,
+    include_children: Option<bool>,
+    only_current_user: Option<bool>,
+    case_sensitive: Option<bool>,
+    exclude: Option<Vec<String>>,
+    include_self: Option<bool>,
+    sampler: State<'_, Sampler>,
+    confirmations: State<'_, KillConfirmations>,
+) -> Result<KillConfirmation, String> {
+    let options = MatchOptions {
+        match_mode: match_mode.unwrap_or_default(),
+        include_children: include_children.unwrap_or(true),
+        only_current_user: only_current_user.unwrap_or(true),
+        case_sensitive: case_sensitive.unwrap_or(false),
+        exclude: exclude.unwrap_or_default(),
+        include_self: include_self.unwrap_or(false),
+    };
+
+    let preview = find_matching_processes(&sampler.latest().processes, &query, &options)?;
+    let expires_at_epoch_ms = epoch_millis() + KILL_CONFIRMATION_TTL.as_millis();
+    let token = confirmations.issue(PendingMatchKill {
+        query,
+        options,
+        targets: preview
+            .roots
+            .iter()
+            .chain(&preview.descendants)
+            .map(|process| (process.pid, process.name.clone()))
+            .collect(),
+        expires_at_epoch_ms,
+    });
+
+    Ok(KillConfirmation {
+        token,
+        expires_at_epoch_ms,
+        preview,
+    })
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+fn preview_matching_processes(
+    query: String,
+    match_mode: Option<MatchMode>,
+    include_children: Option<bool>,
+    only_current_user: Option<bool>,
+    case_sensitive: Option<bool>,
+    exclude: Option<Vec<String>>,
+    include_self: Option<bool>,
+    sampler: State<'_, Sampler>,
+) -> Result<MatchSet, String> {
+    let options = MatchOptions {
+        match_mode: match_mode.unwrap_or_default(),
+        include_children: include_children.unwrap_or(true),
+        only_current_user: only_current_user.unwrap_or(true),
+        case_sensitive: case_sensitive.unwrap_or(false),
+        exclude: exclude.unwrap_or_default(),
+        include_self: include_self.unwrap_or(false),
+    };
+
+    find_matching_processes(&sampler.latest().processes, &query, &options)
+}
+
+#[tauri::command(async)]
+#[allow(clippy::too_many_arguments)]
+fn kill_matching_processes(
+    query: String,
+    match_mode: Option<MatchMode>,
+    include_children: Option<bool>,
+    force: Option<bool>,
+    only_current_user: Option<bool>,
+    case_sensitive: Option<bool>,
+    exclude: Option<Vec<String>>,
+    include_self: Option<bool>,
+    allow_elevation: Option<bool>,
+    verify_ms: Option<u64>,
+    confirmation_token: Option<String>,
+    sampler: State<'_, Sampler>,
+    confirmations: State<'_, KillConfirmations>,
+    app: AppHandle,
+) -> Result<KillReport, String> {
+    let verify = kill_verify_timeout(verify_ms)?;
+    let latest = sampler.latest();
+
+    // With a token the match options are the ones that were previewed; the
+    // rest of the arguments only pick how to kill.
+    let (options, mut result) = match confirmation_token {
+        Some(token) => {
+            let pending = confirmations.redeem(&token)?;
+            if pending.query != query {
+                return Err("Confirmation token was issued for a different query".to_string());
+            }
+            let result = kill_confirmed_match(
+                &latest.processes,
+                &pending,
+                force,
+                allow_elevation.unwrap_or(false),
+            );
+            (pending.options, result)
+        }
+        None => {
+            let options = MatchOptions {
+                match_mode: match_mode.unwrap_or_default(),
+                include_children: include_children.unwrap_or(true),
+                only_current_user: only_current_user.unwrap_or(true),
+                case_sensitive: case_sensitive.unwrap_or(false),
+                exclude: exclude.unwrap_or_default(),
+                include_self: include_self.unwrap_or(false),
+            };
+            let result = kill_matching(
+                &latest.processes,
+                &query,
+                &options,
+                force,
+                allow_elevation.unwrap_or(false),
+            );
+            (options, result)
+        }
+    };
+    verify_kill(&mut result, verify);
+    audit_kill(
+        &app,
+        "ui",
+        KillTarget::Query {
+            query,
+            match_mode: options.match_mode,
+            include_children: options.include_children,
+            case_sensitive: options.case_sensitive,
+            exclude: options.exclude,
+        },
+        force,
+        &latest.processes,
+        &result,
+    );
+    result
+}
+
+/// Writes the whole kill log to `path` as CSV or a JSON array, for sharing
+/// outside pswtf. Returns how many kills were exported.
+#[tauri::command(async)]
+fn export_kill_history(
+    path: String,
+    format: ExportFormat,
+    audit: State<'_, KillAudit>,
+) -> Result<usize, String> {
+    let path = path.trim();
+    if path.is_empty() {
+        return Err("Path must not be empty".to_string());
+    }
+
+    let entries = audit.history(usize::MAX)?;
+    let contents = match format {
+        ExportFormat::Csv => kill_history_csv(&entries).into_bytes(),
+        ExportFormat::Json => serde_json::to_vec_pretty(&entries)
+            .map_err(|error| format!("Failed to encode kill log: {error}"))?,
+    };
+    std::fs::write(path, contents).map_err(|error| format!("Failed to write {path}: {error}"))?;
+    Ok(entries.len())
+}
+
+/// Who held `port` over time, oldest first. Only watched ports are
+/// recorded, from the moment they were first watched.
+#[tauri::command]
+fn get_port_history(
+    port: u16,
+    protocol: Option<String>,
+    limit: Option<usize>,
+    history: State<'_, PortHistory>,
+) -> Result<Vec<PortOwnershipEntry>, String> {
+    let port = checked_watch_port(port)?;
+    let protocol = protocol
+        .map(|protocol| checked_watch_protocol(Some(protocol)))
+        .transpose()?;
+    history.history(
+        port,
+        protocol.as_deref(),
+        limit.unwrap_or(DEFAULT_PORT_HISTORY_LIMIT),
+    )
+}
+
+#[tauri::command]
+fn get_kill_history(
+    limit: Option<usize>,
+    audit: State<'_, KillAudit>,
+) -> Result<Vec<KillAuditEntry>, String> {
+    audit.history(limit.unwrap_or(DEFAULT_KILL_HISTORY_LIMIT))
+}
+
+fn main() {
+    // Forwards links to an already-running instance and exits when there is one.
+    tauri_plugin_deep_link::prepare("com.pswtf.app");
+
+    tauri::Builder::default()
+        .setup(|app| {
+            // The sampler thread records into history, feeds the notifier and
+            // evaluates rules and memory watches, so all of those must be
+            // managed before it starts.
+            app.manage(ProtectedProcesses::load(app.path_resolver().app_data_dir()));
+            app.manage(PortNames::load(app.path_resolver().app_data_dir()));
+            app.manage(SettingsStore::load(app.path_resolver().app_data_dir()));
+            app.manage(KillAudit::new(app.path_resolver().app_data_dir()));
+            app.manage(PortHistory::load(app.path_resolver().app_data_dir()));
+            app.manage(HistoryRecorder::load(app.path_resolver().app_data_dir()));
+            app.manage(Notifier::load(app.path_resolver().app_data_dir()));
+            app.manage(RuleEngine::load(app.path_resolver().app_data_dir()));
+            app.manage(MemoryWatchdog::default());
+            app.manage(PinnedProcesses::load(app.path_resolver().app_data_dir()));
+            app.manage(Sampler::start(app.handle()));
+            let settings = app.state::<SettingsStore>().stored();
+            app.state::<Sampler>()
+                .update(|status| status.interval_ms = settings.refresh_interval_ms);
+            sync_port_watches(&app.state::<PortWatchState>(), &[], &settings.watched_ports);
+            app.manage(KillScheduler::start(app.handle()));
+            app.manage(MetricsExporter(HttpService::load(
+                "pswtf-metrics",
+                METRICS_SETTINGS_FILE,
+                DEFAULT_METRICS_PORT,
+                handle_metrics_request,
+                app.path_resolver().app_data_dir(),
+                app.handle(),
+            )));
+            app.manage(LocalApi(HttpService::load(
+                "pswtf-local-api",
+                LOCAL_API_SETTINGS_FILE,
+                DEFAULT_LOCAL_API_PORT,
+                handle_local_api_request,
+                app.path_resolver().app_data_dir(),
+                app.handle(),
+            )));
+            start_port_watcher(app.handle());
+            start_tray_refresher(app.handle());
+
+            let handle = app.handle();
+            let _ = tauri_plugin_deep_link::register(DEEP_LINK_SCHEME, move |url| {
+                dispatch_deep_link(&handle, &url)
+            });
+            // Windows and Linux hand the launching URL over as an argument.
+            if let Some(url) = std::env::args()
+                .skip(1)
+                .find(|arg| arg.starts_with(DEEP_LINK_SCHEME))
+            {
+                dispatch_deep_link(&app.handle(), &url);
+            }
+            Ok(())
+        })
+        .system_tray(SystemTray::new())
+        .on_system_tray_event(handle_tray_event)
+        // Closing the window keeps pswtf running in the tray; Quit lives there.
+        .on_window_event(|event| {
+            if let WindowEvent::CloseRequested { api, .. } = event.event() {
+                let _ = event.window().hide();
+                api.prevent_close();
+            }
+        })
+        .manage(SnapshotDeltaState::default())
+        .manage(SavedSnapshots::default())
+        .manage(NetworkRateState::default())
+        .manage(DiskRateState::default())
+        .manage(PortWatchState::default())
+        .manage(KillConfirmations::default())
+        .manage(LaunchedProcesses::default())
+        .manage(DeepLinkState::default())
         .invoke_handler(tauri::generate_handler![
             get_process_snapshot,
+            get_snapshot_delta,
+            save_snapshot,
+            diff_snapshots,
+            get_monitoring_status,
+            set_refresh_interval,
+            get_settings,
+            set_settings,
+            pause_monitoring,
+            resume_monitoring,
+            watch_pid,
+            get_history_settings,
+            set_history_settings,
+            get_process_history,
+            get_notification_settings,
+            set_notification_settings,
+            watch_memory,
+            unwatch_memory,
+            list_memory_watches,
+            pin_process,
+            unpin_process,
+            list_pinned_processes,
+            schedule_kill,
+            cancel_scheduled_kill,
+            list_scheduled_kills,
+            spawn_process,
+            list_launched_processes,
+            get_protected_processes,
+            set_protected_processes,
+            get_port_names,
+            set_port_names,
+            add_rule,
+            list_rules,
+            remove_rule,
+            list_rule_activity,
+            get_metrics_exporter,
+            set_metrics_exporter,
+            get_local_api,
+            set_local_api,
+            get_system_overview,
+            get_energy_impact,
+            get_cpu_cores,
+            get_network_interfaces,
+            get_disks,
+            get_sensors,
             get_process_details,
+            get_process_ancestry,
+            get_top_processes,
+            open_terminal_at,
+            reveal_executable,
+            find_file_users,
+            find_mount_blockers,
+            suspend_process,
+            resume_process,
+            get_shell_command,
+            query_processes,
+            list_loaded_modules,
+            list_open_files,
+            list_fds,
+            get_memory_maps,
+            sample_process,
+            list_threads,
             list_open_ports,
+            list_connections,
+            list_unix_sockets,
+            probe_port,
+            watch_port,
+            kill_port_owners,
+            take_pending_deep_link,
             kill_process,
-            kill_matching_processes
+            kill_process_group,
+            kill_multiplexer_session,
+            kill_by_tty,
+            reload_process,
+            list_zombies,
+            signal_zombie_parent,
+            set_priority,
+            set_io_priority,
+            get_affinity,
+            set_affinity,
+            stop_container,
+            stop_unit,
+            restart_unit,
+            bootout_job,
+            disable_job,
+            stop_supervised,
+            preview_matching_processes,
+            prepare_matching_kill,
+            kill_matching_processes,
+            get_kill_history,
+            export_kill_history,
+            get_port_history
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
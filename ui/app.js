@@ -3,18 +3,34 @@ const invoke =
   tauriGlobal?.core?.invoke ??
   tauriGlobal?.tauri?.invoke ??
   tauriGlobal?.invoke;
+const listen = tauriGlobal?.event?.listen;
 
 const state = {
   processes: [],
   ports: [],
+  connections: [],
+  zombies: [],
+  pins: [],
+  fileUsers: null,
+  markedSnapshotId: null,
+  fileUsersMode: null,
+  activeTab: "details",
   selectedPid: null,
+  selectedDetails: null,
   processSearch: "",
   portSearch: "",
+  connectionSearch: "",
+  resolveHosts: false,
   sortBy: "cpu",
   treeMode: true,
+  groupByApp: false,
+  groups: [],
+  expandedGroups: new Set(),
   autoRefresh: true,
   refreshTimer: null,
   isRefreshing: false,
+  startedAt: new Map(),
+  visibleIntervalMs: null,
 };
 
 const el = {};
@@ -23,6 +39,10 @@ const PANE_MIN_LEFT = 520;
 const PANE_MIN_RIGHT = 320;
 const PANE_DIVIDER_WIDTH = 12;
 const PANE_MOBILE_BREAKPOINT = 1100;
+const NEW_PROCESS_HIGHLIGHT_MS = 4000;
+const HIDDEN_REFRESH_MS = 5000;
+// How long kill commands wait for targets to exit before reporting them as still running.
+const KILL_VERIFY_MS = 1500;
 
 function escapeHtml(value) {
   return String(value ?? "")
@@ -50,6 +70,14 @@ function formatBytes(bytes) {
   return `${value.toFixed(value >= 10 || unit === 0 ? 0 : 1)} ${units[unit]}`;
 }
 
+function formatIoRate(bytesPerSec) {
+  return bytesPerSec == null ? "" : ` (${formatBytes(bytesPerSec)}/s)`;
+}
+
+function formatOptionalBytes(bytes) {
+  return bytes === null || bytes === undefined ? "Unavailable" : formatBytes(bytes);
+}
+
 function formatCpu(value) {
   const n = Number(value ?? 0);
   if (!Number.isFinite(n)) {
@@ -223,7 +251,9 @@ function processMatchesQuery(process, query) {
     process.name.toLowerCase().includes(q) ||
     process.cmd.toLowerCase().includes(q) ||
     String(process.pid).includes(q) ||
-    (process.status || "").toLowerCase().includes(q)
+    (process.status || "").toLowerCase().includes(q) ||
+    (process.tty || "").toLowerCase().includes(q) ||
+    (process.multiplexer?.session || "").toLowerCase().includes(q)
   );
 }
 
@@ -241,7 +271,58 @@ function compareProcesses(a, b) {
   }
 }
 
+// Group totals are re-added from the visible members so a search narrows
+// them too. Expanded groups list their members flat underneath.
+function buildGroupRows(processes) {
+  const byPid = new Map(processes.map((process) => [process.pid, process]));
+  const groups = state.groups
+    .map((group) => {
+      const members = group.pids.map((pid) => byPid.get(pid)).filter(Boolean).sort(compareProcesses);
+      return {
+        ...group,
+        members,
+        cpuPercent: members.reduce((sum, process) => sum + (process.cpuPercent || 0), 0),
+        memoryBytes: members.reduce((sum, process) => sum + (process.memoryBytes || 0), 0),
+        pid: group.pids[0],
+      };
+    })
+    .filter((group) => group.members.length > 0)
+    .sort(compareProcesses);
+
+  const rows = [];
+  for (const group of groups) {
+    rows.push({ group });
+    if (state.expandedGroups.has(group.key)) {
+      rows.push(...group.members.map((process) => ({ process, depth: 1 })));
+    }
+  }
+  return rows;
+}
+
+function renderGroupRow(group) {
+  const expanded = state.expandedGroups.has(group.key);
+  const title = group.bundleId || group.bundlePath || group.name;
+  return `
+    <tr data-group="${escapeHtml(group.key)}" class="group-row">
+      <td>
+        <div class="process-name" title="${escapeHtml(title)}">${expanded ? "▾" : "▸"} ${escapeHtml(group.name)} (${group.members.length})</div>
+      </td>
+      <td class="pid">-</td>
+      <td>${formatCpu(group.cpuPercent)}</td>
+      <td>${formatBytes(group.memoryBytes)}</td>
+      <td></td>
+      <td></td>
+      <td></td>
+      <td></td>
+    </tr>
+  `;
+}
+
 function buildRows(processes) {
+  if (state.groupByApp) {
+    return buildGroupRows(processes);
+  }
+
   const sorted = [...processes].sort(compareProcesses);
 
   if (!state.treeMode) {
@@ -303,15 +384,21 @@ function renderProcessTable() {
   }
 
   el.processRows.innerHTML = rows
-    .map(({ process, depth }) => {
+    .map(({ process, depth, group }) => {
+      if (group) {
+        return renderGroupRow(group);
+      }
+
       const selected = process.pid === state.selectedPid ? "selected" : "";
+      const startedAt = state.startedAt.get(process.pid);
+      const fresh = startedAt && Date.now() - startedAt < NEW_PROCESS_HIGHLIGHT_MS ? "fresh" : "";
       const padding = depth * 18;
-      const branch = state.treeMode && depth > 0 ? "↳ " : "";
+      const branch = (state.treeMode || state.groupByApp) && depth > 0 ? "↳ " : "";
 
       return `
-        <tr data-pid="${process.pid}" class="${selected}">
+        <tr data-pid="${process.pid}" class="${selected} ${fresh}">
           <td>
-            <div class="process-name" style="padding-left:${padding}px" title="${escapeHtml(process.cmd || process.name)}">${branch}${escapeHtml(process.name)}</div>
+            <div class="process-name" style="padding-left:${padding}px" title="${escapeHtml(process.cmd || process.name)}">${branch}${escapeHtml(process.name)}${process.exeOutdated ? ' <span class="badge-warn" title="Running an executable that was deleted or replaced after it started">outdated</span>' : ""}</div>
           </td>
           <td class="pid">${process.pid}</td>
           <td>${formatCpu(process.cpuPercent)}</td>
@@ -331,6 +418,270 @@ function renderProcessTable() {
     .join("");
 }
 
+function renderConnectionTable() {
+  const query = state.connectionSearch.trim().toLowerCase();
+  const visible = state.connections.filter((connection) => {
+    if (!query) {
+      return true;
+    }
+
+    return (
+      String(connection.pid ?? "").includes(query) ||
+      (connection.processName || "").toLowerCase().includes(query) ||
+      (connection.protocol || "").toLowerCase().includes(query) ||
+      `${connection.localAddress}:${connection.localPort}`.toLowerCase().includes(query) ||
+      `${connection.remoteAddress}:${connection.remotePort}`.toLowerCase().includes(query) ||
+      (connection.remoteHost || "").toLowerCase().includes(query)
+    );
+  });
+
+  if (visible.length === 0) {
+    el.connectionRows.innerHTML = `
+      <tr>
+        <td colspan="7">No connections match current filters.</td>
+      </tr>
+    `;
+    return;
+  }
+
+  el.connectionRows.innerHTML = visible
+    .map((connection) => {
+      const focusButton = connection.pid
+        ? `<button class="port-action" data-action="focus-pid" data-pid="${connection.pid}">Select</button>`
+        : "";
+
+      return `
+        <tr>
+          <td class="pid">${connection.pid ?? "-"}</td>
+          <td>${escapeHtml(connection.processName || "-")}</td>
+          <td>${escapeHtml(connection.protocol)}</td>
+          <td>${escapeHtml(`${connection.localAddress}:${connection.localPort}`)}</td>
+          <td title="${escapeHtml(connection.remoteAddress)}">${escapeHtml(`${connection.remoteHost || connection.remoteAddress}:${connection.remotePort}`)}</td>
+          <td>${escapeHtml(connection.state || "-")}</td>
+          <td>${focusButton}</td>
+        </tr>
+      `;
+    })
+    .join("");
+}
+
+// Connections churn constantly and cost an lsof run, so they're only
+// fetched while their tab is visible.
+async function refreshConnections() {
+  try {
+    const connections = await call("list_connections", { resolveHosts: state.resolveHosts });
+    state.connections = Array.isArray(connections) ? connections : [];
+    renderConnectionTable();
+  } catch (error) {
+    setStatus(`Loading connections failed: ${error.message || String(error)}`, "warn");
+  }
+}
+
+// Zombies can't be killed, so every action here targets the parent that
+// should have reaped them.
+function renderZombieTable() {
+  if (state.zombies.length === 0) {
+    el.zombieRows.innerHTML = `
+      <tr>
+        <td colspan="4">No zombie processes.</td>
+      </tr>
+    `;
+    return;
+  }
+
+  el.zombieRows.innerHTML = state.zombies
+    .map((group) => {
+      const zombies = group.zombies.map((zombie) => `${zombie.name} (${zombie.pid})`).join(", ");
+      const actions = group.orphaned
+        ? `<span title="The parent is gone or is init; the zombies should be reaped shortly">orphaned</span>`
+        : `
+          <button class="port-action" data-action="nudge-parent" data-pid="${group.parentPid}">Signal Parent</button>
+          <button class="port-action" data-action="kill-parent" data-pid="${group.parentPid}">Kill Parent</button>
+          <button class="port-action" data-action="focus-pid" data-pid="${group.parentPid}">Select</button>
+        `;
+
+      return `
+        <tr>
+          <td class="pid">${group.parentPid ?? "-"}</td>
+          <td>${escapeHtml(group.parent?.name || "-")}</td>
+          <td title="${escapeHtml(zombies)}">${group.zombies.length}: ${escapeHtml(zombies)}</td>
+          <td>${actions}</td>
+        </tr>
+      `;
+    })
+    .join("");
+}
+
+async function refreshZombies() {
+  try {
+    const zombies = await call("list_zombies");
+    state.zombies = Array.isArray(zombies) ? zombies : [];
+    renderZombieTable();
+  } catch (error) {
+    setStatus(`Loading zombies failed: ${error.message || String(error)}`, "warn");
+  }
+}
+
+async function runNudgeParent(pid) {
+  try {
+    const report = await call("signal_zombie_parent", { pid });
+    if (report.failed.length > 0) {
+      setStatus(`Signaling PID ${pid} failed: ${report.failed[0].error}`, "warn");
+    } else {
+      setStatus(`Sent SIGCHLD to PID ${pid}.`);
+    }
+  } catch (error) {
+    setStatus(`Signaling PID ${pid} failed: ${error.message || String(error)}`, "warn");
+  }
+  await refreshZombies();
+}
+
+const FILE_USE_LABELS = {
+  open: "open",
+  cwd: "working dir",
+  root: "root dir",
+  executable: "executable",
+  mapped: "mapped",
+};
+
+function renderFileUserTable() {
+  if (!state.fileUsers) {
+    el.fileUserRows.innerHTML = `
+      <tr>
+        <td colspan="4">Enter a path to see which processes are using it.</td>
+      </tr>
+    `;
+    return;
+  }
+  if (state.fileUsers.length === 0) {
+    el.fileUserRows.innerHTML = `
+      <tr>
+        <td colspan="4">Nothing pswtf can see is using it.</td>
+      </tr>
+    `;
+    return;
+  }
+
+  el.fileUserRows.innerHTML = state.fileUsers
+    .map((user) => {
+      const uses = user.uses
+        .map((entry) => {
+          const fd = entry.fd === null || entry.fd === undefined ? "" : ` fd ${entry.fd}`;
+          return `${FILE_USE_LABELS[entry.kind] || entry.kind}${fd}: ${entry.path}`;
+        })
+        .join("\n");
+      const first = user.uses[0];
+      const more = user.uses.length > 1 ? ` (+${user.uses.length - 1} more)` : "";
+
+      return `
+        <tr>
+          <td class="pid">${user.pid}</td>
+          <td>${escapeHtml(user.name || "-")}</td>
+          <td title="${escapeHtml(uses)}">${escapeHtml(FILE_USE_LABELS[first.kind] || first.kind)}: ${escapeHtml(first.path)}${more}</td>
+          <td>
+            <button class="port-action" data-action="kill-file-user" data-pid="${user.pid}">Kill</button>
+            <button class="port-action" data-action="suspend" data-pid="${user.pid}">Suspend</button>
+            <button class="port-action" data-action="resume" data-pid="${user.pid}">Resume</button>
+            <button class="port-action" data-action="focus-pid" data-pid="${user.pid}">Select</button>
+          </td>
+        </tr>
+      `;
+    })
+    .join("");
+}
+
+async function refreshFileUsers(mode) {
+  const path = el.filePath.value.trim();
+  if (!path) {
+    setStatus("Enter a path first.", "warn");
+    return;
+  }
+
+  state.fileUsersMode = mode;
+  try {
+    setStatus(`Looking for processes using ${path}...`);
+    const command = mode === "mount" ? "find_mount_blockers" : "find_file_users";
+    const users = await call(command, mode === "mount" ? { mountPoint: path } : { path });
+    state.fileUsers = Array.isArray(users) ? users : [];
+    renderFileUserTable();
+    setStatus(`${state.fileUsers.length} process(es) using ${path}.`);
+  } catch (error) {
+    setStatus(`Lookup failed: ${error.message || String(error)}`, "warn");
+  }
+}
+
+// SIGSTOP/SIGCONT, for a holder that shouldn't lose its state while the
+// volume is ejected.
+async function runSuspend(pid, resume) {
+  const verb = resume ? "Resume" : "Suspend";
+  try {
+    const result = await call(resume ? "resume_process" : "suspend_process", { pid });
+    setStatus(`${verb} request completed. ${killSummary(result)}`, killSucceeded(result) ? "info" : "warn");
+  } catch (error) {
+    setStatus(`${verb} failed: ${error.message || String(error)}`, "warn");
+  }
+}
+
+// Confirms what is really behind a listener, e.g. the dev server versus a
+// wedged process that accepts but never answers.
+async function runProbe(port) {
+  try {
+    setStatus(`Probing port ${port}...`);
+    const probe = await call("probe_port", { port });
+    if (!probe.connected) {
+      setStatus(`Port ${port}: ${probe.error || "not reachable"}`, "warn");
+      return;
+    }
+    const protocol = probe.protocol || "accepted but sent nothing";
+    const banner = probe.banner ? ` — ${probe.banner}` : "";
+    setStatus(`Port ${port} on ${probe.address}: ${protocol} (${probe.connectMs} ms)${banner}`, probe.protocol ? "info" : "warn");
+  } catch (error) {
+    setStatus(`Probe failed: ${error.message || String(error)}`, "warn");
+  }
+}
+
+const PORT_HISTORY_SHOWN = 5;
+
+// Only watched ports are recorded, so an empty history usually means the
+// port was never watched.
+async function runPortHistory(port) {
+  try {
+    const history = await call("get_port_history", { port, limit: PORT_HISTORY_SHOWN });
+    if (!history.length) {
+      setStatus(`No history for port ${port}; only watched ports are recorded.`, "warn");
+      return;
+    }
+    const owners = history.map((entry) => {
+      const at = new Date(Number(entry.atEpochMs)).toLocaleString();
+      if (!entry.bound) {
+        return `free since ${at}`;
+      }
+      const container = entry.container ? ` in ${entry.container}` : "";
+      const started = entry.startedAtEpochMs
+        ? `, started ${new Date(Number(entry.startedAtEpochMs)).toLocaleString()}`
+        : "";
+      return `${entry.processName || "unknown"} (PID ${entry.pid ?? "-"}${container}${started}) from ${at}`;
+    });
+    setStatus(`Port ${port}: ${owners.join(" → ")}`);
+  } catch (error) {
+    setStatus(`Loading port history failed: ${error.message || String(error)}`, "warn");
+  }
+}
+
+// Where a tunnel leads, e.g. "prod/svc/api:80" or "db.internal:5432 via bastion".
+function formatForward(forward) {
+  let target = forward.namespace ? `${forward.namespace}/${forward.target}` : forward.target;
+  if (forward.tool === "kubectl" && forward.remotePort) {
+    target += `:${forward.remotePort}`;
+  }
+  return forward.via ? `${target} via ${forward.via}` : target;
+}
+
+const PORT_ORIGIN_LABELS = {
+  windowsHost: "Windows host",
+  wsl: "WSL",
+};
+
 function renderPortTable() {
   const query = state.portSearch.trim().toLowerCase();
 
@@ -341,10 +692,13 @@ function renderPortTable() {
 
     return (
       String(port.port).includes(query) ||
+      (port.service || "").toLowerCase().includes(query) ||
       (port.protocol || "").toLowerCase().includes(query) ||
       (port.localAddress || "").toLowerCase().includes(query) ||
       String(port.pid ?? "").includes(query) ||
-      (port.processName || "").toLowerCase().includes(query)
+      (port.processName || "").toLowerCase().includes(query) ||
+      (port.docker?.containerName || "").toLowerCase().includes(query) ||
+      (port.forward?.target || "").toLowerCase().includes(query)
     );
   });
 
@@ -360,88 +714,347 @@ function renderPortTable() {
   el.portRows.innerHTML = visible
     .map((port) => {
       const pid = port.pid ?? "-";
-      const processName = port.processName || "-";
-      const focusButton = port.pid
+      let processName = port.processName || "-";
+      if (port.docker) {
+        processName = `${port.docker.containerName}:${port.docker.containerPort} (${port.docker.processCommand || port.processName || "docker"})`;
+      } else if (port.forward) {
+        processName = `${processName} → ${formatForward(port.forward)}`;
+      }
+      // PIDs from the other side of WSL aren't in this process table.
+      const local = !port.origin || port.origin === "local";
+      if (!local) {
+        processName = `${processName} (${PORT_ORIGIN_LABELS[port.origin] || port.origin})`;
+      }
+      const focusButton = port.pid && local
         ? `<button class="port-action" data-action="focus-pid" data-pid="${port.pid}">Select</button>`
         : "";
+      const probeButton = port.protocol === "TCP" && local
+        ? `<button class="port-action" data-action="probe-port" data-port="${port.port}">Probe</button>`
+        : "";
+      const historyButton = local
+        ? `<button class="port-action" data-action="port-history" data-port="${port.port}">History</button>`
+        : "";
 
       return `
         <tr>
-          <td class="pid">${port.port}</td>
+          <td class="pid">${port.port}${port.service ? ` <span class="port-service">${escapeHtml(port.service)}</span>` : ""}</td>
           <td>${escapeHtml(port.protocol || "")}</td>
-          <td>${escapeHtml(port.localAddress || "")}</td>
+          <td>${escapeHtml(port.localAddress || "")}${port.exposure === "all" ? ' <span class="badge-warn" title="Listening on all interfaces">all</span>' : ""}</td>
           <td>${escapeHtml(port.state || "-")}</td>
           <td class="pid">${pid}</td>
           <td>${escapeHtml(processName)}</td>
-          <td>${focusButton}</td>
+          <td>${focusButton}${probeButton}${historyButton}</td>
         </tr>
       `;
     })
     .join("");
 }
 
-function renderStats(snapshot, ports) {
+function renderStats(snapshot, ports, overview) {
   el.processCount.textContent = String(snapshot.processCount ?? state.processes.length);
   el.portCount.textContent = String(ports.length);
   el.lastRefresh.textContent = formatEpochMs(snapshot.collectedAtEpochMs);
+  el.systemCpu.textContent = `${formatCpu(overview.cpuPercent)}%`;
+  el.systemMemory.textContent = `${formatBytes(overview.usedMemoryBytes)} / ${formatBytes(overview.totalMemoryBytes)}`;
+}
+
+function formatSocket(socket) {
+  const local = `${socket.localAddress}:${socket.localPort}`;
+  const remote = socket.remoteAddress ? ` -> ${socket.remoteAddress}:${socket.remotePort ?? "*"}` : "";
+  const socketState = socket.state ? ` (${socket.state})` : "";
+  return `${socket.protocol} ${local}${remote}${socketState}`;
+}
+
+function renderSocketList(sockets) {
+  if (!Array.isArray(sockets)) {
+    return "Unavailable";
+  }
+  if (sockets.length === 0) {
+    return "None";
+  }
+  return sockets.map((socket) => escapeHtml(formatSocket(socket))).join("<br />");
+}
+
+function renderChildList(children) {
+  if (!Array.isArray(children) || children.length === 0) {
+    return "None";
+  }
+  return children.map((child) => `${escapeHtml(child.name)} (${child.pid})`).join(", ");
+}
+
+function renderSparkline(points, valueOf) {
+  if (!Array.isArray(points) || points.length < 2) {
+    return "Not enough history";
+  }
+
+  const width = 240;
+  const height = 32;
+  const values = points.map(valueOf);
+  const max = Math.max(...values, 1e-9);
+  const step = width / (values.length - 1);
+  const coords = values
+    .map((value, index) => `${(index * step).toFixed(1)},${(height - (value / max) * height).toFixed(1)}`)
+    .join(" ");
+
+  return `<svg class="sparkline" viewBox="0 0 ${width} ${height}" preserveAspectRatio="none"><polyline points="${coords}" /></svg>`;
+}
+
+function renderHistoryRows(history) {
+  if (!history) {
+    return "";
+  }
+
+  return `
+      <div class="details-item" style="grid-column: 1 / -1"><span class="key">CPU (last ${Math.round(history.windowSeconds / 60)} min)</span><span class="val">${renderSparkline(history.points, (point) => point.cpuPercent)}</span></div>
+      <div class="details-item" style="grid-column: 1 / -1"><span class="key">Memory (last ${Math.round(history.windowSeconds / 60)} min)</span><span class="val">${renderSparkline(history.points, (point) => point.memoryBytes)}</span></div>`;
+}
+
+// "zsh → tmux → node → esbuild", each ancestor clickable so the right
+// level of the tree can be picked before killing.
+function renderAncestry(ancestry) {
+  if (!ancestry || ancestry.length === 0) {
+    return "-";
+  }
+  return ancestry
+    .map((process, index) =>
+      index === ancestry.length - 1
+        ? `<strong>${escapeHtml(process.name)}</strong>`
+        : `<button class="port-action" data-action="select-process" data-pid="${process.pid}" title="PID ${process.pid}">${escapeHtml(process.name)}</button>`,
+    )
+    .join(" &rarr; ");
+}
+
+function formatCpuTime(process) {
+  if (process.userCpuSeconds == null || process.systemCpuSeconds == null) {
+    return "Unavailable";
+  }
+  const total = process.userCpuSeconds + process.systemCpuSeconds;
+  const systemShare = total > 0 ? ` (${Math.round((process.systemCpuSeconds / total) * 100)}% system)` : "";
+  return `${process.userCpuSeconds.toFixed(2)}s user / ${process.systemCpuSeconds.toFixed(2)}s system${systemShare}`;
+}
+
+// The limits people actually run into; the rest of /proc/<pid>/limits is
+// rarely the answer.
+const KEY_RESOURCE_LIMITS = [
+  ["Max open files", "Open files"],
+  ["Max address space", "Address space"],
+  ["Max core file size", "Core size"],
+  ["Max processes", "Processes"],
+];
+
+function formatLimitValue(value, unit) {
+  if (value == null) {
+    return "unlimited";
+  }
+  return unit === "bytes" ? formatBytes(value) : value.toLocaleString();
+}
+
+function renderResourceLimits(limits, openFileHandles) {
+  if (!limits) {
+    return "Unavailable";
+  }
+  const rows = KEY_RESOURCE_LIMITS
+    .map(([name, label]) => {
+      const limit = limits.find((entry) => entry.name === name);
+      if (!limit) {
+        return "";
+      }
+      const soft = formatLimitValue(limit.soft, limit.unit);
+      const hard = formatLimitValue(limit.hard, limit.unit);
+      // A server that stops accepting at 1024 connections is almost always this.
+      const nearLimit = name === "Max open files" && limit.soft != null && openFileHandles != null
+        && openFileHandles >= limit.soft * 0.8;
+      const warning = nearLimit ? ` <span class="badge-warn">${openFileHandles.toLocaleString()} open</span>` : "";
+      return `<div>${escapeHtml(label)}: ${escapeHtml(soft)} (hard ${escapeHtml(hard)})${warning}</div>`;
+    })
+    .join("");
+  return rows || "-";
+}
+
+function formatContextSwitches(counters) {
+  if (counters?.contextSwitches == null) {
+    return "Unavailable";
+  }
+  const total = counters.contextSwitches.toLocaleString();
+  if (counters.involuntaryContextSwitches == null) {
+    return total;
+  }
+  return `${total} (${counters.involuntaryContextSwitches.toLocaleString()} involuntary)`;
+}
+
+function formatPageFaults(counters) {
+  if (counters?.majorPageFaults == null) {
+    return "Unavailable";
+  }
+  return `${counters.majorPageFaults.toLocaleString()} major, ${(counters.minorPageFaults ?? 0).toLocaleString()} minor`;
 }
 
-function renderDetails(details) {
+function renderDetails(details, history, ancestry) {
+  state.selectedDetails = details;
   const process = details.process;
+  const memory = details.memory || {};
 
   el.detailsBody.innerHTML = `
     <div class="row-actions" style="margin-bottom:10px">
       <button data-action="kill" data-pid="${process.pid}">Kill</button>
       <button data-action="kill-tree" data-pid="${process.pid}">Kill Tree</button>
+      ${process.pgid ? `<button data-action="kill-group" data-pgid="${process.pgid}">Kill Group</button>` : ""}
+      <button data-action="reload" data-pid="${process.pid}">Reload (HUP)</button>
+      <button data-action="renice" data-pid="${process.pid}">Renice</button>
+      <button data-action="pin-cpus" data-pid="${process.pid}">Pin CPUs</button>
+      <button data-action="sample-stacks" data-pid="${process.pid}">Sample Stacks</button>
+      <button data-action="pin-process" data-name="${escapeHtml(process.name)}">Pin</button>
+      <button data-action="copy-shell" data-pid="${process.pid}">Copy as Shell</button>
+      ${process.exe ? `<button data-action="reveal-exe" data-pid="${process.pid}">Reveal Executable</button>` : ""}
+      ${details.cwd ? `<button data-action="open-terminal" data-pid="${process.pid}">Open Terminal</button>` : ""}
+      ${details.ioPriority && details.ioPriority.class !== "idle" ? `<button data-action="idle-io" data-pid="${process.pid}">Idle I/O</button>` : ""}
+      ${process.container?.id ? `<button data-action="stop-container" data-container="${escapeHtml(process.container.id)}">Stop Container</button>` : ""}
+      ${renderServiceActions(details.service)}
+      ${details.supervisor ? renderSupervisorActions(details.supervisor) : ""}
+      ${renderMultiplexerActions(process.multiplexer)}
     </div>
     <div class="details-grid">
       <div class="details-item"><span class="key">Name</span><span class="val">${escapeHtml(process.name)}</span></div>
       <div class="details-item"><span class="key">PID</span><span class="val">${process.pid}</span></div>
       <div class="details-item"><span class="key">Parent PID</span><span class="val">${process.parentPid ?? "-"}</span></div>
+      <div class="details-item"><span class="key">Process Group</span><span class="val">${process.pgid ?? "-"}</span></div>
+      <div class="details-item"><span class="key">Session</span><span class="val">${process.sid ?? "-"}</span></div>
+      <div class="details-item"><span class="key">TTY</span><span class="val">${escapeHtml(process.tty || "-")}${process.tty ? ` <button data-action="kill-tty" data-tty="${escapeHtml(process.tty)}">Kill Terminal</button>` : ""}</span></div>
+      <div class="details-item"><span class="key">Multiplexer</span><span class="val">${formatMultiplexer(process.multiplexer)}</span></div>
+      <div class="details-item"><span class="key">Container</span><span class="val">${formatContainer(process.container)}</span></div>
+      <div class="details-item" style="grid-column: 1 / -1"><span class="key">Cgroup</span><span class="val">${formatCgroup(details.cgroup)}</span></div>
+      <div class="details-item"><span class="key">Service</span><span class="val">${formatService(details.service)}</span></div>
+      <div class="details-item"><span class="key">Managed By</span><span class="val">${details.supervisor ? escapeHtml(`${details.supervisor.manager} (PID ${details.supervisor.managerPid})`) : "-"}</span></div>
       <div class="details-item"><span class="key">Status</span><span class="val">${escapeHtml(process.status)}</span></div>
+      <div class="details-item" style="grid-column: 1 / -1"><span class="key">Blocked On</span><span class="val">${renderThreadWaits(details.waits)}</span></div>
+      <div class="details-item"><span class="key">Nice</span><span class="val">${process.nice ?? "-"}</span></div>
+      <div class="details-item"><span class="key">I/O Priority</span><span class="val">${formatIoPriority(details.ioPriority)}</span></div>
       <div class="details-item"><span class="key">CPU %</span><span class="val">${formatCpu(process.cpuPercent)}</span></div>
+      <div class="details-item"><span class="key">CPU Time</span><span class="val">${formatCpuTime(process)}</span></div>
       <div class="details-item"><span class="key">Memory</span><span class="val">${formatBytes(process.memoryBytes)}</span></div>
       <div class="details-item"><span class="key">Virtual Memory</span><span class="val">${formatBytes(process.virtualMemoryBytes)}</span></div>
+      <div class="details-item"><span class="key">Resident</span><span class="val">${formatOptionalBytes(memory.residentBytes)}</span></div>
+      <div class="details-item"><span class="key">Shared</span><span class="val">${formatOptionalBytes(memory.sharedBytes)}</span></div>
+      <div class="details-item"><span class="key">Swapped</span><span class="val">${formatOptionalBytes(memory.swappedBytes)}</span></div>
+      <div class="details-item"><span class="key">Unique (USS)</span><span class="val">${formatOptionalBytes(memory.uniqueBytes)}</span></div>
+      <div class="details-item"><span class="key">Context Switches</span><span class="val">${formatContextSwitches(details.counters)}</span></div>
+      <div class="details-item"><span class="key">Page Faults</span><span class="val">${formatPageFaults(details.counters)}</span></div>
       <div class="details-item"><span class="key">Open File Handles</span><span class="val">${details.openFileHandles ?? "Unavailable"}</span></div>
-      <div class="details-item"><span class="key">I/O Read</span><span class="val">${formatBytes(process.readBytes)}</span></div>
-      <div class="details-item"><span class="key">I/O Written</span><span class="val">${formatBytes(process.writtenBytes)}</span></div>
+      <div class="details-item"><span class="key">Limits</span><span class="val">${renderResourceLimits(details.limits, details.openFileHandles)}</span></div>
+      <div class="details-item"><span class="key">I/O Read</span><span class="val">${formatBytes(process.readBytes)}${formatIoRate(process.readBytesPerSec)}</span></div>
+      <div class="details-item"><span class="key">I/O Written</span><span class="val">${formatBytes(process.writtenBytes)}${formatIoRate(process.writtenBytesPerSec)}</span></div>
       <div class="details-item"><span class="key">Runtime</span><span class="val">${process.runTimeSeconds}s</span></div>
-      <div class="details-item"><span class="key">Executable</span><span class="val">${escapeHtml(process.exe || "-")}</span></div>
+      <div class="details-item"><span class="key">Executable</span><span class="val">${escapeHtml(process.exe || "-")}${process.exeOutdated ? ' <span class="badge-warn">deleted or replaced since start</span>' : ""}</span></div>
+      <div class="details-item" style="grid-column: 1 / -1"><span class="key">SHA-256</span><span class="val">${escapeHtml(details.integrity?.sha256 || "-")}</span></div>
+      ${details.integrity?.signature ? `<div class="details-item" style="grid-column: 1 / -1"><span class="key">Signature</span><span class="val">${formatSignature(details.integrity.signature)}</span></div>` : ""}
       <div class="details-item"><span class="key">Working Directory</span><span class="val">${escapeHtml(details.cwd || "-")}</span></div>
       <div class="details-item"><span class="key">Root</span><span class="val">${escapeHtml(details.root || "-")}</span></div>
+      <div class="details-item" style="grid-column: 1 / -1"><span class="key">Ancestry</span><span class="val">${renderAncestry(ancestry)}</span></div>
       <div class="details-item" style="grid-column: 1 / -1"><span class="key">Command</span><span class="val">${escapeHtml(process.cmd || "-")}</span></div>
+      <div class="details-item" style="grid-column: 1 / -1"><span class="key">Children (${(details.children || []).length})</span><span class="val">${renderChildList(details.children)}</span></div>
+      <div class="details-item" style="grid-column: 1 / -1"><span class="key">Open Sockets</span><span class="val">${renderSocketList(details.openSockets)}</span></div>${renderHistoryRows(history)}
     </div>
   `;
 }
 
 async function loadDetails(pid) {
   try {
-    const details = await call("get_process_details", { pid });
-    renderDetails(details);
+    const [details, history, ancestry] = await Promise.all([
+      call("get_process_details", { pid }),
+      call("get_process_history", { pid }).catch(() => null),
+      call("get_process_ancestry", { pid }).catch(() => null),
+    ]);
+    renderDetails(details, history, ancestry);
   } catch (error) {
     el.detailsBody.innerHTML = `<div class="details-empty">Failed to load details: ${escapeHtml(error.message || String(error))}</div>`;
   }
 }
 
+// Runs a kill command and, if any target was refused with EPERM, offers to
+// retry it behind the OS admin prompt.
+async function callKill(command, args) {
+  args = { verifyMs: KILL_VERIFY_MS, ...args };
+  const result = await call(command, args);
+  const denied = result.failed.filter((failure) => failure.error.startsWith("EPERM"));
+  if (denied.length === 0) {
+    return result;
+  }
+
+  const retry = window.confirm(
+    `${denied.length} process(es) belong to another user or the system. Retry as administrator?`,
+  );
+  if (!retry) {
+    return result;
+  }
+  // A confirmation token is spent by the first call; the retry re-resolves.
+  const { confirmationToken, ...retryArgs } = args;
+  return call(command, { ...retryArgs, allowElevation: true });
+}
+
+function killSummary(result) {
+  const elevated = result.privilege === "elevated" ? " (elevated)" : "";
+  const survivors = result.stillRunning.length > 0 ? `, still running=${result.stillRunning.length}` : "";
+  const roots = result.killed.filter((entry) => entry.role === "root");
+  const names = [...new Set(roots.map((entry) => entry.name || `PID ${entry.pid}`))].slice(0, 5);
+  const children = result.killed.length - roots.length;
+  const named = names.length > 0 ? ` — ${names.join(", ")}${children > 0 ? ` + ${children} child processes` : ""}` : "";
+  return `matched=${result.matched}, attempted=${result.attempted}, killed=${result.killed.length}, failed=${result.failed.length}${survivors}${elevated}${named}${supervisedSummary(result.supervised)}`;
+}
+
+// "this will be restarted by pm2" for kills a process manager will undo.
+function supervisedSummary(supervised) {
+  if (!supervised || supervised.length === 0) {
+    return "";
+  }
+  const managers = [...new Set(supervised.map((entry) => entry.manager))].join(", ");
+  return `. ${supervised.length} will be restarted by ${managers}; use the manager's stop instead`;
+}
+
+function renderSupervisorActions(supervisor) {
+  const stop = supervisor.stopCommand
+    ? `<button data-action="stop-supervised" data-pid="${supervisor.pid}">Stop via ${escapeHtml(supervisor.manager)}</button>`
+    : "";
+  return `${stop}<button data-action="kill" data-pid="${supervisor.managerPid}">Kill ${escapeHtml(supervisor.manager)}</button>`;
+}
+
+async function runStopSupervised(pid) {
+  try {
+    const supervised = await call("stop_supervised", { pid });
+    setStatus(`Stopped PID ${pid} with \`${supervised.stopCommand.join(" ")}\`.`);
+    await refreshAll();
+  } catch (error) {
+    setStatus(`Stop failed: ${error.message || String(error)}`, "warn");
+  }
+}
+
+function killSucceeded(result) {
+  return result.failed.length === 0 && result.stillRunning.length === 0;
+}
+
 async function runKill(pid, includeChildren) {
   const label = includeChildren ? "this process and its child tree" : "this process";
-  const confirmed = window.confirm(`Kill ${label}? PID ${pid}`);
+  const supervisor = state.selectedDetails?.process.pid === pid ? state.selectedDetails.supervisor : null;
+  const confirmed = window.confirm(`Kill ${label}? PID ${pid}${supervisedSummary(supervisor ? [supervisor] : [])}`);
   if (!confirmed) {
     return;
   }
 
   try {
-    const result = await call("kill_process", {
+    const result = await callKill("kill_process", {
       pid,
       includeChildren,
       force: false,
     });
 
     setStatus(
-      `Kill request completed. matched=${result.matched}, attempted=${result.attempted}, killed=${result.killed.length}, failed=${result.failed.length}`,
-      result.failed.length > 0 ? "warn" : "info",
+      `Kill request completed. ${killSummary(result)}`,
+      killSucceeded(result) ? "info" : "warn",
     );
 
+    if (result.killed.some((entry) => entry.pid === pid)) {
+      await call("watch_pid", { pid });
+    }
+
     if (state.selectedPid === pid) {
       state.selectedPid = null;
       el.detailsBody.innerHTML = "<div class='details-empty'>Select a process to inspect details.</div>";
@@ -453,79 +1066,593 @@ async function runKill(pid, includeChildren) {
   }
 }
 
-async function runBulkKill() {
-  const query = el.bulkQuery.value.trim();
-  if (!query) {
-    setStatus("Enter a bulk query first (example: node).", "warn");
-    return;
+function formatContainer(container) {
+  if (!container) {
+    return "-";
   }
+  const label = container.name || (container.id ? container.id.slice(0, 12) : "");
+  return escapeHtml(label ? `${label} (${container.runtime})` : container.runtime);
+}
 
-  const confirmed = window.confirm(`Kill all processes matching "${query}" and their child processes?`);
-  if (!confirmed) {
-    return;
-  }
+// Higher nice values mean lower priority; 10 is a sensible default for
+// pushing a build into the background.
+const PROFILE_DURATION_MS = 2000;
+const PROFILE_FRAMES_SHOWN = 5;
 
+// "What is it actually doing": the functions its threads sat in most.
+async function runSampleStacks(pid) {
+  setStatus(`Sampling stacks of PID ${pid} for ${PROFILE_DURATION_MS / 1000}s...`);
   try {
-    const result = await call("kill_matching_processes", {
-      query,
-      includeChildren: true,
-      force: false,
+    const profile = await call("sample_process", { pid, durationMs: PROFILE_DURATION_MS });
+    const hottest = profile.frames.slice(0, PROFILE_FRAMES_SHOWN).map((frame) => {
+      const share = profile.samples ? Math.round((frame.selfSamples / profile.samples) * 100) : 0;
+      const module = frame.module ? ` [${frame.module}]` : "";
+      return `${frame.function}${module} ${share}%`;
     });
-
-    setStatus(
-      `Bulk kill completed. matched=${result.matched}, attempted=${result.attempted}, killed=${result.killed.length}, failed=${result.failed.length}`,
-      result.failed.length > 0 ? "warn" : "info",
-    );
-
-    await refreshAll();
+    setStatus(`PID ${pid} (${profile.samples} stacks): ${hottest.join(", ") || "no frames resolved"}`);
   } catch (error) {
-    setStatus(`Bulk kill failed: ${error.message || String(error)}`, "warn");
+    setStatus(`Stack sampling failed: ${error.message || String(error)}`, "warn");
   }
 }
 
-async function refreshAll() {
-  if (state.isRefreshing) {
+async function runRenice(pid, current) {
+  const answer = window.prompt(`New nice value for PID ${pid} (-20 to 19, higher is lower priority):`, String(current ?? 10));
+  if (answer === null || answer.trim() === "") {
     return;
   }
 
-  state.isRefreshing = true;
-  try {
-    const [snapshot, ports] = await Promise.all([call("get_process_snapshot"), call("list_open_ports")]);
-
-    state.processes = Array.isArray(snapshot.processes) ? snapshot.processes : [];
-    state.ports = Array.isArray(ports) ? ports : [];
-
-    if (state.selectedPid && !state.processes.some((process) => process.pid === state.selectedPid)) {
-      state.selectedPid = null;
-      el.detailsBody.innerHTML = "<div class='details-empty'>Selected process is no longer running.</div>";
-    }
-
-    renderStats(snapshot, state.ports);
-    renderProcessTable();
-    renderPortTable();
+  const nice = Number(answer);
+  if (!Number.isInteger(nice)) {
+    setStatus(`Invalid nice value: ${answer}`, "warn");
+    return;
+  }
 
-    setStatus(`Refreshed ${state.processes.length} processes and ${state.ports.length} ports.`);
+  try {
+    await call("set_priority", { pid, nice });
+    setStatus(`PID ${pid} is now at nice ${nice}.`);
+    await loadDetails(pid);
   } catch (error) {
-    setStatus(`Refresh failed: ${error.message || String(error)}`, "warn");
-  } finally {
-    state.isRefreshing = false;
+    setStatus(`Renice failed: ${error.message || String(error)}`, "warn");
   }
 }
 
-function activateTab(name) {
-  for (const tabButton of document.querySelectorAll(".tab")) {
-    const isActive = tabButton.dataset.tab === name;
-    tabButton.classList.toggle("active", isActive);
+function renderPins() {
+  if (state.pins.length === 0) {
+    el.pinnedStatus.textContent = "-";
+    el.pinnedStatus.title = "";
+    return;
   }
 
-  el.detailsTab.classList.toggle("active", name === "details");
-  el.portsTab.classList.toggle("active", name === "ports");
+  const running = state.pins.filter((pin) => pin.running).length;
+  el.pinnedStatus.textContent = `${running}/${state.pins.length} up`;
+  el.pinnedStatus.title = state.pins
+    .map((pin) => `${pin.running ? "up" : "down"}: ${pin.pattern}`)
+    .join("\n");
 }
 
-function syncRefreshTimer() {
-  if (state.refreshTimer) {
-    clearInterval(state.refreshTimer);
-    state.refreshTimer = null;
+function updatePin(pin) {
+  const index = state.pins.findIndex((entry) => entry.pattern === pin.pattern);
+  if (index === -1) {
+    state.pins.push(pin);
+  } else {
+    state.pins[index] = pin;
+  }
+  renderPins();
+}
+
+async function refreshPins() {
+  try {
+    state.pins = await call("list_pinned_processes");
+    renderPins();
+  } catch {
+    // Pins are an indicator only; the rest of the UI works without them.
+  }
+}
+
+// No shell is involved, so the command line is split on whitespace only.
+// First click saves a snapshot, the second reports what changed since.
+async function runMark() {
+  try {
+    if (state.markedSnapshotId === null) {
+      const saved = await call("save_snapshot");
+      state.markedSnapshotId = saved.id;
+      el.markBtn.textContent = "Changes Since Mark";
+      setStatus(`Marked ${saved.processCount} processes. Click again to see what changed.`);
+      return;
+    }
+
+    const diff = await call("diff_snapshots", { before: state.markedSnapshotId });
+    state.markedSnapshotId = null;
+    el.markBtn.textContent = "Mark";
+    const names = (processes) => [...new Set(processes.map((process) => process.name))].slice(0, 5).join(", ");
+    const parts = [];
+    if (diff.added.length > 0) {
+      parts.push(`+${diff.added.length} started (${names(diff.added)})`);
+    }
+    if (diff.removed.length > 0) {
+      parts.push(`-${diff.removed.length} exited (${names(diff.removed)})`);
+    }
+    if (diff.changed.length > 0) {
+      const moves = diff.changed
+        .slice(0, 3)
+        .map((change) => `${change.name} ${formatBytes(change.memoryBeforeBytes)} → ${formatBytes(change.memoryAfterBytes)}, CPU ${formatCpu(change.cpuBefore)}% → ${formatCpu(change.cpuAfter)}%`);
+      parts.push(`${diff.changed.length} changed (${moves.join("; ")})`);
+    }
+    const seconds = Math.round(diff.elapsedMs / 1000);
+    setStatus(parts.length > 0 ? `In ${seconds}s: ${parts.join(", ")}` : `Nothing notable changed in ${seconds}s.`);
+  } catch (error) {
+    state.markedSnapshotId = null;
+    el.markBtn.textContent = "Mark";
+    setStatus(`Comparing snapshots failed: ${error.message || String(error)}`, "warn");
+  }
+}
+
+async function runLaunch() {
+  const answer = window.prompt("Command to run (no shell; arguments split on spaces):", "");
+  const [cmd, ...args] = (answer || "").trim().split(/\s+/).filter(Boolean);
+  if (!cmd) {
+    return;
+  }
+
+  try {
+    const launched = await call("spawn_process", { cmd, args });
+    setStatus(`Started ${cmd} as PID ${launched.pid}.`);
+    await refreshAll();
+  } catch (error) {
+    setStatus(`Launch failed: ${error.message || String(error)}`, "warn");
+  }
+}
+
+async function runOpenTerminal(pid) {
+  try {
+    const cwd = await call("open_terminal_at", { pid });
+    setStatus(`Opened a terminal in ${cwd}.`);
+  } catch (error) {
+    setStatus(`Open terminal failed: ${error.message || String(error)}`, "warn");
+  }
+}
+
+async function runCopyShellCommand(pid) {
+  try {
+    const command = await call("get_shell_command", { pid, includeCwd: true });
+    await navigator.clipboard.writeText(command);
+    setStatus(`Copied: ${command}`);
+  } catch (error) {
+    setStatus(`Copy failed: ${error.message || String(error)}`, "warn");
+  }
+}
+
+async function runRevealExecutable(pid) {
+  try {
+    const exe = await call("reveal_executable", { pid });
+    setStatus(`Showing ${exe}.`);
+  } catch (error) {
+    setStatus(`Reveal failed: ${error.message || String(error)}`, "warn");
+  }
+}
+
+async function runPinProcess(name) {
+  try {
+    const pin = await call("pin_process", { pattern: name });
+    updatePin(pin);
+    setStatus(`Pinned ${pin.pattern}; it is ${pin.running ? "running" : "not running"}.`);
+  } catch (error) {
+    setStatus(`Pin failed: ${error.message || String(error)}`, "warn");
+  }
+}
+
+const IO_PRIORITY_LABELS = {
+  none: "default (from nice)",
+  realTime: "real-time",
+  bestEffort: "best-effort",
+  idle: "idle",
+};
+
+// "Sleep" alone doesn't say much; what the threads sleep in does, e.g. a
+// futex (possibly a deadlock) versus epoll (waiting on the network).
+function renderThreadWaits(waits) {
+  if (!waits) {
+    return "Unavailable";
+  }
+  if (!waits.length) {
+    return "-";
+  }
+  return waits
+    .map((wait) => {
+      const channel = wait.waitChannel ? ` in ${escapeHtml(wait.waitChannel)}` : "";
+      const reason = wait.reason ? ` — ${escapeHtml(wait.reason)}` : "";
+      const count = wait.threads === 1 ? "1 thread" : `${wait.threads} threads`;
+      return `<div>${count}: ${escapeHtml(wait.state)}${channel}${reason}</div>`;
+    })
+    .join("");
+}
+
+function formatIoPriority(priority) {
+  if (!priority) {
+    return "-";
+  }
+  const label = IO_PRIORITY_LABELS[priority.class] || priority.class;
+  return escapeHtml(priority.level == null ? label : `${label} ${priority.level}`);
+}
+
+// The idle class is what ionice -c 3 does: the process only touches the
+// disk when nothing else wants it.
+async function runIdleIo(pid) {
+  try {
+    await call("set_io_priority", { pid, class: "idle" });
+    setStatus(`PID ${pid} now only gets idle disk time.`);
+    await loadDetails(pid);
+  } catch (error) {
+    setStatus(`Setting I/O priority failed: ${error.message || String(error)}`, "warn");
+  }
+}
+
+// Accepts the same list syntax as taskset -c: "0,2" or "0-3,6".
+function parseCpuList(text) {
+  const cpus = [];
+  for (const part of text.split(",").map((item) => item.trim()).filter(Boolean)) {
+    const match = /^(\d+)(?:-(\d+))?$/.exec(part);
+    if (!match) {
+      return null;
+    }
+    const first = Number(match[1]);
+    const last = Number(match[2] ?? match[1]);
+    for (let cpu = first; cpu <= last; cpu += 1) {
+      cpus.push(cpu);
+    }
+  }
+  return cpus;
+}
+
+async function runPinCpus(pid) {
+  let current;
+  try {
+    current = await call("get_affinity", { pid });
+  } catch (error) {
+    setStatus(`Reading CPU affinity failed: ${error.message || String(error)}`, "warn");
+    return;
+  }
+
+  const answer = window.prompt(`CPUs PID ${pid} may run on (e.g. 0-3,6):`, current.join(","));
+  if (answer === null) {
+    return;
+  }
+  const cpus = parseCpuList(answer);
+  if (!cpus || cpus.length === 0) {
+    setStatus(`Invalid CPU list: ${answer}`, "warn");
+    return;
+  }
+
+  try {
+    await call("set_affinity", { pid, cpus });
+    setStatus(`PID ${pid} is pinned to CPUs ${cpus.join(",")}.`);
+  } catch (error) {
+    setStatus(`Pinning CPUs failed: ${error.message || String(error)}`, "warn");
+  }
+}
+
+function formatSignature(signature) {
+  if (!signature.signed) {
+    return '<span class="badge-warn">unsigned</span>';
+  }
+  if (signature.adhoc) {
+    return `${escapeHtml(signature.identifier)} <span class="badge-warn">ad-hoc</span>`;
+  }
+  const signer = signature.authorities[0] || "unknown authority";
+  const team = signature.teamId ? ` (team ${signature.teamId})` : "";
+  return escapeHtml(`${signature.identifier} signed by ${signer}${team}`);
+}
+
+// "limited to 2 CPUs, 512 MB" plus whatever has already hit those limits.
+function formatCgroup(cgroup) {
+  if (!cgroup) {
+    return "-";
+  }
+
+  const limits = [];
+  if (cgroup.cpuLimitCores != null) {
+    const cores = Number(cgroup.cpuLimitCores.toFixed(2));
+    limits.push(`${cores} CPU${cores === 1 ? "" : "s"}`);
+  }
+  if (cgroup.memoryLimitBytes != null) {
+    limits.push(formatBytes(cgroup.memoryLimitBytes));
+  }
+  if (cgroup.pidsLimit != null) {
+    limits.push(`${cgroup.pidsLimit} processes`);
+  }
+
+  const parts = [cgroup.path, limits.length ? `limited to ${limits.join(", ")}` : "no limits"];
+  if (cgroup.memoryUsageBytes != null && cgroup.memoryLimitBytes != null) {
+    parts.push(`${formatBytes(cgroup.memoryUsageBytes)} in use`);
+  }
+  if (cgroup.oomKills) {
+    parts.push(`${cgroup.oomKills} OOM kill${cgroup.oomKills === 1 ? "" : "s"}`);
+  }
+  if (cgroup.throttledPeriods) {
+    parts.push(`throttled ${cgroup.throttledPeriods} times`);
+  }
+  return escapeHtml(parts.join(" - "));
+}
+
+async function runStopContainer(id) {
+  if (!window.confirm(`Stop container ${id.slice(0, 12)}? This is usually safer than killing its processes.`)) {
+    return;
+  }
+
+  try {
+    setStatus(`Stopping container ${id.slice(0, 12)}...`);
+    await call("stop_container", { id });
+    setStatus(`Stopped container ${id.slice(0, 12)}.`);
+    await refreshAll();
+  } catch (error) {
+    setStatus(`Stop container failed: ${error.message || String(error)}`, "warn");
+  }
+}
+
+function formatMultiplexer(pane) {
+  if (!pane) {
+    return "-";
+  }
+  if (pane.windowIndex == null) {
+    return escapeHtml(`${pane.kind} ${pane.session}`);
+  }
+  const windowLabel = pane.windowName ? `${pane.windowIndex} (${pane.windowName})` : String(pane.windowIndex);
+  return escapeHtml(`${pane.kind} ${pane.session}:${windowLabel}.${pane.paneIndex ?? 0}`);
+}
+
+function renderMultiplexerActions(pane) {
+  if (!pane) {
+    return "";
+  }
+  const session = escapeHtml(pane.session);
+  const windowButton = pane.windowIndex == null
+    ? ""
+    : `<button data-action="kill-multiplexer" data-session="${session}" data-window="${pane.windowIndex}">Kill Window</button>`;
+  return `${windowButton}<button data-action="kill-multiplexer" data-session="${session}">Kill Session</button>`;
+}
+
+// Dev servers usually live in a tmux pane, so this clears out everything
+// started from one window or session while keeping the panes open.
+async function runMultiplexerKill(session, windowIndex) {
+  const label = windowIndex == null ? `session "${session}"` : `window ${windowIndex} of session "${session}"`;
+  if (!window.confirm(`Kill everything running in ${label}? The pane shells stay open.`)) {
+    return;
+  }
+
+  try {
+    const result = await callKill("kill_multiplexer_session", { session, windowIndex, force: false });
+    setStatus(
+      `Kill request completed. ${killSummary(result)}`,
+      killSucceeded(result) ? "info" : "warn",
+    );
+    await refreshAll();
+  } catch (error) {
+    setStatus(`Kill failed: ${error.message || String(error)}`, "warn");
+  }
+}
+
+function formatService(service) {
+  if (!service) {
+    return "-";
+  }
+  return escapeHtml(`${service.unit} (${service.manager}${service.user ? ", user" : ""})`);
+}
+
+// Killing a supervised process just gets it respawned, so these actions
+// go through the service manager instead.
+const SERVICE_ACTIONS = {
+  systemd: [
+    { action: "stop", label: "Stop Unit", command: "stop_unit", arg: "unit", done: "Stopped" },
+    { action: "restart", label: "Restart Unit", command: "restart_unit", arg: "unit", done: "Restarted" },
+  ],
+  launchd: [
+    { action: "bootout", label: "Unload Job", command: "bootout_job", arg: "label", done: "Unloaded" },
+    { action: "disable", label: "Disable Job", command: "disable_job", arg: "label", done: "Disabled" },
+  ],
+};
+
+function renderServiceActions(service) {
+  return (SERVICE_ACTIONS[service?.manager] || [])
+    .map((entry) => `<button data-action="service" data-service-action="${entry.action}">${entry.label}</button>`)
+    .join("");
+}
+
+async function runServiceAction(service, action) {
+  const entry = (SERVICE_ACTIONS[service?.manager] || []).find((candidate) => candidate.action === action);
+  if (!entry) {
+    return;
+  }
+  if (!window.confirm(`${entry.label.split(" ")[0]} ${service.unit}?`)) {
+    return;
+  }
+
+  try {
+    await call(entry.command, { [entry.arg]: service.unit, user: service.user });
+    setStatus(`${entry.done} ${service.unit}.`);
+    await refreshAll();
+  } catch (error) {
+    setStatus(`${entry.label} failed: ${error.message || String(error)}`, "warn");
+  }
+}
+
+async function runReload(pid, daemon) {
+  const message = daemon
+    ? `Send SIGHUP to ${daemon.daemon} (PID ${pid})?\n\n${daemon.note}.`
+    : `pswtf doesn't know how PID ${pid} handles SIGHUP, and many programs exit on it. Send it anyway?`;
+  if (!window.confirm(message)) {
+    return;
+  }
+
+  try {
+    const result = await call("reload_process", { pid });
+    const delivered = result.report.killed.some((entry) => entry.pid === pid);
+    const failure = result.report.failed[0]?.error;
+    setStatus(
+      delivered ? `Sent SIGHUP to PID ${pid}.` : `Reload failed: ${failure || "signal not sent"}`,
+      delivered ? "info" : "warn",
+    );
+    await refreshAll();
+  } catch (error) {
+    setStatus(`Reload failed: ${error.message || String(error)}`, "warn");
+  }
+}
+
+// Everything started from one terminal window, shell included, e.g. after
+// the window was closed but its jobs kept running.
+async function runTtyKill(tty) {
+  const members = state.processes.filter((process) => process.tty === tty);
+  if (!window.confirm(`Kill all ${members.length} processes on ${tty}, including its shell?`)) {
+    return;
+  }
+
+  try {
+    const result = await callKill("kill_by_tty", { tty, force: false });
+    setStatus(`${tty}: ${killSummary(result)}`, killSucceeded(result) ? "info" : "warn");
+    await refreshAll();
+  } catch (error) {
+    setStatus(`Terminal kill failed: ${error.message || String(error)}`, "warn");
+  }
+}
+
+async function runGroupKill(pgid) {
+  const members = state.processes.filter((process) => process.pgid === pgid);
+  const confirmed = window.confirm(`Kill all ${members.length} processes in group ${pgid}?`);
+  if (!confirmed) {
+    return;
+  }
+
+  try {
+    const result = await call("kill_process_group", { pgid, force: false });
+    setStatus(`Group ${pgid}: ${killSummary(result)}`, killSucceeded(result) ? "info" : "warn");
+    await refreshAll();
+  } catch (error) {
+    setStatus(`Group kill failed: ${error.message || String(error)}`, "warn");
+  }
+}
+
+async function runBulkKill() {
+  const query = el.bulkQuery.value.trim();
+  if (!query) {
+    setStatus("Enter a bulk query first (example: node).", "warn");
+    return;
+  }
+
+  const matchArgs = {
+    query,
+    matchMode: el.bulkMatchMode.value,
+    caseSensitive: el.bulkCaseSensitive.checked,
+    exclude: el.bulkExclude.value
+      .split(",")
+      .map((pattern) => pattern.trim())
+      .filter(Boolean),
+    includeChildren: true,
+  };
+
+  // The token pins the kill to exactly what's previewed here, and can only
+  // be used once.
+  let confirmation;
+  try {
+    confirmation = await call("prepare_matching_kill", matchArgs);
+  } catch (error) {
+    setStatus(`Bulk kill failed: ${error.message || String(error)}`, "warn");
+    return;
+  }
+
+  const preview = confirmation.preview;
+  if (preview.roots.length === 0) {
+    setStatus(`No processes match "${query}".`, "warn");
+    return;
+  }
+
+  const names = [...new Set(preview.roots.map((process) => process.name))].slice(0, 8).join(", ");
+  const confirmed = window.confirm(
+    `Kill ${preview.roots.length} processes matching "${query}" (${names}) and ${preview.descendants.length} child processes?${supervisedSummary(preview.supervised)}`,
+  );
+  if (!confirmed) {
+    return;
+  }
+
+  try {
+    const result = await callKill("kill_matching_processes", {
+      ...matchArgs,
+      force: false,
+      confirmationToken: confirmation.token,
+    });
+
+    setStatus(
+      `Bulk kill completed. ${killSummary(result)}`,
+      killSucceeded(result) ? "info" : "warn",
+    );
+
+    await refreshAll();
+  } catch (error) {
+    setStatus(`Bulk kill failed: ${error.message || String(error)}`, "warn");
+  }
+}
+
+async function refreshAll() {
+  if (state.isRefreshing) {
+    return;
+  }
+
+  state.isRefreshing = true;
+  try {
+    const [snapshot, ports, overview] = await Promise.all([
+      call("get_process_snapshot", state.groupByApp ? { groupBy: "app" } : {}),
+      call("list_open_ports"),
+      call("get_system_overview"),
+    ]);
+
+    state.processes = Array.isArray(snapshot.processes) ? snapshot.processes : [];
+    state.groups = Array.isArray(snapshot.groups) ? snapshot.groups : [];
+    state.ports = Array.isArray(ports) ? ports : [];
+
+    if (state.selectedPid && !state.processes.some((process) => process.pid === state.selectedPid)) {
+      state.selectedPid = null;
+      el.detailsBody.innerHTML = "<div class='details-empty'>Selected process is no longer running.</div>";
+    }
+
+    renderStats(snapshot, state.ports, overview);
+    renderProcessTable();
+    renderPortTable();
+    if (state.activeTab === "connections") {
+      await refreshConnections();
+    }
+
+    setStatus(`Refreshed ${state.processes.length} processes and ${state.ports.length} ports.`);
+  } catch (error) {
+    setStatus(`Refresh failed: ${error.message || String(error)}`, "warn");
+  } finally {
+    state.isRefreshing = false;
+  }
+}
+
+function activateTab(name) {
+  for (const tabButton of document.querySelectorAll(".tab")) {
+    const isActive = tabButton.dataset.tab === name;
+    tabButton.classList.toggle("active", isActive);
+  }
+
+  el.detailsTab.classList.toggle("active", name === "details");
+  el.portsTab.classList.toggle("active", name === "ports");
+  el.connectionsTab.classList.toggle("active", name === "connections");
+  el.zombiesTab.classList.toggle("active", name === "zombies");
+  el.filesTab.classList.toggle("active", name === "files");
+
+  const opened = name !== state.activeTab;
+  state.activeTab = name;
+  if (opened && name === "connections") {
+    refreshConnections();
+  }
+  if (opened && name === "zombies") {
+    refreshZombies();
+  }
+}
+
+function syncRefreshTimer() {
+  if (state.refreshTimer) {
+    clearInterval(state.refreshTimer);
+    state.refreshTimer = null;
   }
 
   if (state.autoRefresh) {
@@ -546,6 +1673,16 @@ function bindEvents() {
     renderPortTable();
   });
 
+  el.connectionSearch.addEventListener("input", (event) => {
+    state.connectionSearch = event.target.value;
+    renderConnectionTable();
+  });
+
+  el.resolveHosts.addEventListener("change", async (event) => {
+    state.resolveHosts = event.target.checked;
+    await refreshConnections();
+  });
+
   el.sortBy.addEventListener("change", (event) => {
     state.sortBy = event.target.value;
     renderProcessTable();
@@ -556,6 +1693,11 @@ function bindEvents() {
     renderProcessTable();
   });
 
+  el.groupByApp.addEventListener("change", async (event) => {
+    state.groupByApp = event.target.checked;
+    await refreshAll();
+  });
+
   el.autoRefresh.addEventListener("change", (event) => {
     state.autoRefresh = event.target.checked;
     syncRefreshTimer();
@@ -565,6 +1707,14 @@ function bindEvents() {
     refreshAll();
   });
 
+  el.launchBtn.addEventListener("click", () => {
+    runLaunch();
+  });
+
+  el.markBtn.addEventListener("click", () => {
+    runMark();
+  });
+
   el.bulkKillBtn.addEventListener("click", () => {
     runBulkKill();
   });
@@ -581,6 +1731,16 @@ function bindEvents() {
       return;
     }
 
+    const groupRow = event.target.closest("tr[data-group]");
+    if (groupRow) {
+      const key = groupRow.dataset.group;
+      if (!state.expandedGroups.delete(key)) {
+        state.expandedGroups.add(key);
+      }
+      renderProcessTable();
+      return;
+    }
+
     const row = event.target.closest("tr[data-pid]");
     if (!row) {
       return;
@@ -604,20 +1764,125 @@ function bindEvents() {
       await runKill(pid, false);
     } else if (actionButton.dataset.action === "kill-tree") {
       await runKill(pid, true);
+    } else if (actionButton.dataset.action === "kill-group") {
+      await runGroupKill(Number(actionButton.dataset.pgid));
+    } else if (actionButton.dataset.action === "stop-container") {
+      await runStopContainer(actionButton.dataset.container);
+    } else if (actionButton.dataset.action === "kill-tty") {
+      await runTtyKill(actionButton.dataset.tty);
+    } else if (actionButton.dataset.action === "kill-multiplexer") {
+      const windowIndex = actionButton.dataset.window;
+      await runMultiplexerKill(actionButton.dataset.session, windowIndex === undefined ? null : Number(windowIndex));
+    } else if (actionButton.dataset.action === "stop-supervised") {
+      await runStopSupervised(pid);
+    } else if (actionButton.dataset.action === "service") {
+      await runServiceAction(state.selectedDetails?.service, actionButton.dataset.serviceAction);
+    } else if (actionButton.dataset.action === "reload") {
+      await runReload(pid, state.selectedDetails?.reload);
+    } else if (actionButton.dataset.action === "renice") {
+      await runRenice(pid, state.selectedDetails?.process.nice);
+    } else if (actionButton.dataset.action === "select-process") {
+      state.selectedPid = pid;
+      renderProcessTable();
+      await loadDetails(pid);
+    } else if (actionButton.dataset.action === "idle-io") {
+      await runIdleIo(pid);
+    } else if (actionButton.dataset.action === "pin-cpus") {
+      await runPinCpus(pid);
+    } else if (actionButton.dataset.action === "sample-stacks") {
+      await runSampleStacks(pid);
+    } else if (actionButton.dataset.action === "pin-process") {
+      await runPinProcess(actionButton.dataset.name);
+    } else if (actionButton.dataset.action === "open-terminal") {
+      await runOpenTerminal(pid);
+    } else if (actionButton.dataset.action === "reveal-exe") {
+      await runRevealExecutable(pid);
+    } else if (actionButton.dataset.action === "copy-shell") {
+      await runCopyShellCommand(pid);
     }
   });
 
   el.portRows.addEventListener("click", async (event) => {
-    const actionButton = event.target.closest("button[data-action='focus-pid']");
+    const probeButton = event.target.closest("button[data-action='probe-port']");
+    if (probeButton) {
+      await runProbe(Number(probeButton.dataset.port));
+    }
+    const historyButton = event.target.closest("button[data-action='port-history']");
+    if (historyButton) {
+      await runPortHistory(Number(historyButton.dataset.port));
+    }
+  });
+
+  el.zombieRows.addEventListener("click", async (event) => {
+    const actionButton = event.target.closest("button[data-action]");
     if (!actionButton) {
       return;
     }
 
     const pid = Number(actionButton.dataset.pid);
-    state.selectedPid = pid;
-    activateTab("details");
-    renderProcessTable();
-    await loadDetails(pid);
+    if (actionButton.dataset.action === "nudge-parent") {
+      await runNudgeParent(pid);
+    } else if (actionButton.dataset.action === "kill-parent") {
+      await runKill(pid, false);
+      await refreshZombies();
+    }
+  });
+
+  el.findFileUsersBtn.addEventListener("click", () => {
+    refreshFileUsers("path");
+  });
+
+  el.findMountBlockersBtn.addEventListener("click", () => {
+    refreshFileUsers("mount");
+  });
+
+  el.fileUserRows.addEventListener("click", async (event) => {
+    const actionButton = event.target.closest("button[data-action]");
+    if (!actionButton) {
+      return;
+    }
+
+    const pid = Number(actionButton.dataset.pid);
+    if (actionButton.dataset.action === "kill-file-user") {
+      await runKill(pid, false);
+      await refreshFileUsers(state.fileUsersMode);
+    } else if (actionButton.dataset.action === "suspend") {
+      await runSuspend(pid, false);
+    } else if (actionButton.dataset.action === "resume") {
+      await runSuspend(pid, true);
+    }
+  });
+
+  for (const rows of [el.portRows, el.connectionRows, el.zombieRows, el.fileUserRows]) {
+    rows.addEventListener("click", async (event) => {
+      const actionButton = event.target.closest("button[data-action='focus-pid']");
+      if (!actionButton) {
+        return;
+      }
+
+      const pid = Number(actionButton.dataset.pid);
+      state.selectedPid = pid;
+      activateTab("details");
+      renderProcessTable();
+      await loadDetails(pid);
+    });
+  }
+
+  // Hidden windows still feed the tray menu and watchers, so slow the
+  // sampler down rather than pausing it.
+  document.addEventListener("visibilitychange", async () => {
+    try {
+      if (document.hidden) {
+        const status = await call("get_monitoring_status");
+        state.visibleIntervalMs = status.intervalMs;
+        await call("set_refresh_interval", { ms: Math.max(status.intervalMs, HIDDEN_REFRESH_MS) });
+      } else if (state.visibleIntervalMs) {
+        await call("set_refresh_interval", { ms: state.visibleIntervalMs });
+        state.visibleIntervalMs = null;
+      }
+    } catch {
+      // Best effort; the sampler keeps its current interval.
+    }
   });
 
   document.querySelectorAll(".tab").forEach((tabButton) => {
@@ -627,6 +1892,109 @@ function bindEvents() {
   });
 }
 
+async function focusProcess(pid) {
+  state.selectedPid = pid;
+  activateTab("details");
+  renderProcessTable();
+  await loadDetails(pid);
+}
+
+async function runPortKill(port) {
+  let owners;
+  try {
+    const ports = await call("list_open_ports");
+    owners = ports.filter((entry) => entry.port === port);
+  } catch (error) {
+    setStatus(`Port kill failed: ${error.message || String(error)}`, "warn");
+    return;
+  }
+
+  if (owners.length === 0) {
+    setStatus(`Nothing is listening on port ${port}.`, "warn");
+    return;
+  }
+
+  const names = [...new Set(owners.map((entry) => `${entry.processName || "unknown"} (${entry.pid ?? "-"})`))];
+  const confirmed = window.confirm(`Kill the process tree holding port ${port}?\n\n${names.join("\n")}`);
+  if (!confirmed) {
+    return;
+  }
+
+  try {
+    const result = await callKill("kill_port_owners", { port, includeChildren: true, force: false });
+    setStatus(
+      `Port ${port}: ${killSummary(result)}`,
+      killSucceeded(result) ? "info" : "warn",
+    );
+    await refreshAll();
+  } catch (error) {
+    setStatus(`Port kill failed: ${error.message || String(error)}`, "warn");
+  }
+}
+
+async function handlePendingDeepLink() {
+  const link = await call("take_pending_deep_link").catch(() => null);
+  if (!link) {
+    return;
+  }
+
+  if (link.action === "inspect") {
+    await focusProcess(link.pid);
+  } else if (link.action === "killPort") {
+    await runPortKill(link.port);
+  }
+}
+
+function subscribeToLifecycleEvents() {
+  if (!listen) {
+    return;
+  }
+
+  listen("process-started", (event) => {
+    state.startedAt.set(event.payload.pid, Date.now());
+  });
+
+  listen("process-exited", (event) => {
+    state.startedAt.delete(event.payload.pid);
+  });
+
+  listen("deep-link", () => {
+    handlePendingDeepLink();
+  });
+
+  listen("deep-link-error", (event) => {
+    setStatus(String(event.payload), "warn");
+  });
+
+  listen("port-exposed", (event) => {
+    const { port, process } = event.payload;
+    const name = process?.name || port.processName || "A dev server";
+    setStatus(`${name} is listening on ${port.localAddress}:${port.port}, reachable from the network.`, "warn");
+  });
+
+  listen("launched-process-exited", (event) => {
+    const { pid, cmd, exitCode, exitSignal } = event.payload;
+    const how = exitSignal ? `signal ${exitSignal}` : `code ${exitCode ?? "?"}`;
+    setStatus(`${cmd} (PID ${pid}) exited with ${how}.`, exitCode === 0 ? undefined : "warn");
+  });
+
+  listen("pinned-process-up", (event) => {
+    updatePin(event.payload);
+    setStatus(`${event.payload.pattern} is running.`);
+  });
+
+  listen("pinned-process-down", (event) => {
+    updatePin(event.payload);
+    setStatus(`${event.payload.pattern} stopped.`, "warn");
+  });
+
+  listen("pid-exited", (event) => {
+    const { pid, detectedAtEpochMs } = event.payload;
+    const at = new Date(detectedAtEpochMs).toLocaleTimeString();
+    setStatus(`PID ${pid} exited (confirmed at ${at}).`);
+  });
+}
+
 function cacheElements() {
   el.mainGrid = document.getElementById("mainGrid");
   el.paneDivider = document.getElementById("paneDivider");
@@ -636,24 +2004,44 @@ function cacheElements() {
   el.processCount = document.getElementById("processCount");
   el.portCount = document.getElementById("portCount");
   el.lastRefresh = document.getElementById("lastRefresh");
+  el.systemCpu = document.getElementById("systemCpu");
+  el.systemMemory = document.getElementById("systemMemory");
+  el.pinnedStatus = document.getElementById("pinnedStatus");
   el.statusBar = document.getElementById("statusBar");
 
   el.processSearch = document.getElementById("processSearch");
   el.portSearch = document.getElementById("portSearch");
+  el.connectionSearch = document.getElementById("connectionSearch");
+  el.resolveHosts = document.getElementById("resolveHosts");
   el.bulkQuery = document.getElementById("bulkQuery");
+  el.bulkMatchMode = document.getElementById("bulkMatchMode");
+  el.bulkCaseSensitive = document.getElementById("bulkCaseSensitive");
+  el.bulkExclude = document.getElementById("bulkExclude");
 
   el.sortBy = document.getElementById("sortBy");
   el.treeMode = document.getElementById("treeMode");
+  el.groupByApp = document.getElementById("groupByApp");
   el.autoRefresh = document.getElementById("autoRefresh");
 
   el.refreshBtn = document.getElementById("refreshBtn");
+  el.launchBtn = document.getElementById("launchBtn");
+  el.markBtn = document.getElementById("markBtn");
   el.bulkKillBtn = document.getElementById("bulkKillBtn");
 
   el.processRows = document.getElementById("processRows");
   el.portRows = document.getElementById("portRows");
+  el.connectionRows = document.getElementById("connectionRows");
+  el.zombieRows = document.getElementById("zombieRows");
+  el.fileUserRows = document.getElementById("fileUserRows");
+  el.filePath = document.getElementById("filePath");
+  el.findFileUsersBtn = document.getElementById("findFileUsersBtn");
+  el.findMountBlockersBtn = document.getElementById("findMountBlockersBtn");
 
   el.detailsTab = document.getElementById("detailsTab");
   el.portsTab = document.getElementById("portsTab");
+  el.connectionsTab = document.getElementById("connectionsTab");
+  el.zombiesTab = document.getElementById("zombiesTab");
+  el.filesTab = document.getElementById("filesTab");
   el.detailsBody = document.getElementById("detailsBody");
 }
 
@@ -661,7 +2049,9 @@ async function init() {
   cacheElements();
   setupPaneResize();
   bindEvents();
+  subscribeToLifecycleEvents();
   syncRefreshTimer();
+  renderFileUserTable();
 
   if (!invoke) {
     setStatus("Tauri bridge missing. Launch with `cargo tauri dev` or `cargo run --manifest-path src-tauri/Cargo.toml`.", "warn");
@@ -669,6 +2059,8 @@ async function init() {
   }
 
   await refreshAll();
+  await refreshPins();
+  await handlePendingDeepLink();
 }
 
 window.addEventListener("DOMContentLoaded", init);